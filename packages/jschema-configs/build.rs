@@ -31,5 +31,6 @@ fn main() {
     );
     build_schema("deno", "DenoConfigurationFileSchema");
     build_schema("package", "JsonSchemaForNpmPackageJsonFiles");
-    build_schema("cargo", "TomlManifest")
+    build_schema("cargo", "TomlManifest");
+    build_schema("composer", "Package");
 }
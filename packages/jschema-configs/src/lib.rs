@@ -21,3 +21,11 @@ pub mod package {
 pub mod cargo {
     include!(concat!(env!("OUT_DIR"), "/cargo.rs"));
 }
+#[cfg(feature = "composer")]
+/// `composer.json` [Config](confique::Config) feature enablable, entry point at [composer `ConfigRoot`](composer::ConfigRoot)
+///
+/// Generated from `schemas/composer.json`, a hand-written subset of the official
+/// [composer schema](https://getcomposer.org/schema.json) covering package metadata
+pub mod composer {
+    include!(concat!(env!("OUT_DIR"), "/composer.rs"));
+}
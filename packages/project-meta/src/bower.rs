@@ -0,0 +1,135 @@
+//! `bower.json` reader for legacy front-end packages.
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec};
+use crate::npm::{self, SpecifierError};
+use crate::util::json_license;
+use crate::{MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn from_json(json: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    for (key, kind) in [
+        ("dependencies", DependencyKind::Runtime),
+        ("devDependencies", DependencyKind::Dev),
+    ] {
+        let group = (kind != DependencyKind::Runtime).then_some(key);
+        for (name, specifier) in json[key].as_object().into_iter().flatten() {
+            let dependency = match specifier {
+                Value::String(specifier) => parse_specifier(name, kind, specifier),
+                specifier => Err(SpecifierError::NotAString(specifier.to_string())),
+            }
+            .map_err(|err| MetadataError::DependencySpecifierError(name.clone(), err))?;
+            dependencies.push(dependency.group(group));
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let mut urls = HashMap::new();
+    if let Some(homepage) = json["homepage"].as_str() {
+        urls.insert("homepage".to_string(), homepage.to_string());
+    }
+    if let Some(repository) = json["repository"]["url"].as_str() {
+        urls.insert("repository".to_string(), repository.to_string());
+    }
+    let license = json_license(&json["license"]);
+    // Bower lists one entry file per type, such as a script and its stylesheet
+    let main = match &json["main"] {
+        Value::Array(mains) => mains.first().and_then(|main| main.as_str()),
+        main => main.as_str(),
+    };
+
+    Ok(ProjectMetadata {
+        name: json["name"].as_str().unwrap_or("").to_string(),
+        // Deprecated, bower takes versions from the git tags
+        version: json["version"].as_str().unwrap_or("").to_string(),
+        description: json["description"].as_str().map(|s| s.to_string()),
+        // Author objects link to a `homepage` rather than a `url`
+        authors: json["authors"].as_array().map(|arr| {
+            arr.iter()
+                .filter_map(|author| match author["homepage"].as_str() {
+                    Some(homepage) => Some(Person::new(
+                        author["name"].as_str(),
+                        author["email"].as_str(),
+                        Some(homepage),
+                    )),
+                    None => Person::from_json(author),
+                })
+                .collect()
+        }),
+        license,
+        keywords: json["keywords"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        urls: (!urls.is_empty()).then_some(urls),
+        exports: main.map(|main| HashMap::from([(".".to_string(), main.to_string())])),
+        exclude: json["ignore"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Parse a bower endpoint: a range, `package#range` for a registered package under another
+/// name, or a git repository, URL or `owner/repo` shorthand followed by `#` and a range over its
+/// tags or a ref
+fn parse_specifier(
+    name: &str,
+    kind: DependencyKind,
+    specifier: &str,
+) -> Result<DependencySpec, SpecifierError> {
+    let specifier = specifier.trim();
+    let dependency = DependencySpec::new(name, kind);
+    if ["./", "../", "/", "~/"]
+        .iter()
+        .any(|prefix| specifier.starts_with(prefix))
+    {
+        return Ok(dependency.source(DependencySource::Path {
+            path: specifier.to_string(),
+        }));
+    }
+    let Some((target, committish)) = specifier.split_once('#') else {
+        let is_url = specifier.starts_with("http://") || specifier.starts_with("https://");
+        if is_url && !specifier.ends_with(".git") {
+            return Ok(dependency.source(DependencySource::Url {
+                url: specifier.to_string(),
+            }));
+        }
+        if specifier.contains(['/', ':']) {
+            return Ok(dependency.source(git_source(specifier, None)));
+        }
+        return npm::parse_specifier(name, kind, specifier);
+    };
+    // A committish that is not a range is a branch or a commit
+    let (version, rev) = match npm::parse_range(committish) {
+        Ok(version) => ((!version.accepts_any()).then_some(version), None),
+        Err(_) => (None, Some(committish)),
+    };
+    let source = match target.contains(['/', ':']) {
+        true => git_source(target, rev),
+        false => DependencySource::Alias {
+            registry: None,
+            package: target.to_string(),
+            dist_tag: rev.map(|s| s.to_string()),
+        },
+    };
+    Ok(dependency.source(source).with_version(version))
+}
+
+/// A git URL, `owner/repo` standing for a GitHub repository
+fn git_source(url: &str, rev: Option<&str>) -> DependencySource {
+    let url = match url.contains(':') {
+        true => url.to_string(),
+        false => format!("github:{url}"),
+    };
+    DependencySource::Git {
+        url,
+        branch: None,
+        tag: None,
+        rev: rev.map(|s| s.to_string()),
+    }
+}
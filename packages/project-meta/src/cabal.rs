@@ -0,0 +1,394 @@
+//! Cabal package descriptions (`*.cabal`): the top-level fields, and the `build-depends` of the
+//! library, executable, test-suite and benchmark stanzas, with the `common` stanzas they
+//! `import`. Dependencies under an `if` are restricted to its condition, as their `target`.
+use crate::dependency::{
+    Comparator, DependencyKind, DependencySource, DependencySpec, Op, VersionReq,
+};
+use crate::syntax::SyntaxError;
+use crate::{Person, ProjectMetadata};
+use std::collections::HashMap;
+
+/// Sections a package description may have, `if` and `else` nesting in the others
+const SECTIONS: [&str; 12] = [
+    "library",
+    "executable",
+    "test-suite",
+    "benchmark",
+    "foreign-library",
+    "common",
+    "flag",
+    "source-repository",
+    "custom-setup",
+    "if",
+    "elif",
+    "else",
+];
+
+/// Top-level fields read into [`ProjectMetadata`] fields rather than `properties`
+const FIELDS: [&str; 14] = [
+    "name",
+    "version",
+    "synopsis",
+    "description",
+    "author",
+    "maintainer",
+    "license",
+    "license-file",
+    "license-files",
+    "homepage",
+    "bug-reports",
+    "category",
+    "extra-source-files",
+    "extra-doc-files",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Item {
+    /// Field, its name lowercased as field names are case insensitive
+    Field { name: String, value: String },
+    Section {
+        name: String,
+        args: String,
+        items: Vec<Item>,
+    },
+}
+
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+    number: usize,
+}
+
+pub(crate) fn parse(contents: &str) -> Result<ProjectMetadata, SyntaxError> {
+    let lines: Vec<Line> = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with("--"))
+        .map(|(index, line)| Line {
+            indent: line.len() - line.trim_start().len(),
+            text: line.trim(),
+            number: index + 1,
+        })
+        .collect();
+    let items = items(&lines)?;
+
+    let field = |name: &str| {
+        items.iter().find_map(|item| match item {
+            Item::Field { name: field, value } if field == name => Some(value.as_str()),
+            _ => None,
+        })
+    };
+    let list = |name: &str| {
+        field(name).map(|value| {
+            value
+                .split([',', '\n'])
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+    let package = field("name").unwrap_or("");
+
+    let mut urls = HashMap::new();
+    for (key, label) in [("homepage", "homepage"), ("bug-reports", "issues")] {
+        if let Some(url) = field(key) {
+            urls.insert(label.to_string(), url.to_string());
+        }
+    }
+    let mut common = HashMap::new();
+    let mut dependencies = Vec::new();
+    let mut scripts = HashMap::new();
+    for item in &items {
+        let Item::Section { name, args, items } = item else {
+            continue;
+        };
+        let (kind, group) = match name.as_str() {
+            "library" if args.is_empty() => (DependencyKind::Runtime, None),
+            "library" | "executable" | "foreign-library" => {
+                (DependencyKind::Runtime, Some(format!("{name} {args}")))
+            }
+            "test-suite" | "benchmark" => (DependencyKind::Dev, Some(format!("{name} {args}"))),
+            "custom-setup" => (DependencyKind::Build, Some(name.clone())),
+            "common" => {
+                common.insert(args.clone(), items);
+                continue;
+            }
+            "source-repository" => {
+                let location = items.iter().find_map(|item| match item {
+                    Item::Field { name, value } if name == "location" => Some(value),
+                    _ => None,
+                });
+                if let Some(location) = location.filter(|_| args == "head") {
+                    urls.insert("repository".to_string(), location.clone());
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if name == "executable" {
+            let main = items.iter().find_map(|item| match item {
+                Item::Field { name, value } if name == "main-is" => Some(value),
+                _ => None,
+            });
+            if let Some(main) = main {
+                scripts.insert(args.clone(), main.clone());
+            }
+        }
+        let mut stanza = Vec::new();
+        build_depends(items, &common, &mut Vec::new(), &mut stanza);
+        for (dependency, target) in stanza {
+            // Executables and tests depend on the library of their own package
+            let dependency = match dependency.name == package {
+                true => dependency.source(DependencySource::Workspace),
+                false => dependency,
+            };
+            let dependency = dependency
+                .kind(kind)
+                .group(group.as_deref())
+                .target(target.as_deref());
+            if !dependencies.contains(&dependency) {
+                dependencies.push(dependency);
+            }
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let properties: HashMap<String, String> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Field { name, value } if !FIELDS.contains(&name.as_str()) => {
+                Some((name.clone(), value.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    let people = |name: &str| {
+        list(name).map(|people| people.iter().map(|person| Person::parse(person)).collect())
+    };
+
+    Ok(ProjectMetadata {
+        name: package.to_string(),
+        version: field("version").unwrap_or("").to_string(),
+        description: field("synopsis").map(|s| s.to_string()),
+        authors: people("author"),
+        maintainers: people("maintainer"),
+        license: field("license").map(|s| s.to_string()),
+        license_file: list("license-files")
+            .or_else(|| list("license-file"))
+            .and_then(|files| files.into_iter().next()),
+        keywords: list("category"),
+        urls: (!urls.is_empty()).then_some(urls),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        scripts: (!scripts.is_empty()).then_some(scripts),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    })
+}
+
+/// Read the fields and sections of `lines`, each item taking the lines indented below it
+fn items(lines: &[Line]) -> Result<Vec<Item>, SyntaxError> {
+    let mut items = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = &lines[index];
+        let end = lines[index + 1..]
+            .iter()
+            .position(|next| next.indent <= line.indent)
+            .map_or(lines.len(), |offset| index + 1 + offset);
+        let body = &lines[index + 1..end];
+        let field_name = line
+            .text
+            .split_once(':')
+            .map(|(name, value)| (name.trim_end(), value.trim()))
+            .filter(|(name, _)| {
+                !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            });
+        if let Some((name, value)) = field_name {
+            // A line holding only `.` is a blank line of the value
+            let mut value = value.to_string();
+            for continuation in body {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                if continuation.text != "." {
+                    value.push_str(continuation.text);
+                }
+            }
+            items.push(Item::Field {
+                name: name.to_ascii_lowercase(),
+                value,
+            });
+        } else {
+            let (name, args) = line
+                .text
+                .split_once(char::is_whitespace)
+                .unwrap_or((line.text, ""));
+            let name = name.to_ascii_lowercase();
+            if !SECTIONS.contains(&name.as_str()) {
+                return Err(SyntaxError::new(
+                    line.number,
+                    format!(
+                        "expected `field: value` or a section, found `{}`",
+                        line.text
+                    ),
+                ));
+            }
+            if line.text.contains('{') {
+                return Err(SyntaxError::new(
+                    line.number,
+                    "sections delimited by braces are not supported",
+                ));
+            }
+            items.push(Item::Section {
+                name,
+                args: args.trim().to_string(),
+                items: self::items(body)?,
+            });
+        }
+        index = end;
+    }
+    Ok(items)
+}
+
+/// Collect the `build-depends` of a stanza, with the conditions they are under
+fn build_depends(
+    items: &[Item],
+    common: &HashMap<String, &Vec<Item>>,
+    conditions: &mut Vec<String>,
+    dependencies: &mut Vec<(DependencySpec, Option<String>)>,
+) {
+    let mut previous_condition = None;
+    for item in items {
+        match item {
+            Item::Field { name, value } if name == "build-depends" => {
+                let target = (!conditions.is_empty()).then(|| conditions.join(" && "));
+                for dependency in value.split([',', '\n']).map(str::trim) {
+                    if !dependency.is_empty() {
+                        dependencies.push((dependency_spec(dependency), target.clone()));
+                    }
+                }
+            }
+            Item::Field { name, value } if name == "import" => {
+                for import in value.split(',').map(str::trim) {
+                    if let Some(items) = common.get(import) {
+                        build_depends(items, common, conditions, dependencies);
+                    }
+                }
+            }
+            Item::Section { name, args, items }
+                if name == "if" || name == "elif" || name == "else" =>
+            {
+                let condition = match name.as_str() {
+                    "else" => match &previous_condition {
+                        Some(previous) => format!("!({previous})"),
+                        None => continue,
+                    },
+                    _ => args.clone(),
+                };
+                conditions.push(condition.clone());
+                build_depends(items, common, conditions, dependencies);
+                conditions.pop();
+                previous_condition = Some(condition);
+                continue;
+            }
+            _ => {}
+        }
+        previous_condition = None;
+    }
+}
+
+/// `name`, `name >=1.2 && <1.3`, `name ^>=1.2` or `name:{lib1, lib2}` with a constraint
+fn dependency_spec(dependency: &str) -> DependencySpec {
+    let name_end = dependency
+        .find(|c: char| c.is_whitespace() || "<>=^:(".contains(c))
+        .unwrap_or(dependency.len());
+    let name = &dependency[..name_end];
+    let mut rest = dependency[name_end..].trim();
+    // Sublibraries of the package
+    let mut features = None;
+    if let Some(libraries) = rest.strip_prefix(':') {
+        let libraries = libraries.trim();
+        let (list, after) = match libraries.strip_prefix('{') {
+            Some(braced) => braced.split_once('}').unwrap_or((braced, "")),
+            None => libraries
+                .split_once(char::is_whitespace)
+                .unwrap_or((libraries, "")),
+        };
+        features = Some(
+            list.split(',')
+                .map(|library| library.trim().to_string())
+                .filter(|library| !library.is_empty())
+                .collect(),
+        );
+        rest = after.trim();
+    }
+    DependencySpec::new(name, DependencyKind::Runtime)
+        .with_version((!rest.is_empty()).then(|| version_range(rest)))
+        .features(features)
+}
+
+/// Parse a Cabal version range: `||` separates alternatives of `&&` joined constraints, `^>=1.2`
+/// accepts `1.2` up to the next major version, `1.2.*` any version it is a prefix of, and
+/// `-any` any version
+fn version_range(raw: &str) -> VersionReq {
+    let any_of = raw
+        .split("||")
+        .map(|alternative| {
+            alternative
+                .split("&&")
+                .flat_map(|constraint| {
+                    let constraint = constraint
+                        .trim()
+                        .trim_start_matches('(')
+                        .trim_end_matches(')');
+                    let constraint = constraint.trim();
+                    if constraint == "-any" {
+                        return Vec::new();
+                    }
+                    // The major version is made of the first two components
+                    if let Some(version) = constraint.strip_prefix("^>=") {
+                        let version = version.trim();
+                        return vec![
+                            Comparator::new(Op::GreaterEq, version.to_string()),
+                            Comparator::new(Op::Less, bump(version, 2)),
+                        ];
+                    }
+                    let (op, version) = Op::split(constraint).unwrap_or((Op::Exact, constraint));
+                    match (op, version.strip_suffix(".*")) {
+                        (Op::Exact, Some(prefix)) => {
+                            let components = prefix.split('.').count();
+                            vec![
+                                Comparator::new(Op::GreaterEq, prefix.to_string()),
+                                Comparator::new(Op::Less, bump(prefix, components)),
+                            ]
+                        }
+                        _ => vec![Comparator::new(op, version.to_string())],
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    VersionReq::new(raw, any_of)
+}
+
+/// Increment the component at `components - 1`, dropping the ones after it: `bump("1.2.3", 2)`
+/// gives `1.3`
+fn bump(version: &str, components: usize) -> String {
+    let mut parts: Vec<u64> = version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    parts.resize(components.max(1), 0);
+    if let Some(last) = parts.last_mut() {
+        *last += 1;
+    }
+    parts
+        .iter()
+        .map(|part| part.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,303 @@
+//! `Cargo.toml` reader, resolving `workspace = true` inheritance against the workspace root.
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec, VersionSyntax};
+use crate::util::{located, string_array};
+use crate::{FieldValue, MetadataError, Person, ProjectMetadata};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Read `toml`, the manifest at `manifest_path`; the workspace root it inherits from is located
+/// from that path
+pub(crate) fn from_toml(
+    toml: &toml::Value,
+    manifest_path: Option<&Path>,
+) -> Result<ProjectMetadata, MetadataError> {
+    let root_manifest;
+    let workspace = match (toml.get("workspace"), manifest_path) {
+        (Some(workspace), _) => Some(workspace),
+        (None, Some(manifest_path)) if inherits(toml) => {
+            root_manifest = workspace_root(toml, manifest_path)?;
+            root_manifest.get("workspace")
+        }
+        (None, None) if inherits(toml) => {
+            return Err(MetadataError::WorkspaceInheritanceError(
+                "the manifest inherits from a workspace, which is only located from its path"
+                    .to_string(),
+            ))
+        }
+        (None, _) => None,
+    };
+    let manifest = Manifest {
+        workspace,
+        path: manifest_path,
+    };
+
+    // Virtual manifests only carry what `[workspace.package]` shares with the members
+    let (package, package_table) = match toml.get("package") {
+        Some(package) => (Some(package), "package"),
+        None => (
+            workspace.and_then(|workspace| workspace.get("package")),
+            "workspace.package",
+        ),
+    };
+    let mut sources = HashMap::new();
+    let mut field = |key: &str| -> Result<Option<&toml::Value>, MetadataError> {
+        let Some(value) = package.and_then(|package| package.get(key)) else {
+            return Ok(None);
+        };
+        let (value, source) = if is_inherited(value) {
+            let inherited = workspace
+                .and_then(|workspace| workspace.get("package")?.get(key))
+                .ok_or_else(|| manifest.missing(&format!("workspace.package.{key}")))?;
+            (inherited, "workspace.package")
+        } else {
+            (value, package_table)
+        };
+        let field = match key {
+            "rust-version" => "engines".to_string(),
+            "homepage" | "repository" | "documentation" => "urls".to_string(),
+            key => key.replace('-', "_"),
+        };
+        sources.insert(field, FieldValue::from_table(source));
+        Ok(Some(value))
+    };
+
+    let name = field("name")?
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let version = field("version")?
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let description = field("description")?
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let authors = field("authors")?
+        .and_then(string_array)
+        .map(|authors| authors.iter().map(|author| Person::parse(author)).collect());
+    let license = field("license")?
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let license_file = field("license-file")?
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    // `readme = true` stands for the default `README.md`, `false` disables it
+    let readme = field("readme")?.and_then(|v| match v {
+        toml::Value::Boolean(true) => Some("README.md".to_string()),
+        v => v.as_str().map(|s| s.to_string()),
+    });
+    let keywords = field("keywords")?.and_then(string_array);
+    let mut urls = HashMap::new();
+    for key in ["homepage", "repository", "documentation"] {
+        if let Some(url) = field(key)?.and_then(|v| v.as_str()) {
+            urls.insert(key.to_string(), url.to_string());
+        }
+    }
+    let engines = field("rust-version")?
+        .and_then(|v| v.as_str())
+        .map(|spec| HashMap::from([("rust".to_string(), spec.to_string())]));
+
+    let mut dependencies = match toml.get("package") {
+        Some(_) => {
+            let mut dependencies = manifest.dependencies(toml, None)?;
+            for (target, table) in toml
+                .get("target")
+                .and_then(|v| v.as_table())
+                .into_iter()
+                .flatten()
+            {
+                dependencies.extend(manifest.dependencies(table, Some(target))?);
+            }
+            dependencies
+        }
+        None => workspace
+            .and_then(|workspace| workspace.get("dependencies"))
+            .and_then(|v| v.as_table())
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| dependency(name, value, DependencyKind::Runtime))
+            .collect(),
+    };
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        name,
+        version,
+        description,
+        authors,
+        license,
+        license_file,
+        readme,
+        keywords,
+        urls: (!urls.is_empty()).then_some(urls),
+        engines,
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        sources: (!sources.is_empty()).then_some(sources),
+        scripts: None, // Cargo.toml does not have a scripts field
+        ..Default::default()
+    })
+}
+
+struct Manifest<'a> {
+    workspace: Option<&'a toml::Value>,
+    path: Option<&'a Path>,
+}
+
+impl Manifest<'_> {
+    /// Read the `[dependencies]`-like tables of `table`, also accepting the legacy
+    /// `dev_dependencies` spelling, and substitute `{ workspace = true }` entries with
+    /// `[workspace.dependencies]`
+    fn dependencies(
+        &self,
+        table: &toml::Value,
+        target: Option<&str>,
+    ) -> Result<Vec<DependencySpec>, MetadataError> {
+        let mut dependencies = Vec::new();
+        for (key, kind) in [
+            ("dependencies", DependencyKind::Runtime),
+            ("dev-dependencies", DependencyKind::Dev),
+            ("build-dependencies", DependencyKind::Build),
+        ] {
+            let Some(deps) = table
+                .get(key)
+                .or_else(|| table.get(key.replace('-', "_").as_str()))
+                .and_then(|v| v.as_table())
+            else {
+                continue;
+            };
+            for (name, value) in deps {
+                let dependency = if is_inherited(value) {
+                    let inherited = self
+                        .workspace
+                        .and_then(|workspace| workspace.get("dependencies")?.get(name))
+                        .ok_or_else(|| self.missing(&format!("workspace.dependencies.{name}")))?;
+                    // Members may only add features and make the dependency optional
+                    let member = dependency(name, value, kind);
+                    let mut dependency = dependency(name, inherited, kind);
+                    if let Some(features) = member.features {
+                        dependency
+                            .features
+                            .get_or_insert_with(Vec::new)
+                            .extend(features);
+                    }
+                    if member.kind == DependencyKind::Optional {
+                        dependency.kind = DependencyKind::Optional;
+                    }
+                    dependency
+                } else {
+                    dependency(name, value, kind)
+                };
+                dependencies.push(dependency.target(target));
+            }
+        }
+        Ok(dependencies)
+    }
+
+    fn missing(&self, key: &str) -> MetadataError {
+        MetadataError::WorkspaceInheritanceError(located(
+            self.path,
+            format!("`{key}` is not defined in the workspace root"),
+        ))
+    }
+}
+
+/// Read the `"1.0"` shorthand or the `{ version = "1.0", git = ..., features = [...] }` table;
+/// optional dependencies are of the `Optional` kind whatever table declares them
+fn dependency(name: &str, value: &toml::Value, kind: DependencyKind) -> DependencySpec {
+    let dependency = DependencySpec::new(name, kind);
+    if let Some(version) = value.as_str() {
+        return dependency.version(Some(version), VersionSyntax::Cargo);
+    }
+    let get = |key: &str| value.get(key).and_then(|v| v.as_str());
+    let owned = |key: &str| get(key).map(|s| s.to_string());
+    let source = if let Some(url) = get("git") {
+        DependencySource::Git {
+            url: url.to_string(),
+            branch: owned("branch"),
+            tag: owned("tag"),
+            rev: owned("rev"),
+        }
+    } else if let Some(path) = get("path") {
+        DependencySource::Path {
+            path: path.to_string(),
+        }
+    } else {
+        DependencySource::Registry {
+            registry: owned("registry"),
+            dist_tag: None,
+        }
+    };
+    let kind = match value.get("optional").and_then(|v| v.as_bool()) {
+        Some(true) => DependencyKind::Optional,
+        _ => kind,
+    };
+    dependency
+        .kind(kind)
+        .version(get("version"), VersionSyntax::Cargo)
+        .source(source)
+        .package(get("package"))
+        .features(value.get("features").and_then(string_array))
+        .default_features(
+            value
+                .get("default-features")
+                .or_else(|| value.get("default_features"))
+                .and_then(|v| v.as_bool()),
+        )
+}
+
+fn is_inherited(value: &toml::Value) -> bool {
+    value.get("workspace").and_then(|v| v.as_bool()) == Some(true)
+}
+
+/// Whether any package field or dependency is inherited from the workspace
+fn inherits(toml: &toml::Value) -> bool {
+    let tables = |toml: &toml::Value| {
+        ["dependencies", "dev-dependencies", "build-dependencies"]
+            .into_iter()
+            .filter_map(|kind| toml.get(kind)?.as_table())
+            .flat_map(|table| table.values())
+            .any(is_inherited)
+    };
+    let package = toml.get("package").and_then(|v| v.as_table());
+    package.is_some_and(|package| package.values().any(is_inherited))
+        || tables(toml)
+        || toml
+            .get("target")
+            .and_then(|v| v.as_table())
+            .is_some_and(|targets| targets.values().any(tables))
+}
+
+/// Locate the workspace root like cargo does: `package.workspace` when set, otherwise the
+/// closest ancestor manifest with a `[workspace]` table
+fn workspace_root(toml: &toml::Value, manifest_path: &Path) -> Result<toml::Value, MetadataError> {
+    let dir = manifest_path.parent().unwrap_or(Path::new(""));
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    if let Some(root) = toml
+        .get("package")
+        .and_then(|package| package.get("workspace"))
+        .and_then(|v| v.as_str())
+    {
+        return read_manifest(&dir.join(root).join("Cargo.toml"));
+    }
+    for ancestor in dir.ancestors().skip(1) {
+        let candidate = ancestor.join("Cargo.toml");
+        if candidate.is_file() {
+            let root = read_manifest(&candidate)?;
+            if root.get("workspace").is_some() {
+                return Ok(root);
+            }
+        }
+    }
+    Err(MetadataError::WorkspaceInheritanceError(format!(
+        "{}: no workspace root found",
+        manifest_path.display()
+    )))
+}
+
+fn read_manifest(path: &Path) -> Result<toml::Value, MetadataError> {
+    let path = path.display().to_string();
+    let contents =
+        fs::read_to_string(&path).map_err(|_| MetadataError::ReadError(path.to_string()))?;
+    toml::from_str(&contents).map_err(|_| MetadataError::TomlParseError(path))
+}
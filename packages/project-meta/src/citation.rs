@@ -0,0 +1,52 @@
+//! `CITATION.cff` reader, the Citation File Format research software is cited with.
+use crate::util::json_license;
+use crate::{yaml, MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn from_yaml(yaml: &Value, contents: &str) -> Result<ProjectMetadata, MetadataError> {
+    let mut urls = HashMap::new();
+    for (key, label) in [("url", "homepage"), ("repository-code", "repository")] {
+        if let Some(url) = yaml[key].as_str() {
+            urls.insert(label.to_string(), url.to_string());
+        }
+    }
+    let license = json_license(&yaml["license"]);
+
+    Ok(ProjectMetadata {
+        name: yaml["title"].as_str().unwrap_or("").to_string(),
+        version: yaml::top_level_string(yaml, contents, "version").unwrap_or_default(),
+        description: yaml["abstract"].as_str().map(|s| s.trim().to_string()),
+        authors: yaml["authors"]
+            .as_array()
+            .map(|arr| arr.iter().map(person).collect()),
+        license,
+        keywords: yaml["keywords"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        urls: (!urls.is_empty()).then_some(urls),
+        ..Default::default()
+    })
+}
+
+/// A person, whose name is split into its parts, or an entity such as a team or institution
+fn person(author: &Value) -> Person {
+    let name = match author["name"].as_str() {
+        Some(entity) => entity.to_string(),
+        None => [
+            "given-names",
+            "name-particle",
+            "family-names",
+            "name-suffix",
+        ]
+        .iter()
+        .filter_map(|part| author[*part].as_str())
+        .collect::<Vec<_>>()
+        .join(" "),
+    };
+    // The ORCID identifies the author better than their website
+    let url = author["orcid"].as_str().or(author["website"].as_str());
+    Person::new(Some(&name), author["email"].as_str(), url)
+}
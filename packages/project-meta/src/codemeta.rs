@@ -0,0 +1,86 @@
+//! `codemeta.json` reader, the CodeMeta JSON-LD description of research software.
+use crate::util::any_license;
+use crate::{MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Prefix of the SPDX license URLs CodeMeta identifies licenses with
+const SPDX_URL: &str = "spdx.org/licenses/";
+
+pub(crate) fn from_json(json: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut urls = HashMap::new();
+    for (key, label) in [
+        ("url", "homepage"),
+        ("codeRepository", "repository"),
+        ("issueTracker", "issues"),
+    ] {
+        if let Some(url) = json[key].as_str() {
+            urls.insert(label.to_string(), url.to_string());
+        }
+    }
+    let licenses: Vec<String> = one_or_many(&json["license"])
+        .filter_map(|license| license.as_str())
+        .map(|license| match license.find(SPDX_URL) {
+            Some(start) => license[start + SPDX_URL.len()..].to_string(),
+            None => license.to_string(),
+        })
+        .collect();
+    let people = |key: &str| {
+        let people: Vec<Person> = one_or_many(&json[key]).map(person).collect();
+        (!people.is_empty()).then_some(people)
+    };
+
+    Ok(ProjectMetadata {
+        name: json["name"].as_str().unwrap_or("").to_string(),
+        version: json["version"]
+            .as_str()
+            .or(json["softwareVersion"].as_str())
+            .unwrap_or("")
+            .to_string(),
+        description: json["description"].as_str().map(|s| s.to_string()),
+        authors: people("author"),
+        maintainers: people("maintainer"),
+        contributors: people("contributor"),
+        license: any_license(&licenses),
+        keywords: match &json["keywords"] {
+            Value::String(keywords) => Some(
+                keywords
+                    .split(',')
+                    .map(|keyword| keyword.trim().to_string())
+                    .collect(),
+            ),
+            keywords => keywords.as_array().map(|arr| {
+                arr.iter()
+                    .map(|v| v.as_str().unwrap_or("").to_string())
+                    .collect()
+            }),
+        },
+        urls: (!urls.is_empty()).then_some(urls),
+        ..Default::default()
+    })
+}
+
+/// JSON-LD properties hold a single value or an array of them
+fn one_or_many(value: &Value) -> impl Iterator<Item = &Value> {
+    match value {
+        Value::Array(values) => values.iter().collect::<Vec<_>>(),
+        Value::Null => Vec::new(),
+        value => vec![value],
+    }
+    .into_iter()
+}
+
+/// A `Person` with its given and family names, or an `Organization`
+fn person(person: &Value) -> Person {
+    let name = match person["name"].as_str() {
+        Some(name) => name.to_string(),
+        None => ["givenName", "familyName"]
+            .iter()
+            .filter_map(|part| person[*part].as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    // People are identified by their ORCID as `@id`
+    let url = person["@id"].as_str().or(person["url"].as_str());
+    Person::new(Some(&name), person["email"].as_str(), url)
+}
@@ -0,0 +1,79 @@
+//! `composer.json` reader for PHP packages.
+use crate::dependency::{DependencyKind, DependencySpec, VersionSyntax};
+use crate::npm::SpecifierError;
+use crate::util::json_license;
+use crate::{MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn from_json(json: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    let mut engines = HashMap::new();
+    for (key, kind) in [
+        ("require", DependencyKind::Runtime),
+        ("require-dev", DependencyKind::Dev),
+    ] {
+        for (name, constraint) in json[key].as_object().into_iter().flatten() {
+            let constraint = constraint.as_str().ok_or_else(|| {
+                MetadataError::DependencySpecifierError(
+                    name.clone(),
+                    SpecifierError::NotAString(constraint.to_string()),
+                )
+            })?;
+            // The interpreter is a platform package, unlike `ext-*` and `lib-*` ones which are
+            // installed separately
+            if name == "php" && kind == DependencyKind::Runtime {
+                engines.insert("php".to_string(), constraint.to_string());
+                continue;
+            }
+            dependencies.push(
+                DependencySpec::new(name, kind).version(Some(constraint), VersionSyntax::Composer),
+            );
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        name: json["name"].as_str().unwrap_or("").to_string(),
+        version: json["version"].as_str().unwrap_or("").to_string(),
+        description: json["description"].as_str().map(|s| s.to_string()),
+        authors: json["authors"].as_array().map(|authors| {
+            authors
+                .iter()
+                .map(|author| {
+                    Person::new(
+                        author["name"].as_str(),
+                        author["email"].as_str(),
+                        author["homepage"].as_str(),
+                    )
+                })
+                .collect()
+        }),
+        license: json_license(&json["license"]),
+        keywords: json["keywords"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        engines: (!engines.is_empty()).then_some(engines),
+        // Commands of a script run in order, stopping at the first failure
+        scripts: json["scripts"].as_object().map(|scripts| {
+            scripts
+                .iter()
+                .map(|(name, script)| {
+                    let command = match script {
+                        Value::Array(commands) => commands
+                            .iter()
+                            .filter_map(|command| command.as_str())
+                            .collect::<Vec<_>>()
+                            .join(" && "),
+                        script => script.as_str().unwrap_or("").to_string(),
+                    };
+                    (name.clone(), command)
+                })
+                .collect()
+        }),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,257 @@
+//! conda `environment.yml` files, and the match specs conda and pixi select packages with.
+//!
+//! A match spec is `[channel[/subdir]::]name [version [build]]`, also written
+//! `name=version=build` or `name[version='...', build='...']`. The channel is kept as the
+//! registry of the package and the subdirectory as its target; build strings have no place in
+//! the dependency model, so they stay in the raw requirement next to the version.
+use crate::dependency::{
+    Comparator, DependencyKind, DependencySource, DependencySpec, Op, VersionReq,
+};
+use crate::pyproject::parse_requirement;
+use crate::{MetadataError, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn from_yaml(yaml: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    let mut engines = HashMap::new();
+    for dependency in yaml["dependencies"].as_array().into_iter().flatten() {
+        match dependency {
+            Value::String(spec) => {
+                let dependency = match_spec(spec);
+                // The interpreter is a package like any other for conda
+                if dependency.name == "python" {
+                    let version = spec.rsplit("::").next().unwrap_or(spec);
+                    let version = version.trim().trim_start_matches("python").trim();
+                    if !version.is_empty() {
+                        engines.insert("python".to_string(), version.to_string());
+                    }
+                    continue;
+                }
+                dependencies.push(dependency);
+            }
+            // `- pip: [...]` installs PyPI requirements into the environment
+            Value::Object(section) => {
+                for requirement in section
+                    .get("pip")
+                    .and_then(|pip| pip.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|requirement| requirement.as_str())
+                    // `-e .`, `-r requirements.txt` and other pip options
+                    .filter(|requirement| !requirement.starts_with('-'))
+                {
+                    dependencies.push(pypi(pip_requirement(requirement)));
+                }
+            }
+            _ => {}
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let channels: Vec<&str> = yaml["channels"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|channel| channel.as_str())
+        .collect();
+    let properties = (!channels.is_empty())
+        .then(|| HashMap::from([("channels".to_string(), channels.join(", "))]));
+
+    Ok(ProjectMetadata {
+        name: yaml["name"].as_str().unwrap_or("").to_string(),
+        engines: (!engines.is_empty()).then_some(engines),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties,
+        ..Default::default()
+    })
+}
+
+/// Parse a pip requirement, which may also be a bare VCS or archive URL such as
+/// `git+https://github.com/org/repo.git@v1.0`, named by its `#egg=` fragment or else after the
+/// repository or the archive
+fn pip_requirement(requirement: &str) -> DependencySpec {
+    let scheme_end = requirement.find("://").unwrap_or(0);
+    let is_url = scheme_end > 0
+        && requirement[..scheme_end]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !is_url {
+        return parse_requirement(requirement);
+    }
+    // `git+https://`, `hg+https://` and the other version control schemes
+    let is_vcs = requirement[..scheme_end].contains('+');
+    let (url, fragment) = match requirement.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (requirement, None),
+    };
+    let egg = fragment
+        .into_iter()
+        .flat_map(|fragment| fragment.split('&'))
+        .find_map(|parameter| parameter.strip_prefix("egg="));
+    let name = egg.unwrap_or_else(|| {
+        let file = url.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+        match is_vcs {
+            // A revision follows the repository
+            true => file
+                .split('@')
+                .next()
+                .unwrap_or(file)
+                .trim_end_matches(".git"),
+            // Archives are named `name-version.tar.gz`, `name-version-tags.whl`
+            false => file.split('-').next().unwrap_or(file),
+        }
+    });
+    parse_requirement(&format!("{name} @ {url}"))
+}
+
+/// Mark a PEP 508 requirement as installed from PyPI rather than from a conda channel
+pub(crate) fn pypi(dependency: DependencySpec) -> DependencySpec {
+    match dependency.source {
+        DependencySource::Registry { .. } => dependency.source(DependencySource::Registry {
+            registry: Some("pypi".to_string()),
+            dist_tag: None,
+        }),
+        _ => dependency,
+    }
+}
+
+/// Parse a match spec into a runtime dependency
+pub(crate) fn match_spec(spec: &str) -> DependencySpec {
+    let spec = spec.trim();
+    let (channel, subdir, spec) = match spec.split_once("::") {
+        Some((channel, spec)) => match channel.split_once('/') {
+            Some((channel, subdir)) => (Some(channel), Some(subdir), spec),
+            None => (Some(channel), None, spec),
+        },
+        None => (None, None, spec),
+    };
+    let name_end = spec
+        .find(|c: char| c.is_whitespace() || "=<>!~[".contains(c))
+        .unwrap_or(spec.len());
+    let (name, rest) = spec.split_at(name_end);
+    let mut rest = rest.trim();
+
+    // `name[version='>=1.0', build='py*']`
+    let mut bracketed = HashMap::new();
+    if let Some(inner) = rest.strip_prefix('[') {
+        let inner = inner.trim_end().trim_end_matches(']');
+        for pair in inner.split(',') {
+            if let Some((key, value)) = pair.split_once('=') {
+                let value = value.trim().trim_matches(['\'', '"']);
+                bracketed.insert(key.trim(), value);
+            }
+        }
+        rest = "";
+    }
+    let (version, build) = match (bracketed.get("version"), bracketed.get("build")) {
+        (None, None) => split_version(rest),
+        (version, build) => (version.copied(), build.copied()),
+    };
+    let channel = bracketed.get("channel").copied().or(channel);
+
+    let raw = match (version, build) {
+        (Some(version), Some(build)) => Some(format!("{version} {build}")),
+        (Some(version), None) => Some(version.to_string()),
+        (None, Some(build)) => Some(format!("* {build}")),
+        (None, None) => None,
+    };
+    let version = version
+        .map(|version| conda_version(version).with_raw(raw.as_deref().unwrap_or(version)))
+        .filter(|version| !(version.accepts_any() && build.is_none()));
+    DependencySpec::new(name, DependencyKind::Runtime)
+        .with_version(version)
+        .source(DependencySource::Registry {
+            registry: channel.map(|channel| channel.to_string()),
+            dist_tag: None,
+        })
+        .target(subdir)
+}
+
+/// Split `1.2.* py39_0`, `=1.2=py39_0` and `==1.2=py39_0` into the version and the build string
+fn split_version(rest: &str) -> (Option<&str>, Option<&str>) {
+    if rest.is_empty() {
+        return (None, None);
+    }
+    // The build string is the last word, unless it continues the version
+    if let Some((version, build)) = rest.rsplit_once(char::is_whitespace) {
+        let version = version.trim_end();
+        let continues = build.starts_with(['=', '<', '>', '!', '~', '|', ','])
+            || version.ends_with(['=', '<', '>', '!', '~', '|', ',']);
+        if !continues {
+            return (Some(version), Some(build));
+        }
+        return (Some(rest), None);
+    }
+    // Only a single version may be followed by `=build`
+    if rest.contains([',', '|', '<', '>', '!', '~']) {
+        return (Some(rest), None);
+    }
+    // A `=` after the first operator separates the build string
+    let operator_end = rest
+        .find(|c: char| !"=<>!~".contains(c))
+        .unwrap_or(rest.len());
+    match rest[operator_end..].split_once('=') {
+        // With a build string, `=1.2` is the exact version rather than a prefix
+        Some((version, build)) => {
+            let version = match &rest[..operator_end] {
+                "=" => &rest[1..operator_end + version.len()],
+                _ => &rest[..operator_end + version.len()],
+            };
+            (Some(version), Some(build))
+        }
+        None => (Some(rest), None),
+    }
+}
+
+/// Parse a conda version: `|` separates alternatives of `,` joined constraints, `=1.2` and
+/// `1.2.*` accept the versions starting with `1.2`, and a bare version is exact
+pub(crate) fn conda_version(raw: &str) -> VersionReq {
+    let any_of = raw
+        .split('|')
+        .map(|alternative| {
+            alternative
+                .split(',')
+                .flat_map(|constraint| {
+                    let constraint = constraint.trim();
+                    let prefix = if constraint.starts_with("==") || constraint.starts_with("~=") {
+                        None
+                    } else if let Some(prefix) = constraint.strip_prefix('=') {
+                        Some(prefix.trim_end_matches(['*', '.']))
+                    } else if constraint.ends_with('*') && !constraint.starts_with(['<', '>', '!'])
+                    {
+                        Some(constraint.trim_end_matches(['*', '.']))
+                    } else {
+                        None
+                    };
+                    match prefix {
+                        Some("") => Vec::new(),
+                        Some(prefix) => vec![
+                            Comparator::new(Op::GreaterEq, prefix.to_string()),
+                            Comparator::new(Op::Less, next_prefix(prefix)),
+                        ],
+                        None if constraint.is_empty() => Vec::new(),
+                        None => {
+                            let (op, version) =
+                                Op::split(constraint).unwrap_or((Op::Exact, constraint));
+                            vec![Comparator::new(op, version.to_string())]
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    VersionReq::new(raw, any_of)
+}
+
+/// Smallest version above all the versions starting with `prefix`: `1.2` gives `1.3`
+fn next_prefix(prefix: &str) -> String {
+    let mut parts: Vec<String> = prefix.split('.').map(|part| part.to_string()).collect();
+    if let Some(last) = parts.last_mut() {
+        *last = match last.parse::<u64>() {
+            Ok(number) => (number + 1).to_string(),
+            Err(_) => format!("{last}.1"),
+        };
+    }
+    parts.join(".")
+}
@@ -0,0 +1,47 @@
+//! Debian control files, the format of R's `DESCRIPTION`: `Field: value` lines, values continued
+//! on lines starting with whitespace.
+use crate::syntax::SyntaxError;
+
+/// A field, with the line it starts on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Field {
+    pub(crate) name: String,
+    /// Value with its continuation lines, joined by newlines
+    pub(crate) value: String,
+    pub(crate) line: usize,
+}
+
+/// Fields of the first paragraph, the only one a `DESCRIPTION` has
+pub(crate) fn parse(contents: &str) -> Result<Vec<Field>, SyntaxError> {
+    let mut fields: Vec<Field> = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let error = |message: &str| SyntaxError::new(index + 1, message);
+        if line.trim().is_empty() {
+            if fields.is_empty() {
+                continue;
+            }
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            let field = fields
+                .last_mut()
+                .ok_or_else(|| error("continuation line without a field"))?;
+            field.value.push('\n');
+            field.value.push_str(line.trim());
+            continue;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .filter(|(name, _)| !name.is_empty() && !name.contains(char::is_whitespace))
+            .ok_or_else(|| error("expected `Field: value`"))?;
+        if fields.iter().any(|field| field.name == name) {
+            return Err(error(&format!("duplicate field `{name}`")));
+        }
+        fields.push(Field {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            line: index + 1,
+        });
+    }
+    Ok(fields)
+}
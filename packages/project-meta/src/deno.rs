@@ -0,0 +1,142 @@
+//! `deno.json`, `deno.jsonc` and `jsr.json` reader, covering the tasks, the import map and the
+//! fields JSR publishes the package with.
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec};
+use crate::npm::{self, SpecifierError};
+use crate::{MetadataError, ProjectMetadata, Task};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+pub(crate) fn from_json(json: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = match json["imports"].as_object() {
+        Some(imports) => import_map(imports, None)?,
+        None => Vec::new(),
+    };
+    // Scopes remap specifiers for the modules under a prefix
+    for (scope, imports) in json["scopes"].as_object().into_iter().flatten() {
+        if let Some(imports) = imports.as_object() {
+            dependencies.extend(import_map(imports, Some(scope))?);
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let tasks: Option<HashMap<String, Task>> = json["tasks"].as_object().map(|tasks| {
+        tasks
+            .iter()
+            .map(|(name, task)| {
+                let task = match task {
+                    Value::String(command) => Task {
+                        command: Some(command.clone()),
+                        ..Default::default()
+                    },
+                    task => Task {
+                        command: task["command"].as_str().map(|s| s.to_string()),
+                        dependencies: task["dependencies"].as_array().map(|arr| strings(arr)),
+                        description: task["description"].as_str().map(|s| s.to_string()),
+                    },
+                };
+                (name.clone(), task)
+            })
+            .collect()
+    });
+    // Tasks only running their dependencies have no command to compare with other scripts
+    let scripts = tasks.as_ref().map(|tasks| {
+        tasks
+            .iter()
+            .filter_map(|(name, task)| Some((name.clone(), task.command.clone()?)))
+            .collect()
+    });
+
+    Ok(ProjectMetadata {
+        name: json["name"].as_str().unwrap_or("").to_string(),
+        version: json["version"].as_str().unwrap_or("").to_string(),
+        description: json["description"].as_str().map(|s| s.to_string()),
+        authors: None, // deno.json does not have an authors field
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        scripts,
+        tasks,
+        license: json["license"].as_str().map(|s| s.to_string()),
+        // FIXME https://github.com/denoland/deno/blob/1d49b3cb0f54eb8184acc00ec4bb3bd519653441/cli/schemas/config-file.v1.json#L4
+        keywords: json["keywords"].as_array().map(|arr| strings(arr)),
+        // A single module stands for the `.` entry point
+        exports: match &json["exports"] {
+            Value::String(module) => Some(HashMap::from([(".".to_string(), module.clone())])),
+            Value::Object(exports) => Some(
+                exports
+                    .iter()
+                    .map(|(entry, module)| {
+                        (entry.clone(), module.as_str().unwrap_or("").to_string())
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        },
+        // Either a list of members or `{ "members": [...] }`
+        workspace: json["workspace"]
+            .as_array()
+            .or_else(|| json["workspace"]["members"].as_array())
+            .map(|arr| strings(arr)),
+        include: json["publish"]["include"]
+            .as_array()
+            .map(|arr| strings(arr)),
+        exclude: json["publish"]["exclude"]
+            .as_array()
+            .map(|arr| strings(arr)),
+        ..Default::default()
+    })
+}
+
+/// Read an import map, whose targets are `jsr:` or `npm:` specifiers, paths or URLs
+fn import_map(
+    imports: &Map<String, Value>,
+    scope: Option<&str>,
+) -> Result<Vec<DependencySpec>, MetadataError> {
+    let mut dependencies = Vec::new();
+    for (name, target) in imports {
+        let target = target.as_str().ok_or_else(|| {
+            MetadataError::DependencySpecifierError(
+                name.clone(),
+                SpecifierError::NotAString(target.to_string()),
+            )
+        })?;
+        let dependency = if let Some(package) = package_specifier(target) {
+            npm::dependency(name, DependencyKind::Runtime, &package)
+        } else if ["./", "../", "/"]
+            .iter()
+            .any(|prefix| target.starts_with(prefix))
+        {
+            DependencySpec::new(name, DependencyKind::Runtime).source(DependencySource::Path {
+                path: target.to_string(),
+            })
+        } else {
+            DependencySpec::new(name, DependencyKind::Runtime).source(DependencySource::Url {
+                url: target.to_string(),
+            })
+        };
+        dependencies.push(dependency.group(scope));
+    }
+    Ok(dependencies)
+}
+
+/// `jsr:` or `npm:` target without the leading `/` and the `/subpath` of a module in the
+/// package: `jsr:/@std/path@^1/posix` targets `jsr:@std/path@^1`
+fn package_specifier(target: &str) -> Option<String> {
+    let (registry, package) = ["jsr", "npm"]
+        .into_iter()
+        .find_map(|registry| Some((registry, target.strip_prefix(registry)?.strip_prefix(':')?)))?;
+    let package = package.trim_start_matches('/');
+    // The subpath starts at the first `/` after the name, past the one of a scope
+    let name_start = match package.starts_with('@') {
+        true => package.find('/').map_or(package.len(), |slash| slash + 1),
+        false => 0,
+    };
+    let end = package[name_start..]
+        .find('/')
+        .map_or(package.len(), |slash| name_start + slash);
+    Some(format!("{registry}:{}", &package[..end]))
+}
+
+fn strings(arr: &[Value]) -> Vec<String> {
+    arr.iter()
+        .map(|v| v.as_str().unwrap_or("").to_string())
+        .collect()
+}
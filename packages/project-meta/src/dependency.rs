@@ -0,0 +1,462 @@
+//! Ecosystem independent dependency model, so a Cargo dependency, an npm one and a PEP 508
+//! requirement on the same package can be compared.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct DependencySpec {
+    pub(crate) name: String,
+    pub(crate) kind: DependencyKind,
+    /// `None` when any version is accepted
+    pub(crate) version: Option<VersionReq>,
+    pub(crate) source: DependencySource,
+    /// Python extras or Cargo features enabled on the dependency
+    pub(crate) features: Option<Vec<String>>,
+    pub(crate) default_features: Option<bool>,
+    /// PEP 508 environment markers
+    pub(crate) markers: Option<String>,
+    /// Target triple or `cfg(..)` expression the dependency is restricted to
+    pub(crate) target: Option<String>,
+    /// Extra, dependency group or npm dependency group declaring it
+    pub(crate) group: Option<String>,
+    /// Name the dependency is published under when the manifest renames it, such as Cargo's
+    /// `package` key, whatever its source
+    pub(crate) package: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Copy, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    #[default]
+    Runtime,
+    Dev,
+    Peer,
+    Optional,
+    Build,
+    /// Version forced onto the dependency graph: npm `overrides`, Yarn `resolutions`, pnpm
+    /// `overrides`
+    Override,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DependencySource {
+    /// Default registry of the ecosystem when `registry` is not set
+    Registry {
+        registry: Option<String>,
+        /// npm dist-tag, such as `latest`, selecting the version
+        dist_tag: Option<String>,
+    },
+    Git {
+        url: String,
+        branch: Option<String>,
+        tag: Option<String>,
+        rev: Option<String>,
+    },
+    Path {
+        path: String,
+    },
+    Url {
+        url: String,
+    },
+    /// Resolved from the workspace the project belongs to (`workspace:` protocol)
+    Workspace,
+    /// Shipped with an SDK rather than downloaded, such as Flutter's packages
+    Sdk {
+        sdk: String,
+    },
+    /// Published under another name, possibly on another registry (`npm:`, `jsr:`). A Cargo
+    /// `package = "…"` rename keeps its source and sets [`DependencySpec::package`] instead.
+    Alias {
+        registry: Option<String>,
+        package: String,
+        dist_tag: Option<String>,
+    },
+}
+
+impl Default for DependencySource {
+    fn default() -> Self {
+        DependencySource::Registry {
+            registry: None,
+            dist_tag: None,
+        }
+    }
+}
+
+/// Requirement syntax, which decides what a bare version means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSyntax {
+    /// Bare versions are caret requirements
+    Cargo,
+    /// npm, Poetry and PEP 440: bare versions are exact
+    Exact,
+    /// Bare versions are exact and a single `|` also separates alternatives
+    Composer,
+    /// Bare versions are minimums, as with Go's minimal version selection or Maven's soft
+    /// requirements
+    Minimum,
+}
+
+/// A version requirement, kept verbatim alongside its parsed form
+///
+/// Requirements compare by their parsed form, so Cargo's `1.0` equals npm's `^1.0` and PEP 440's
+/// `==1.0` equals npm's `1.0`. An unparsed requirement has no alternatives and only equals the
+/// same raw text.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionReq {
+    raw: String,
+    /// Alternatives (`||`) each satisfied when all of their comparators are
+    any_of: Vec<Vec<Comparator>>,
+}
+
+impl PartialEq for VersionReq {
+    fn eq(&self, other: &Self) -> bool {
+        self.any_of == other.any_of && (!self.any_of.is_empty() || self.raw == other.raw)
+    }
+}
+
+impl Eq for VersionReq {}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+#[serde(into = "String", try_from = "String")]
+pub struct Comparator {
+    op: Op,
+    version: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Op {
+    Exact,
+    NotEqual,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Caret,
+    Tilde,
+    /// PEP 440 `~=`, which Composer writes `~`
+    Compatible,
+    /// PEP 440 `===`
+    Arbitrary,
+}
+
+impl Op {
+    /// Operators by decreasing length, so that `>=` is tried before `>`
+    pub(crate) const ALL: [(&'static str, Op); 11] = [
+        ("===", Op::Arbitrary),
+        ("~=", Op::Compatible),
+        ("==", Op::Exact),
+        ("!=", Op::NotEqual),
+        (">=", Op::GreaterEq),
+        ("<=", Op::LessEq),
+        ("^", Op::Caret),
+        ("~", Op::Tilde),
+        (">", Op::Greater),
+        ("<", Op::Less),
+        ("=", Op::Exact),
+    ];
+
+    pub(crate) fn split(comparator: &str) -> Option<(Op, &str)> {
+        Op::ALL.iter().find_map(|(symbol, op)| {
+            comparator
+                .strip_prefix(symbol)
+                .map(|version| (*op, version.trim()))
+        })
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Exact => "=",
+            Op::NotEqual => "!=",
+            Op::Greater => ">",
+            Op::GreaterEq => ">=",
+            Op::Less => "<",
+            Op::LessEq => "<=",
+            Op::Caret => "^",
+            Op::Tilde => "~",
+            Op::Compatible => "~=",
+            Op::Arbitrary => "===",
+        }
+    }
+}
+
+impl Comparator {
+    pub(crate) fn new(op: Op, version: String) -> Self {
+        Comparator { op, version }
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.op.symbol(), self.version)
+    }
+}
+
+impl From<Comparator> for String {
+    fn from(comparator: Comparator) -> Self {
+        comparator.to_string()
+    }
+}
+
+impl TryFrom<String> for Comparator {
+    type Error = String;
+
+    fn try_from(comparator: String) -> Result<Self, Self::Error> {
+        let (op, version) =
+            Op::split(&comparator).ok_or_else(|| format!("missing operator in `{comparator}`"))?;
+        Ok(Comparator {
+            op,
+            version: version.to_string(),
+        })
+    }
+}
+
+impl VersionReq {
+    pub(crate) fn new(raw: &str, any_of: Vec<Vec<Comparator>>) -> Self {
+        VersionReq {
+            raw: raw.to_string(),
+            any_of,
+        }
+    }
+
+    /// A requirement kept as written, without alternatives, when it could not be parsed
+    pub(crate) fn unparsed(raw: &str) -> Self {
+        VersionReq::new(raw, Vec::new())
+    }
+
+    /// Parse the interval notation of Maven and NuGet, where ranges such as `[1.0,2.0)` or
+    /// `(,1.0],[1.2,)` list alternatives; bare versions follow `syntax`
+    pub(crate) fn parse_intervals(raw: &str, syntax: VersionSyntax) -> Result<Self, String> {
+        let invalid = |reason: &str| reason.to_string();
+        let trimmed = raw.trim();
+        if !trimmed.starts_with(['[', '(']) {
+            return Ok(VersionReq::parse(trimmed, syntax).with_raw(raw));
+        }
+        let mut any_of = Vec::new();
+        let mut rest = trimmed;
+        while !rest.is_empty() {
+            let inclusive_low = match rest.chars().next() {
+                Some('[') => true,
+                Some('(') => false,
+                _ => return Err(invalid("expected `[` or `(` to open a range")),
+            };
+            let close = rest
+                .find([']', ')'])
+                .ok_or_else(|| invalid("unclosed range"))?;
+            let bounds = &rest[1..close];
+            let inclusive_high = rest[close..].starts_with(']');
+            let comparators = match bounds.split_once(',') {
+                None if inclusive_low && inclusive_high && !bounds.trim().is_empty() => {
+                    vec![Comparator::new(Op::Exact, bounds.trim().to_string())]
+                }
+                None => return Err(invalid("a single version is written `[1.0]`")),
+                Some((low, high)) => {
+                    let (low, high) = (low.trim(), high.trim());
+                    if high.contains(',') {
+                        return Err(invalid("a range has at most two bounds"));
+                    }
+                    let mut comparators = Vec::new();
+                    if !low.is_empty() {
+                        let op = if inclusive_low {
+                            Op::GreaterEq
+                        } else {
+                            Op::Greater
+                        };
+                        comparators.push(Comparator::new(op, low.to_string()));
+                    }
+                    if !high.is_empty() {
+                        let op = if inclusive_high { Op::LessEq } else { Op::Less };
+                        comparators.push(Comparator::new(op, high.to_string()));
+                    }
+                    comparators
+                }
+            };
+            any_of.push(comparators);
+            rest = rest[close + 1..].trim_start();
+            if let Some(next) = rest.strip_prefix(',') {
+                rest = next.trim_start();
+                if rest.is_empty() {
+                    return Err(invalid("dangling `,` after a range"));
+                }
+            } else if !rest.is_empty() {
+                return Err(invalid("ranges are separated by `,`"));
+            }
+        }
+        Ok(VersionReq::new(raw, any_of))
+    }
+
+    /// Keep `raw` as the written form, when the requirement was parsed from a normalized one
+    pub(crate) fn with_raw(mut self, raw: &str) -> Self {
+        self.raw = raw.to_string();
+        self
+    }
+
+    /// Whether some alternative has no comparator, as `*` and empty requirements
+    pub(crate) fn accepts_any(&self) -> bool {
+        self.any_of.iter().any(|comparators| comparators.is_empty())
+    }
+
+    /// Parse `||` separated alternatives of comma or space separated comparators; `*` and empty
+    /// requirements accept any version. Composer also reads `1.0 - 2.0` hyphen ranges.
+    pub fn parse(raw: &str, syntax: VersionSyntax) -> Self {
+        let alternatives: Vec<&str> = match syntax {
+            VersionSyntax::Composer => raw
+                .split('|')
+                .filter(|alternative| !alternative.is_empty())
+                .collect(),
+            _ => raw.split("||").collect(),
+        };
+        let any_of = alternatives
+            .into_iter()
+            .map(|alternative| {
+                let mut comparators: Vec<Comparator> = Vec::new();
+                let mut pending_op: Option<&str> = None;
+                let mut hyphen = false;
+                for token in alternative
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|token| !token.is_empty())
+                {
+                    if syntax == VersionSyntax::Composer && token == "-" && !comparators.is_empty()
+                    {
+                        hyphen = true;
+                        continue;
+                    }
+                    if std::mem::take(&mut hyphen) {
+                        if let Some(low) = comparators.last_mut() {
+                            low.op = Op::GreaterEq;
+                        }
+                        comparators.push(hyphen_upper_bound(token));
+                        continue;
+                    }
+                    // `>= 1.0` puts a space between the operator and the version
+                    if Op::ALL.iter().any(|(symbol, _)| *symbol == token) {
+                        pending_op = Some(token);
+                        continue;
+                    }
+                    let token = match pending_op.take() {
+                        Some(op) => format!("{op}{token}"),
+                        None => token.to_string(),
+                    };
+                    if token == "*" {
+                        continue;
+                    }
+                    comparators.push(match Op::split(&token) {
+                        // Composer's `~1.2` allows `<2.0`, unlike npm and Cargo's `<1.3`
+                        Some((Op::Tilde, version)) if syntax == VersionSyntax::Composer => {
+                            Comparator::new(Op::Compatible, version.to_string())
+                        }
+                        Some((op, version)) => Comparator {
+                            op,
+                            version: version.to_string(),
+                        },
+                        None => Comparator {
+                            op: match syntax {
+                                VersionSyntax::Cargo => Op::Caret,
+                                VersionSyntax::Exact | VersionSyntax::Composer => Op::Exact,
+                                VersionSyntax::Minimum => Op::GreaterEq,
+                            },
+                            version: token,
+                        },
+                    });
+                }
+                comparators
+            })
+            .collect();
+        VersionReq {
+            raw: raw.to_string(),
+            any_of,
+        }
+    }
+}
+
+/// Upper bound of a Composer hyphen range: a partial version stands for all of its versions,
+/// `1.0 - 2` meaning `>=1.0 <3`, while a complete one is inclusive
+fn hyphen_upper_bound(version: &str) -> Comparator {
+    let parts: Vec<&str> = version.split('.').collect();
+    let bumped = match parts.split_last() {
+        Some((last, init)) if parts.len() < 3 => last.parse::<u64>().ok().map(|last| {
+            let mut bumped: Vec<String> = init.iter().map(|part| part.to_string()).collect();
+            bumped.push((last + 1).to_string());
+            bumped.join(".")
+        }),
+        _ => None,
+    };
+    match bumped {
+        Some(bumped) => Comparator::new(Op::Less, bumped),
+        None => Comparator::new(Op::LessEq, version.to_string()),
+    }
+}
+
+impl DependencySpec {
+    pub fn new(name: &str, kind: DependencyKind) -> Self {
+        DependencySpec {
+            name: name.to_string(),
+            kind,
+            version: None,
+            source: DependencySource::default(),
+            features: None,
+            default_features: None,
+            markers: None,
+            target: None,
+            group: None,
+            package: None,
+        }
+    }
+
+    pub fn version(mut self, raw: Option<&str>, syntax: VersionSyntax) -> Self {
+        self.version = raw.map(|raw| VersionReq::parse(raw, syntax));
+        self
+    }
+
+    pub fn with_version(mut self, version: Option<VersionReq>) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn source(mut self, source: DependencySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn features(mut self, features: Option<Vec<String>>) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub fn default_features(mut self, default_features: Option<bool>) -> Self {
+        self.default_features = default_features;
+        self
+    }
+
+    pub fn markers(mut self, markers: Option<String>) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    pub fn target(mut self, target: Option<&str>) -> Self {
+        self.target = target.map(|s| s.to_string());
+        self
+    }
+
+    pub fn group(mut self, group: Option<&str>) -> Self {
+        self.group = group.map(|s| s.to_string());
+        self
+    }
+
+    pub fn package(mut self, package: Option<&str>) -> Self {
+        self.package = package.map(|s| s.to_string());
+        self
+    }
+
+    pub fn kind(mut self, kind: DependencyKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Give manifests a stable dependency order, whatever their map ordering
+    pub(crate) fn sort(dependencies: &mut [DependencySpec]) {
+        dependencies.sort_by(|a, b| {
+            (a.kind, &a.group, &a.target, &a.name).cmp(&(b.kind, &b.group, &b.target, &b.name))
+        });
+    }
+}
@@ -0,0 +1,231 @@
+//! OCaml `dune-project` files: the project fields, and the `(package ...)` stanzas dune generates
+//! opam files from.
+//!
+//! A project may define several packages. The one named like the project, or else the first
+//! one, gives the description, keywords and dependencies; the names of all of them are kept in
+//! `properties` as `packages`.
+use crate::dependency::DependencySpec;
+use crate::opam::{compiler_version, dependency, Formula};
+use crate::sexp::{Sexp, Value};
+use crate::syntax::SyntaxError;
+use crate::util::any_license;
+use crate::{Person, ProjectMetadata};
+use std::collections::HashMap;
+
+pub(crate) fn from_sexps(sexps: &[Sexp]) -> Result<ProjectMetadata, SyntaxError> {
+    let error = |line: usize, message: String| SyntaxError::new(line, message);
+    // dune reads the version of its language before anything else
+    let lang = match sexps.first().and_then(Sexp::as_form) {
+        Some(("lang", [language, version])) if language.as_atom() == Some("dune") => {
+            version.as_atom()
+        }
+        _ => None,
+    };
+    let Some(lang) = lang else {
+        let line = sexps.first().map(|sexp| sexp.line).unwrap_or(1);
+        return Err(error(
+            line,
+            "expected `(lang dune <version>)` first".to_string(),
+        ));
+    };
+
+    let mut stanzas: HashMap<&str, &[Sexp]> = HashMap::new();
+    let mut packages = Vec::new();
+    for sexp in &sexps[1..] {
+        let Some((name, args)) = sexp.as_form() else {
+            return Err(error(
+                sexp.line,
+                "expected a `(name ...)` stanza".to_string(),
+            ));
+        };
+        match name {
+            "package" => {
+                let package = fields(args);
+                let name = package
+                    .get("name")
+                    .and_then(|name| name.first())
+                    .and_then(Sexp::as_atom)
+                    .ok_or_else(|| {
+                        error(
+                            sexp.line,
+                            "`(package)` stanza without a `(name ...)`".to_string(),
+                        )
+                    })?;
+                packages.push((name, package));
+            }
+            name => {
+                stanzas.insert(name, args);
+            }
+        }
+    }
+    let atom = |args: Option<&[Sexp]>| args?.first()?.as_atom().map(|s| s.to_string());
+    let atoms = |args: Option<&[Sexp]>| -> Option<Vec<String>> {
+        args.map(|args| {
+            args.iter()
+                .filter_map(Sexp::as_atom)
+                .map(|s| s.to_string())
+                .collect()
+        })
+    };
+
+    let name = atom(stanzas.get("name").copied())
+        .or_else(|| packages.first().map(|(name, _)| name.to_string()))
+        .unwrap_or_default();
+    let package = packages
+        .iter()
+        .find(|(package, _)| *package == name)
+        .or(packages.first())
+        .map(|(_, fields)| fields);
+    // Packages may override the fields of the project
+    let field = |name: &str| {
+        package
+            .and_then(|package| package.get(name).copied())
+            .or(stanzas.get(name).copied())
+    };
+    let people = |name: &str| {
+        atoms(field(name)).map(|people| people.iter().map(|person| Person::parse(person)).collect())
+    };
+
+    let mut engines = HashMap::from([("dune".to_string(), format!(">= {lang}"))]);
+    let mut dependencies = Vec::new();
+    for key in ["depends", "depopts"] {
+        let items = package.and_then(|package| package.get(key).copied());
+        for item in items.into_iter().flatten() {
+            let (name, formula) = match &item.value {
+                Value::Atom(name) => (name.as_str(), None),
+                Value::List(items) => match items.split_first() {
+                    Some((name, constraints)) if name.as_atom().is_some() => {
+                        let formulas = constraints
+                            .iter()
+                            .map(formula)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let formula = match formulas.len() {
+                            1 => formulas.into_iter().next(),
+                            _ => Some(Formula::And(formulas)),
+                        };
+                        (name.as_atom().unwrap_or_default(), formula)
+                    }
+                    _ => {
+                        let message = "expected a package or `(package constraint)`".to_string();
+                        return Err(error(item.line, message));
+                    }
+                },
+            };
+            // The compiler is a package like any other for opam
+            if name == "ocaml" {
+                if let Some(version) = compiler_version(formula.as_ref()) {
+                    engines.insert("ocaml".to_string(), version);
+                }
+                continue;
+            }
+            dependencies.push(dependency(name, formula.as_ref(), key));
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let mut urls = HashMap::new();
+    for (stanza, key) in [
+        ("homepage", "homepage"),
+        ("bug_reports", "issues"),
+        ("documentation", "documentation"),
+    ] {
+        if let Some(url) = atom(field(stanza)) {
+            urls.insert(key.to_string(), url);
+        }
+    }
+    // `(source (github user/repo))` and the like
+    if let Some(source) = field("source").and_then(|args| args.first()) {
+        let repository = match source.as_form() {
+            Some(("github", [repo])) => repo.as_atom().map(|r| format!("https://github.com/{r}")),
+            Some(("gitlab", [repo])) => repo.as_atom().map(|r| format!("https://gitlab.com/{r}")),
+            Some(("codeberg", [repo])) => {
+                repo.as_atom().map(|r| format!("https://codeberg.org/{r}"))
+            }
+            Some(("sourcehut", [repo])) => repo.as_atom().map(|r| format!("https://sr.ht/~{r}")),
+            Some(("uri", [uri])) => uri.as_atom().map(|uri| uri.to_string()),
+            _ => None,
+        };
+        if let Some(repository) = repository {
+            urls.insert("repository".to_string(), repository);
+        }
+    }
+
+    let mut properties = HashMap::new();
+    if packages.len() > 1 {
+        let names: Vec<&str> = packages.iter().map(|(name, _)| *name).collect();
+        properties.insert("packages".to_string(), names.join(", "));
+    }
+
+    Ok(ProjectMetadata {
+        name,
+        version: atom(field("version")).unwrap_or_default(),
+        description: atom(field("synopsis")).or_else(|| atom(field("description"))),
+        authors: people("authors"),
+        maintainers: people("maintainers"),
+        license: atoms(field("license")).and_then(|licenses| any_license(&licenses)),
+        // `(tags (a b))` as well as `(tags a b)`
+        keywords: field("tags").map(|tags| {
+            tags.iter()
+                .flat_map(|tag| match &tag.value {
+                    Value::Atom(tag) => vec![tag.clone()],
+                    Value::List(tags) => atoms(Some(tags)).unwrap_or_default(),
+                })
+                .collect()
+        }),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: Some(engines),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    })
+}
+
+/// Arguments of the `(field args...)` forms of a stanza
+fn fields(args: &[Sexp]) -> HashMap<&str, &[Sexp]> {
+    args.iter().filter_map(Sexp::as_form).collect()
+}
+
+/// `:with-test`, `(>= 1.0)`, `(and ...)`, `(or ...)`, `(not ...)`
+fn formula(sexp: &Sexp) -> Result<Formula, SyntaxError> {
+    if let Some(atom) = sexp.as_atom() {
+        return match atom.strip_prefix(':') {
+            Some(flag) => Ok(Formula::Flag(flag.to_string())),
+            None => Err(SyntaxError::new(
+                sexp.line,
+                format!("expected a `:flag` or a constraint, found `{atom}`"),
+            )),
+        };
+    }
+    let error = || {
+        SyntaxError::new(
+            sexp.line,
+            "expected a `(op version)`, `(and ...)`, `(or ...)` or `(not ...)` constraint",
+        )
+    };
+    let (op, args) = sexp.as_form().ok_or_else(error)?;
+    match (op, args) {
+        ("and", formulas) => Ok(Formula::And(
+            formulas.iter().map(formula).collect::<Result<_, _>>()?,
+        )),
+        ("or", formulas) => Ok(Formula::Or(
+            formulas.iter().map(formula).collect::<Result<_, _>>()?,
+        )),
+        ("not", [negated]) => Ok(Formula::Not(Box::new(formula(negated)?))),
+        ("=" | "<>" | "<" | "<=" | ">" | ">=", [version]) => {
+            let version = match version.as_atom() {
+                // `(= :version)` is the version of the project itself
+                Some(variable) if variable.starts_with(':') => {
+                    format!("%{{{}}}%", &variable[1..])
+                }
+                Some(version) => version.to_string(),
+                None => return Err(error()),
+            };
+            let op = match op {
+                "<>" => "!=",
+                op => op,
+            };
+            Ok(Formula::Constraint(op.to_string(), version))
+        }
+        _ => Err(error()),
+    }
+}
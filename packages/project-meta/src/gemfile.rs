@@ -0,0 +1,254 @@
+//! Bundler `Gemfile` reader, read statically like gemspecs: `gem` calls with literal arguments
+//! become dependencies, inside the `group`, `platforms`, `source`, `git` and `path` blocks they
+//! appear in.
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec};
+use crate::ruby::{self, Expr, Parser, Token, Value};
+use crate::syntax::SyntaxError;
+use crate::util::located;
+use crate::{MetadataError, ProjectMetadata};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Options of a `gem` call or of the block it is in
+#[derive(Default, Clone)]
+struct Options {
+    groups: Vec<String>,
+    platforms: Vec<String>,
+    source: Option<String>,
+    git: Option<String>,
+    branch: Option<String>,
+    tag: Option<String>,
+    rev: Option<String>,
+    path: Option<String>,
+}
+
+impl Options {
+    fn set(&mut self, key: &str, value: &Value) {
+        let string = || value.as_str().map(|s| s.to_string());
+        match key {
+            "group" | "groups" => self.groups = value.strings().unwrap_or_default(),
+            "platform" | "platforms" => self.platforms = value.strings().unwrap_or_default(),
+            "source" => self.source = string(),
+            "git" => self.git = string(),
+            // Bundler's built-in shorthand for GitHub repositories
+            "github" => {
+                self.git = string().map(|repository| format!("https://github.com/{repository}.git"))
+            }
+            "branch" => self.branch = string(),
+            "tag" => self.tag = string(),
+            "ref" => self.rev = string(),
+            "path" => self.path = string(),
+            _ => {}
+        }
+    }
+
+    fn dependency(&self, name: &str, requirements: &[String]) -> DependencySpec {
+        // Gems only needed to develop the project are in the development and test groups
+        let dev = !self.groups.is_empty()
+            && self
+                .groups
+                .iter()
+                .all(|group| matches!(group.as_str(), "development" | "test"));
+        let kind = match dev {
+            true => DependencyKind::Dev,
+            false => DependencyKind::Runtime,
+        };
+        let source = match (&self.git, &self.path) {
+            (Some(url), _) => DependencySource::Git {
+                url: url.clone(),
+                branch: self.branch.clone(),
+                tag: self.tag.clone(),
+                rev: self.rev.clone(),
+            },
+            (None, Some(path)) => DependencySource::Path { path: path.clone() },
+            (None, None) => DependencySource::Registry {
+                registry: self.source.clone(),
+                dist_tag: None,
+            },
+        };
+        let groups = self.groups.join(", ");
+        let platforms = self.platforms.join(", ");
+        DependencySpec::new(name, kind)
+            .with_version(ruby::requirement(requirements))
+            .source(source)
+            .group((!groups.is_empty()).then_some(groups.as_str()))
+            .target((!platforms.is_empty()).then_some(platforms.as_str()))
+    }
+}
+
+pub(crate) fn from_ruby(
+    contents: &str,
+    gemfile_path: Option<&Path>,
+) -> Result<ProjectMetadata, MetadataError> {
+    let error = |err: SyntaxError| {
+        MetadataError::ParseError("Ruby".to_string(), located(gemfile_path, err))
+    };
+    let tokens = ruby::lex(contents).map_err(error)?;
+    let mut parser = Parser::new(contents, &tokens);
+
+    let mut dependencies = Vec::new();
+    let mut engines = HashMap::new();
+    // Options of the enclosing blocks, `None` for blocks not opened by a known method
+    let mut blocks: Vec<Option<Options>> = Vec::new();
+    // Options outside of any block
+    let mut base = Options::default();
+    loop {
+        parser.skip_newlines();
+        let start = parser.pos;
+        let Some(token) = parser.peek() else {
+            break;
+        };
+        let options = blocks
+            .iter()
+            .rev()
+            .find_map(|block| block.clone())
+            .unwrap_or_else(|| base.clone());
+        match token {
+            Token::Ident(end) if end == "end" => {
+                if blocks.pop().is_none() {
+                    return Err(error(SyntaxError::new(parser.line(), "unexpected `end`")));
+                }
+                parser.bump();
+            }
+            // Conditional gems are read too, the condition being left to the user
+            Token::Ident(keyword)
+                if matches!(keyword.as_str(), "if" | "unless" | "case" | "begin") =>
+            {
+                blocks.push(None);
+                skip_line(&mut parser);
+            }
+            Token::Ident(keyword)
+                if matches!(keyword.as_str(), "else" | "elsif" | "when" | "rescue") =>
+            {
+                skip_line(&mut parser)
+            }
+            Token::Ident(method) => {
+                parser.bump();
+                let method = method.as_str();
+                let parens = parser.eat("(");
+                let (arguments, keywords) = arguments(&mut parser);
+                if parens {
+                    parser.eat(")");
+                }
+                let opens_block = parser.is_ident("do");
+                if opens_block {
+                    parser.bump();
+                    // Block parameters
+                    if parser.eat("|") {
+                        parser.skip(&["|"]);
+                        parser.eat("|");
+                    }
+                }
+                let mut block = options.clone();
+                let known = match method {
+                    "gem" => {
+                        let mut options = options.clone();
+                        for (key, value) in &keywords {
+                            options.set(key, value);
+                        }
+                        let mut strings = arguments.iter().filter_map(|argument| argument.as_str());
+                        if let Some(name) = strings.next() {
+                            let requirements: Vec<String> =
+                                strings.map(|s| s.to_string()).collect();
+                            dependencies.push(options.dependency(name, &requirements));
+                        }
+                        true
+                    }
+                    "source" if !opens_block => {
+                        // Gems come from RubyGems unless the global source is another registry
+                        base.source = arguments
+                            .first()
+                            .and_then(|value| value.as_str())
+                            .filter(|source| !source.contains("rubygems.org"))
+                            .map(|source| source.to_string());
+                        true
+                    }
+                    "ruby" => {
+                        let requirements: Vec<&str> = arguments
+                            .iter()
+                            .filter_map(|value| value.as_str())
+                            .collect();
+                        if !requirements.is_empty() {
+                            engines.insert("ruby".to_string(), requirements.join(", "));
+                        }
+                        true
+                    }
+                    "group" | "platforms" | "platform" => {
+                        block.set(method, &Value::Array(arguments));
+                        true
+                    }
+                    "source" | "git" | "github" | "path" => {
+                        if let Some(value) = arguments.first() {
+                            block.set(method, value);
+                        }
+                        for (key, value) in &keywords {
+                            block.set(key, value);
+                        }
+                        true
+                    }
+                    _ => false,
+                };
+                if opens_block {
+                    blocks.push(known.then_some(block));
+                }
+            }
+            _ => parser.skip(&[]),
+        }
+        if !parser.at_end(&[]) {
+            parser.skip(&[]);
+        }
+        if parser.pos == start {
+            parser.bump();
+        }
+    }
+    if !blocks.is_empty() {
+        return Err(error(SyntaxError::new(parser.line(), "unterminated block")));
+    }
+
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        engines: (!engines.is_empty()).then_some(engines),
+        ..Default::default()
+    })
+}
+
+/// Literal positional and keyword arguments of a call, the others being left out
+fn arguments(parser: &mut Parser) -> (Vec<Value>, Vec<(String, Value)>) {
+    let mut positional = Vec::new();
+    let mut keywords = Vec::new();
+    while !parser.at_end(&["do"]) {
+        let keyword = match parser.peek() {
+            Some(Token::Label(label)) => {
+                parser.bump();
+                Some(label.clone())
+            }
+            Some(Token::Symbol(symbol)) if parser.peek_at(1) == Some(&Token::Punct("=>")) => {
+                parser.pos += 2;
+                Some(symbol.clone())
+            }
+            _ => None,
+        };
+        let value = match parser.expression(&[",", "do"]) {
+            Expr::Static(value) => Some(value),
+            Expr::Dynamic(_) => None,
+        };
+        match (keyword, value) {
+            (Some(keyword), Some(value)) => keywords.push((keyword, value)),
+            (None, Some(value)) => positional.push(value),
+            _ => {}
+        }
+        if !parser.eat(",") {
+            break;
+        }
+        parser.skip_newlines();
+    }
+    (positional, keywords)
+}
+
+fn skip_line(parser: &mut Parser) {
+    while !matches!(parser.peek(), None | Some(Token::Newline)) {
+        parser.bump();
+    }
+}
@@ -0,0 +1,460 @@
+//! `*.gemspec` reader. The `Gem::Specification.new` block is read without running it: attributes
+//! assigned a literal are kept, and the others are listed in `dynamic` with the expression
+//! computing them, rather than guessed. A `VERSION` constant is the exception, looked up in the
+//! files the gemspec requires the way Bundler's gem template writes it.
+use crate::dependency::{DependencyKind, DependencySpec};
+use crate::ruby::{self, Expr, Parser, Token, Value};
+use crate::syntax::SyntaxError;
+use crate::util::{any_license, located};
+use crate::{FieldValue, MetadataError, Person, ProjectMetadata};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Statements starting a nested block, whose assignments depend on a condition
+const CONDITIONALS: [&str; 6] = ["if", "unless", "case", "while", "until", "begin"];
+
+/// Metadata keys of the project links, and the label they get in `urls`
+const URI_KEYS: [(&str, &str); 7] = [
+    ("homepage_uri", "homepage"),
+    ("source_code_uri", "repository"),
+    ("documentation_uri", "documentation"),
+    ("changelog_uri", "changelog"),
+    ("bug_tracker_uri", "issues"),
+    ("wiki_uri", "wiki"),
+    ("funding_uri", "funding"),
+];
+
+pub(crate) fn from_ruby(
+    contents: &str,
+    gemspec_path: Option<&Path>,
+) -> Result<ProjectMetadata, MetadataError> {
+    let error = |err: SyntaxError| {
+        MetadataError::ParseError("Ruby".to_string(), located(gemspec_path, err))
+    };
+    let tokens = ruby::lex(contents).map_err(error)?;
+    let spec = Specification::read(contents, &tokens).map_err(error)?;
+    let dir = gemspec_path.map(|path| path.parent().unwrap_or(Path::new("")));
+
+    let mut fields = Fields::default();
+    let name = fields.string("name", spec.attribute(&["name"]));
+    let version = match spec.attribute(&["version"]) {
+        Some(Expr::Dynamic(expression)) => match resolve_version(expression, &spec, &name, dir) {
+            Some((version, source)) => {
+                fields.dynamic.push("version".to_string());
+                fields
+                    .sources
+                    .insert("version".to_string(), FieldValue::Resolved { source });
+                Some(version)
+            }
+            None => fields.string("version", spec.attribute(&["version"])),
+        },
+        version => fields.string("version", version),
+    };
+    // The summary is the short description other formats have
+    let description = fields.string("description", spec.attribute(&["summary", "description"]));
+    let authors = fields.strings("authors", spec.attribute(&["authors", "author"]));
+    let emails = fields.strings("authors", spec.attribute(&["email"]));
+    let authors = authors.map(|authors| {
+        // Emails pair with the authors when there is one for each
+        let emails = emails.filter(|emails| emails.len() == authors.len());
+        authors
+            .iter()
+            .enumerate()
+            .map(|(index, author)| {
+                let email = emails.as_ref().map(|emails| emails[index].as_str());
+                Person::new(Some(author), email, None)
+            })
+            .collect()
+    });
+    let license = fields
+        .strings("license", spec.attribute(&["licenses", "license"]))
+        .and_then(|licenses| any_license(&licenses));
+    let include = fields.strings("include", spec.attribute(&["files"]));
+
+    let mut engines = HashMap::new();
+    for (attribute, engine) in [
+        ("required_ruby_version", "ruby"),
+        ("required_rubygems_version", "rubygems"),
+    ] {
+        if let Some(requirement) = fields.strings("engines", spec.attribute(&[attribute])) {
+            engines.insert(engine.to_string(), requirement.join(", "));
+        }
+    }
+
+    let mut urls = HashMap::new();
+    if let Some(homepage) = fields.string("urls", spec.attribute(&["homepage"])) {
+        urls.insert("homepage".to_string(), homepage);
+    }
+    let mut properties = HashMap::new();
+    for (key, value) in &spec.metadata {
+        let label = URI_KEYS
+            .iter()
+            .find(|(uri_key, _)| uri_key == key)
+            .map(|(_, label)| *label);
+        let field = if label.is_some() {
+            "urls"
+        } else {
+            "properties"
+        };
+        let Some(value) = fields.string(field, Some(value)) else {
+            continue;
+        };
+        match label {
+            Some(label) => urls.insert(label.to_string(), value),
+            None => properties.insert(key.clone(), value),
+        };
+    }
+    if let Some(expression) = &spec.dynamic_metadata {
+        fields.report("properties", expression);
+    }
+    for expression in &spec.dynamic_dependencies {
+        fields.report("dependencies", expression);
+    }
+    let mut dependencies = spec.dependencies.clone();
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        name: name.unwrap_or_default(),
+        version: version.unwrap_or_default(),
+        description,
+        authors,
+        license,
+        include,
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: (!engines.is_empty()).then_some(engines),
+        properties: (!properties.is_empty()).then_some(properties),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        dynamic: (!fields.dynamic.is_empty()).then_some(fields.dynamic),
+        sources: (!fields.sources.is_empty()).then_some(fields.sources),
+        ..Default::default()
+    })
+}
+
+/// Fields read so far that could not be evaluated, with the expressions computing them
+#[derive(Default)]
+struct Fields {
+    dynamic: Vec<String>,
+    sources: HashMap<String, FieldValue>,
+}
+
+impl Fields {
+    fn report(&mut self, field: &str, expression: &str) {
+        if !self.dynamic.iter().any(|dynamic| dynamic == field) {
+            self.dynamic.push(field.to_string());
+        }
+        // A field computed in several places is reported with the first one
+        self.sources
+            .entry(field.to_string())
+            .or_insert_with(|| FieldValue::Dynamic {
+                source: expression.to_string(),
+            });
+    }
+
+    fn value<'a>(&mut self, field: &str, expr: Option<&'a Expr>) -> Option<&'a Value> {
+        match expr? {
+            Expr::Static(value) => Some(value),
+            Expr::Dynamic(expression) => {
+                self.report(field, expression);
+                None
+            }
+        }
+    }
+
+    fn string(&mut self, field: &str, expr: Option<&Expr>) -> Option<String> {
+        match self.value(field, expr)? {
+            Value::Number(number) => Some(number.clone()),
+            value => value.as_str().map(|s| s.to_string()),
+        }
+    }
+
+    fn strings(&mut self, field: &str, expr: Option<&Expr>) -> Option<Vec<String>> {
+        self.value(field, expr)?.strings()
+    }
+}
+
+/// What the `Gem::Specification.new` block sets
+#[derive(Default)]
+struct Specification {
+    /// Attribute assignments in order, conditional ones being dynamic
+    attributes: Vec<(String, Expr)>,
+    metadata: Vec<(String, Expr)>,
+    /// Expression assigned to `metadata` as a whole when it is not a literal
+    dynamic_metadata: Option<String>,
+    dependencies: Vec<DependencySpec>,
+    dynamic_dependencies: Vec<String>,
+    /// Files loaded with `require_relative`, relative to the gemspec, and with `require`,
+    /// relative to `lib`
+    requires: Vec<String>,
+}
+
+impl Specification {
+    /// Last assignment of the first of `attributes` that is assigned
+    fn attribute(&self, attributes: &[&str]) -> Option<&Expr> {
+        attributes.iter().find_map(|attribute| {
+            self.attributes
+                .iter()
+                .rev()
+                .find(|(name, _)| name == attribute)
+                .map(|(_, expr)| expr)
+        })
+    }
+
+    fn read(contents: &str, tokens: &[ruby::Spanned]) -> Result<Self, SyntaxError> {
+        let mut spec = Specification::default();
+        let mut parser = Parser::new(contents, tokens);
+        for (index, token) in tokens.iter().enumerate() {
+            let Token::Ident(method) = &token.token else {
+                continue;
+            };
+            let prefix = match method.as_str() {
+                "require_relative" => "./",
+                "require" => "lib/",
+                _ => continue,
+            };
+            let path = tokens[index + 1..]
+                .iter()
+                .find(|token| token.token != Token::Punct("("));
+            if let Some(Token::Str(Some(path))) = path.map(|token| &token.token) {
+                spec.requires.push(format!("{prefix}{path}"));
+            }
+        }
+
+        let var = spec_block(&mut parser)?;
+        let mut nesting = 0usize;
+        loop {
+            parser.skip_newlines();
+            let start = parser.pos;
+            match parser.peek() {
+                None => {
+                    return Err(SyntaxError::new(
+                        parser.line(),
+                        "unterminated `Gem::Specification.new` block",
+                    ))
+                }
+                Some(Token::Ident(end)) if end == "end" && nesting == 0 => break,
+                Some(Token::Punct("}")) if nesting == 0 => break,
+                Some(Token::Ident(end)) if end == "end" => {
+                    nesting -= 1;
+                    parser.bump();
+                }
+                Some(Token::Ident(keyword)) if CONDITIONALS.contains(&keyword.as_str()) => {
+                    nesting += 1;
+                    skip_line(&mut parser);
+                }
+                Some(Token::Ident(keyword))
+                    if matches!(
+                        keyword.as_str(),
+                        "else" | "elsif" | "when" | "rescue" | "ensure"
+                    ) =>
+                {
+                    skip_line(&mut parser)
+                }
+                Some(Token::Ident(receiver))
+                    if *receiver == var
+                        && parser.peek_at(1) == Some(&Token::Punct("."))
+                        && matches!(parser.peek_at(2), Some(Token::Ident(_))) =>
+                {
+                    let Some(Token::Ident(attribute)) = parser.peek_at(2) else {
+                        unreachable!()
+                    };
+                    parser.pos += 3;
+                    spec.statement(&mut parser, attribute, start, nesting > 0);
+                }
+                _ => parser.skip(&[]),
+            }
+            // Whatever is left of a statement that is not understood
+            if !parser.at_end(&[]) {
+                parser.skip(&[]);
+            }
+            if parser.pos == start {
+                parser.bump();
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Read the statement calling `attribute` on the specification, the receiver being consumed
+    fn statement(&mut self, parser: &mut Parser, attribute: &str, start: usize, conditional: bool) {
+        let kind = match attribute {
+            "add_dependency" | "add_runtime_dependency" => Some(DependencyKind::Runtime),
+            "add_development_dependency" => Some(DependencyKind::Dev),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let parens = parser.eat("(");
+            let mut arguments = Vec::new();
+            loop {
+                arguments.push(parser.expression(&[","]));
+                if !parser.eat(",") {
+                    break;
+                }
+                parser.skip_newlines();
+            }
+            if parens {
+                parser.eat(")");
+            }
+            let strings: Option<Vec<String>> = arguments
+                .iter()
+                .map(|argument| match argument {
+                    Expr::Static(value) => value.strings(),
+                    Expr::Dynamic(_) => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|arguments| arguments.concat());
+            match strings {
+                Some(strings) if !conditional && !strings.is_empty() => {
+                    let dependency = DependencySpec::new(&strings[0], kind)
+                        .with_version(ruby::requirement(&strings[1..]));
+                    self.dependencies.push(dependency);
+                }
+                _ => self.dynamic_dependencies.push(parser.text(start)),
+            }
+            return;
+        }
+
+        if attribute == "metadata" && parser.eat("[") {
+            let key = parser.expression(&[]);
+            parser.eat("]");
+            if !parser.eat("=") {
+                return;
+            }
+            let value = parser.expression(&[]);
+            match key {
+                Expr::Static(Value::Str(key)) => {
+                    let value = match conditional {
+                        true => Expr::Dynamic(parser.text(start)),
+                        false => value,
+                    };
+                    self.metadata.push((key, value));
+                }
+                _ => {
+                    self.dynamic_metadata.get_or_insert(parser.text(start));
+                }
+            }
+            return;
+        }
+
+        if !parser.eat("=") {
+            // `spec.files << "..."` and the like change an attribute in place
+            if matches!(parser.peek(), Some(Token::Punct("<<" | "+=" | "||="))) {
+                parser.skip(&[]);
+                self.attributes
+                    .push((attribute.to_string(), Expr::Dynamic(parser.text(start))));
+            }
+            return;
+        }
+        let value = match parser.expression(&[]) {
+            _ if conditional => Expr::Dynamic(parser.text(start)),
+            value => value,
+        };
+        match (attribute, value) {
+            ("metadata", Expr::Static(Value::Hash(entries))) => {
+                for (key, value) in entries {
+                    if let Some(key) = key.as_str() {
+                        self.metadata.push((key.to_string(), Expr::Static(value)));
+                    }
+                }
+            }
+            ("metadata", Expr::Static(_)) => {}
+            ("metadata", Expr::Dynamic(expression)) => {
+                self.dynamic_metadata.get_or_insert(expression);
+            }
+            (attribute, value) => self.attributes.push((attribute.to_string(), value)),
+        }
+    }
+}
+
+/// Find `Gem::Specification.new do |spec|` and return the name of its block variable
+fn spec_block(parser: &mut Parser) -> Result<String, SyntaxError> {
+    let is = |token: Option<&Token>, expected: &Token| token == Some(expected);
+    while parser.peek().is_some() {
+        let opens = is(parser.peek(), &Token::Const("Gem".to_string()))
+            && is(parser.peek_at(1), &Token::Punct("::"))
+            && is(
+                parser.peek_at(2),
+                &Token::Const("Specification".to_string()),
+            )
+            && is(parser.peek_at(3), &Token::Punct("."))
+            && is(parser.peek_at(4), &Token::Ident("new".to_string()));
+        if !opens {
+            parser.bump();
+            continue;
+        }
+        parser.pos += 5;
+        // `Gem::Specification.new("name", "1.0") do |spec|` also passes the name and version
+        if matches!(parser.peek(), Some(Token::Punct("("))) {
+            parser.skip(&["do", "{"]);
+        }
+        if parser.is_ident("do") || parser.eat("{") {
+            if parser.is_ident("do") {
+                parser.bump();
+            }
+            if parser.eat("|") {
+                if let Some(Token::Ident(var)) = parser.bump() {
+                    if parser.eat("|") {
+                        return Ok(var.clone());
+                    }
+                }
+            }
+        }
+        break;
+    }
+    Err(SyntaxError::new(
+        parser.line(),
+        "expected a `Gem::Specification.new do |spec|` block",
+    ))
+}
+
+fn skip_line(parser: &mut Parser) {
+    while !matches!(parser.peek(), None | Some(Token::Newline)) {
+        parser.bump();
+    }
+}
+
+/// Look up the `VERSION` constant a `Confy::VERSION` expression refers to in the files the
+/// gemspec requires, or in `lib/<name>/version.rb`; the version and the file it was found in
+fn resolve_version(
+    expression: &str,
+    spec: &Specification,
+    name: &Option<String>,
+    dir: Option<&Path>,
+) -> Option<(String, String)> {
+    let dir = dir?;
+    let constant = expression.trim_end_matches(".freeze").rsplit("::").next()?;
+    let is_constant = expression
+        .trim_end_matches(".freeze")
+        .split("::")
+        .all(|segment| segment.starts_with(|c: char| c.is_uppercase()));
+    if !is_constant {
+        return None;
+    }
+    let conventional = name
+        .as_ref()
+        .map(|name| format!("lib/{}/version", name.replace('-', "/")));
+    spec.requires
+        .iter()
+        .chain(conventional.as_ref())
+        .map(|file| {
+            let file = file.trim_start_matches("./");
+            match file.ends_with(".rb") {
+                true => file.to_string(),
+                false => format!("{file}.rb"),
+            }
+        })
+        .find_map(|file| {
+            let contents = fs::read_to_string(dir.join(&file)).ok()?;
+            let tokens = ruby::lex(&contents).ok()?;
+            let version = tokens.windows(3).find_map(|window| {
+                match [&window[0].token, &window[1].token, &window[2].token] {
+                    [Token::Const(name), Token::Punct("="), Token::Str(Some(version))]
+                        if name == constant =>
+                    {
+                        Some(version.clone())
+                    }
+                    _ => None,
+                }
+            })?;
+            Some((version, file))
+        })
+}
@@ -0,0 +1,260 @@
+//! `go.mod` reader, following the [go.mod reference] grammar: one directive per line, or a
+//! parenthesized block of them. `retract` directives are validated but not kept, they describe
+//! published versions of the module rather than the project.
+//!
+//! [go.mod reference]: https://go.dev/ref/mod#go-mod-file
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec, VersionSyntax};
+use crate::syntax::SyntaxError;
+use crate::ProjectMetadata;
+use std::collections::HashMap;
+
+/// Tokens of a line, and its trailing comment
+struct Line {
+    number: usize,
+    tokens: Vec<String>,
+    comment: Option<String>,
+}
+
+impl Line {
+    fn error(&self, message: impl Into<String>) -> SyntaxError {
+        SyntaxError::new(self.number, message)
+    }
+}
+
+pub(crate) fn parse(contents: &str) -> Result<ProjectMetadata, SyntaxError> {
+    let lines = lex(contents)?;
+    let mut module = None;
+    let mut engines = HashMap::new();
+    let mut dependencies = Vec::new();
+    let mut lines = lines.iter();
+    while let Some(line) = lines.next() {
+        let Some((verb, args)) = line.tokens.split_first() else {
+            continue;
+        };
+        if args.len() == 1 && args[0] == "(" {
+            if !matches!(
+                verb.as_str(),
+                "require" | "replace" | "exclude" | "retract" | "godebug" | "tool" | "ignore"
+            ) {
+                return Err(line.error(format!("`{verb}` directives cannot be grouped")));
+            }
+            let mut closed = false;
+            for line in lines.by_ref() {
+                match line.tokens.as_slice() {
+                    [] => continue,
+                    [close] if close == ")" => {
+                        closed = true;
+                        break;
+                    }
+                    args => directive(verb, args, line, &mut dependencies)?,
+                }
+            }
+            if !closed {
+                return Err(line.error(format!("unterminated `{verb}` block")));
+            }
+            continue;
+        }
+        match verb.as_str() {
+            "module" => match args {
+                [path] if module.is_none() => module = Some(path.clone()),
+                [_] => return Err(line.error("repeated `module` directive")),
+                _ => return Err(line.error("usage: module module/path")),
+            },
+            "go" | "toolchain" => match args {
+                [version] => {
+                    engines.insert(verb.clone(), version.clone());
+                }
+                _ => return Err(line.error(format!("usage: {verb} version"))),
+            },
+            verb => directive(verb, args, line, &mut dependencies)?,
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        name: module.unwrap_or_default(),
+        engines: (!engines.is_empty()).then_some(engines),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        ..Default::default()
+    })
+}
+
+/// A directive that may appear in a block, with its verb already stripped
+fn directive(
+    verb: &str,
+    args: &[String],
+    line: &Line,
+    dependencies: &mut Vec<DependencySpec>,
+) -> Result<(), SyntaxError> {
+    match verb {
+        "require" => {
+            let [path, version] = args else {
+                return Err(line.error("usage: require module/path v1.2.3"));
+            };
+            check_version(version, line)?;
+            // `go mod tidy` marks modules only needed by dependencies
+            let indirect = line
+                .comment
+                .as_deref()
+                .is_some_and(|comment| comment.split(';').any(|part| part.trim() == "indirect"));
+            dependencies.push(
+                DependencySpec::new(path, DependencyKind::Runtime)
+                    .version(Some(version), VersionSyntax::Minimum)
+                    .group(indirect.then_some("indirect")),
+            );
+        }
+        "exclude" => {
+            let [path, version] = args else {
+                return Err(line.error("usage: exclude module/path v1.2.3"));
+            };
+            check_version(version, line)?;
+            dependencies.push(
+                DependencySpec::new(path, DependencyKind::Override)
+                    .version(Some(&format!("!={version}")), VersionSyntax::Exact)
+                    .group(Some("exclude")),
+            );
+        }
+        "replace" => {
+            let arrow = args.iter().position(|arg| arg == "=>");
+            let (old, new) =
+                match arrow {
+                    Some(arrow) => (&args[..arrow], &args[arrow + 1..]),
+                    None => return Err(line.error(
+                        "usage: replace module/path [v1.2.3] => other/module v1.4.5 | local/dir",
+                    )),
+                };
+            let (name, version) = match old {
+                [path] => (path.clone(), None),
+                [path, version] => {
+                    check_version(version, line)?;
+                    (path.clone(), Some(version.as_str()))
+                }
+                _ => return Err(line.error("replaced module must be `module/path [v1.2.3]`")),
+            };
+            let replacement = match new {
+                [path] if is_local(path) => DependencySpec::new(&name, DependencyKind::Override)
+                    .source(DependencySource::Path { path: path.clone() }),
+                [path, version] if !is_local(path) => {
+                    check_version(version, line)?;
+                    DependencySpec::new(&name, DependencyKind::Override)
+                        .version(Some(version), VersionSyntax::Exact)
+                        .source(DependencySource::Alias {
+                            registry: None,
+                            package: path.clone(),
+                            dist_tag: None,
+                        })
+                }
+                [path] => {
+                    return Err(line.error(format!("replacement module `{path}` needs a version")))
+                }
+                _ => {
+                    return Err(
+                        line.error("replacement must be `module/path v1.2.3` or a local directory")
+                    )
+                }
+            };
+            // Only a given version of the module may be replaced
+            let replacement = match version {
+                Some(version) => DependencySpec {
+                    name: format!("{name}@{version}"),
+                    ..replacement
+                },
+                None => replacement,
+            };
+            dependencies.push(replacement.group(Some("replace")));
+        }
+        "retract" => match args {
+            [version] => check_version(version, line)?,
+            [open, low, comma, high, close] if open == "[" && comma == "," && close == "]" => {
+                check_version(low, line)?;
+                check_version(high, line)?;
+            }
+            _ => return Err(line.error("usage: retract v1.2.3 | [v1.0.0, v1.9.9]")),
+        },
+        "godebug" | "tool" | "ignore" => {}
+        verb => return Err(line.error(format!("unknown directive `{verb}`"))),
+    }
+    Ok(())
+}
+
+/// Directories, as opposed to module paths, start with `./`, `../` or `/`
+fn is_local(path: &str) -> bool {
+    path.starts_with("./") || path.starts_with("../") || path.starts_with('/')
+}
+
+/// Module versions are canonical semantic versions prefixed with `v`
+fn check_version(version: &str, line: &Line) -> Result<(), SyntaxError> {
+    let valid = version
+        .strip_prefix('v')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+    match valid {
+        true => Ok(()),
+        false => Err(line.error(format!("invalid version `{version}`, expected v1.2.3"))),
+    }
+}
+
+/// Split `contents` into lines of tokens: words, quoted strings, and the `(`, `)`, `[`, `]`, `,`
+/// and `=>` punctuation
+fn lex(contents: &str) -> Result<Vec<Line>, SyntaxError> {
+    let mut lines = Vec::new();
+    for (index, text) in contents.lines().enumerate() {
+        let number = index + 1;
+        let mut tokens = Vec::new();
+        let mut comment = None;
+        let mut chars = text.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            match c {
+                c if c.is_whitespace() => {
+                    chars.next();
+                }
+                '/' if text[start..].starts_with("//") => {
+                    comment = Some(text[start + 2..].trim().to_string());
+                    break;
+                }
+                '(' | ')' | '[' | ']' | ',' => {
+                    tokens.push(c.to_string());
+                    chars.next();
+                }
+                '"' | '`' => {
+                    chars.next();
+                    let mut token = String::new();
+                    let mut closed = false;
+                    while let Some((_, next)) = chars.next() {
+                        match next {
+                            '\\' if c == '"' => token.extend(chars.next().map(|(_, c)| c)),
+                            next if next == c => {
+                                closed = true;
+                                break;
+                            }
+                            next => token.push(next),
+                        }
+                    }
+                    if !closed {
+                        return Err(SyntaxError::new(number, "unterminated quoted string"));
+                    }
+                    tokens.push(token);
+                }
+                _ => {
+                    let end = text[start..]
+                        .find(|c: char| {
+                            c.is_whitespace()
+                                || matches!(c, '(' | ')' | '[' | ']' | ',' | '"' | '`')
+                        })
+                        .map_or(text.len(), |end| start + end);
+                    let end = match text[start..end].find("//") {
+                        Some(comment) => start + comment,
+                        None => end,
+                    };
+                    tokens.push(text[start..end].to_string());
+                    while chars.next_if(|&(offset, _)| offset < end).is_some() {}
+                }
+            }
+        }
+        lines.push(Line {
+            number,
+            tokens,
+            comment,
+        });
+    }
+    Ok(lines)
+}
@@ -0,0 +1,684 @@
+//! Gradle builds, `build.gradle` and `build.gradle.kts`, read without running the script.
+//!
+//! The `group`, `version` and `description` assignments and the calls of the `dependencies` and
+//! `plugins` blocks are read in both the Groovy and the Kotlin DSL, with the local variables,
+//! `ext` properties and `gradle.properties` entries their values refer to. `libs` accessors are
+//! resolved against the version catalog of the build, `gradle/libs.versions.toml`, and the
+//! settings script names the root project and lists the projects it includes. Values that are
+//! computed are listed in `dynamic` with the expression computing them.
+use crate::dependency::{
+    DependencyKind, DependencySource, DependencySpec, VersionReq, VersionSyntax,
+};
+use crate::npm::SpecifierError;
+use crate::util::{canonical, located, relative_to};
+use crate::{FieldValue, MetadataError, ProjectMetadata};
+use catalog::{Catalog, Version};
+use lexer::Part;
+use script::{Arg, Declaration, Expr, Located, Script};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+mod catalog;
+mod lexer;
+mod script;
+
+/// Settings scripts, the directory of which is the root of the build
+const SETTINGS: [&str; 2] = ["settings.gradle.kts", "settings.gradle"];
+/// Project properties read into fields rather than `properties`
+const FIELDS: [&str; 3] = ["group", "version", "description"];
+/// Nesting limit of variables referring to other variables
+const MAX_DEPTH: usize = 16;
+
+/// Read the script at `script_path`, with the settings script, `gradle.properties` files and
+/// version catalog of its build, which are found from that path
+pub(crate) fn from_script(
+    contents: &str,
+    script_path: Option<&Path>,
+) -> Result<ProjectMetadata, MetadataError> {
+    let script = read_script(contents, script_path)?;
+    let script_name = script_path
+        .and_then(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dir = script_path.map(|path| path.parent().unwrap_or(Path::new("")));
+    let settings_path = dir.and_then(|dir| {
+        canonical(dir).ancestors().find_map(|ancestor| {
+            SETTINGS
+                .iter()
+                .map(|file| ancestor.join(file))
+                .find(|candidate| candidate.is_file())
+        })
+    });
+    let root = settings_path
+        .as_ref()
+        .and_then(|path| path.parent())
+        .map(Path::to_path_buf)
+        .or_else(|| dir.map(canonical));
+    let is_root = root.is_some() && root == dir.map(canonical);
+
+    // The properties of the root project apply to the whole build, a project's own override them
+    let mut properties = HashMap::new();
+    if let (Some(root), Some(dir)) = (&root, dir) {
+        let mut property_files = vec![root.join("gradle.properties")];
+        if !is_root {
+            property_files.push(dir.join("gradle.properties"));
+        }
+        for path in property_files.iter().filter(|path| path.is_file()) {
+            let contents = fs::read_to_string(path)
+                .map_err(|_| MetadataError::ReadError(path.display().to_string()))?;
+            let source = relative_to(path, dir);
+            for (name, value) in java_properties(&contents) {
+                properties.insert(name, (value, source.clone()));
+            }
+        }
+    }
+
+    let catalog_path = root.map(|root| root.join("gradle").join("libs.versions.toml"));
+    let catalog = match catalog_path.filter(|path| path.is_file()) {
+        Some(catalog_path) => {
+            let contents = fs::read_to_string(&catalog_path)
+                .map_err(|_| MetadataError::ReadError(catalog_path.display().to_string()))?;
+            let toml: toml::Value = toml::from_str(&contents)
+                .map_err(|_| MetadataError::TomlParseError(catalog_path.display().to_string()))?;
+            let catalog = Catalog::parse(&toml).map_err(|reason| {
+                MetadataError::ParseError(
+                    "Gradle build".to_string(),
+                    format!("{}: {reason}", catalog_path.display()),
+                )
+            })?;
+            Some(catalog)
+        }
+        None => None,
+    };
+
+    let build = Build {
+        script: &script,
+        script_name: &script_name,
+        script_path,
+        properties: &properties,
+        catalog: catalog.as_ref(),
+    };
+    let mut fields = Fields::default();
+
+    // Projects are named after their directory unless the settings script of the root names it
+    let mut project_name = dir
+        .and_then(|dir| dir.canonicalize().ok())
+        .and_then(|dir| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_default();
+    let mut workspace = Vec::new();
+    if let (Some(settings_path), Some(dir), true) = (&settings_path, dir, is_root) {
+        let contents = fs::read_to_string(settings_path)
+            .map_err(|_| MetadataError::ReadError(settings_path.display().to_string()))?;
+        let settings = read_script(&contents, Some(settings_path))?;
+        let settings_name = relative_to(settings_path, dir);
+        let settings_build = Build {
+            script: &settings,
+            script_name: &settings_name,
+            ..build
+        };
+        if let Some(name) = settings_build.field("name", "name", &mut fields) {
+            project_name = name;
+        }
+        // `:core:api` is the project in `core/api`
+        workspace.extend(settings.includes.iter().filter_map(|include| {
+            let path = settings_build.resolve(&include.value, 0)?;
+            Some(path.trim_start_matches(':').replace(':', "/"))
+        }));
+    }
+    let group = build.field("group", "name", &mut fields);
+    let version = build.field("version", "version", &mut fields);
+    let description = build.field("description", "description", &mut fields);
+
+    let mut dependencies = Vec::new();
+    for declaration in &script.declarations {
+        let configuration = declaration.value.configuration.as_str();
+        let kind = match configuration {
+            _ if declaration.value.buildscript => DependencyKind::Build,
+            "compileOnly" | "compileOnlyApi" => DependencyKind::Peer,
+            "annotationProcessor" | "kapt" | "ksp" => DependencyKind::Build,
+            _ if configuration.starts_with("test")
+                || configuration.starts_with("androidTest")
+                || configuration.contains("Test") =>
+            {
+                DependencyKind::Dev
+            }
+            _ => DependencyKind::Runtime,
+        };
+        let group = (configuration != "implementation").then_some(configuration);
+        match build.declaration(declaration)? {
+            Some(declared) => dependencies.extend(
+                declared
+                    .into_iter()
+                    .map(|dependency| dependency.kind(kind).group(group)),
+            ),
+            None => fields.dynamic("dependencies", &declaration.text),
+        }
+    }
+    for plugin in &script.plugins {
+        match build.plugin(plugin)? {
+            Some(Some(dependency)) => dependencies.push(dependency),
+            // Core plugins ship with Gradle
+            Some(None) => {}
+            None => fields.dynamic("dependencies", &plugin.text),
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let engines = script
+        .java
+        .as_ref()
+        .and_then(|java| build.java_version(&java.value))
+        .map(|java| HashMap::from([("java".to_string(), java)]));
+
+    let mut extra: HashMap<String, String> = properties
+        .iter()
+        .filter(|(name, _)| !FIELDS.contains(&name.as_str()) && !name.starts_with("org.gradle."))
+        .map(|(name, (value, _))| (name.clone(), value.clone()))
+        .collect();
+    for name in &script.extra {
+        if let Some(value) = build.lookup(name, 0) {
+            extra.insert(name.clone(), value);
+        }
+    }
+
+    Ok(ProjectMetadata {
+        name: match group {
+            Some(group) if !group.is_empty() => format!("{group}:{project_name}"),
+            _ => project_name,
+        },
+        version: version.unwrap_or_default(),
+        description,
+        engines,
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        workspace: (!workspace.is_empty()).then_some(workspace),
+        properties: (!extra.is_empty()).then_some(extra),
+        dynamic: (!fields.dynamic.is_empty()).then_some(fields.dynamic),
+        sources: (!fields.sources.is_empty()).then_some(fields.sources),
+        ..Default::default()
+    })
+}
+
+fn read_script(contents: &str, path: Option<&Path>) -> Result<Script, MetadataError> {
+    lexer::lex(contents)
+        .and_then(|tokens| script::parse(contents, &tokens))
+        .map_err(|err| MetadataError::ParseError("Gradle build".to_string(), located(path, err)))
+}
+
+/// Fields that are computed, and where the others were read from
+#[derive(Default)]
+struct Fields {
+    dynamic: Vec<String>,
+    sources: HashMap<String, FieldValue>,
+}
+
+impl Fields {
+    fn dynamic(&mut self, field: &str, source: &str) {
+        if !self.dynamic.iter().any(|dynamic| dynamic == field) {
+            self.dynamic.push(field.to_string());
+        }
+        self.sources
+            .entry(field.to_string())
+            .or_insert_with(|| FieldValue::Dynamic {
+                source: source.to_string(),
+            });
+    }
+}
+
+/// A script with the properties and the catalog of its build, resolving what it refers to
+#[derive(Clone, Copy)]
+struct Build<'a> {
+    script: &'a Script,
+    /// Name of the script in `sources`
+    script_name: &'a str,
+    script_path: Option<&'a Path>,
+    /// `gradle.properties` entries, with the file setting them
+    properties: &'a HashMap<String, (String, String)>,
+    catalog: Option<&'a Catalog>,
+}
+
+impl Build<'_> {
+    /// Value of the project property `property`: assigned in the script, or set in
+    /// `gradle.properties`. Assignments that cannot be resolved make `field` dynamic.
+    fn field(&self, property: &str, field: &str, fields: &mut Fields) -> Option<String> {
+        let assignment = self
+            .script
+            .assignments
+            .iter()
+            .rev()
+            .find(|(name, _)| name == property);
+        match assignment {
+            Some((_, value)) => match self.resolve(&value.value, 0) {
+                Some(resolved) => {
+                    let source = FieldValue::from_table(self.script_name);
+                    fields.sources.entry(field.to_string()).or_insert(source);
+                    Some(resolved)
+                }
+                None => {
+                    fields.dynamic(field, &value.text);
+                    None
+                }
+            },
+            None => {
+                let (value, source) = self.properties.get(property)?;
+                let source = FieldValue::from_table(source);
+                fields.sources.entry(field.to_string()).or_insert(source);
+                Some(value.clone())
+            }
+        }
+    }
+
+    /// Value of a variable, an `ext` property, a `gradle.properties` entry or a project property
+    fn lookup(&self, name: &str, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let variable = self.script.variables.iter().rev().find(|(n, _)| n == name);
+        if let Some((_, value)) = variable {
+            return self.resolve(&value.value, depth + 1);
+        }
+        if let Some((value, _)) = self.properties.get(name) {
+            return Some(value.clone());
+        }
+        let assignment = self
+            .script
+            .assignments
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name);
+        self.resolve(&assignment?.1.value, depth + 1)
+    }
+
+    /// Value a dotted reference evaluates to
+    fn reference(&self, path: &[&str], depth: usize) -> Option<String> {
+        match path {
+            ["libs", "versions", alias @ ..] if !alias.is_empty() => {
+                let version = self.catalog?.versions.get(&alias.join("."))?;
+                version.constraint().map(|version| version.to_string())
+            }
+            ["project" | "rootProject" | "ext" | "extra", rest @ ..] if !rest.is_empty() => {
+                self.reference(rest, depth)
+            }
+            // `gradle.properties` keys are often dotted
+            path => self.lookup(&path.join("."), depth),
+        }
+    }
+
+    fn resolve(&self, expr: &Expr, depth: usize) -> Option<String> {
+        match expr {
+            Expr::Str(parts) => parts
+                .iter()
+                .map(|part| match part {
+                    Part::Text(text) => Some(text.clone()),
+                    Part::Var(name) => {
+                        let path: Vec<&str> = name.split('.').collect();
+                        self.reference(&path, depth)
+                    }
+                    Part::Expr(_) => None,
+                })
+                .collect(),
+            Expr::Number(number) => Some(number.clone()),
+            Expr::Ref(path) => {
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                self.reference(&path, depth)
+            }
+            Expr::Call { name, args } => match name.as_str() {
+                "property" | "findProperty" | "project.property" | "project.findProperty" => {
+                    let name = self.resolve(&args.first()?.value, depth)?;
+                    self.lookup(&name, depth + 1)
+                }
+                _ => None,
+            },
+            Expr::Other => None,
+        }
+    }
+
+    /// Dependencies of a call in `dependencies`, `None` when they are computed
+    fn declaration(
+        &self,
+        declaration: &Located<Declaration>,
+    ) -> Result<Option<Vec<DependencySpec>>, MetadataError> {
+        let args = &declaration.value.args;
+        let named = |key: &str| {
+            args.iter()
+                .find(|arg| arg.name.as_deref() == Some(key))
+                .map(|arg| self.resolve(&arg.value, 0))
+        };
+        // `group: "...", name: "...", version: "..."`
+        if let Some(name) = named("name") {
+            let (Some(Some(group)), Some(name)) = (named("group"), name) else {
+                return Ok(None);
+            };
+            let version = match named("version") {
+                Some(Some(version)) => Some(version),
+                Some(None) => return Ok(None),
+                None => None,
+            };
+            let name = format!("{group}:{name}");
+            let version = version
+                .map(|version| self.version(&name, &version))
+                .transpose()?;
+            let dependency =
+                DependencySpec::new(&name, DependencyKind::Runtime).with_version(version);
+            return Ok(Some(vec![dependency]));
+        }
+        match args.iter().find(|arg| arg.name.is_none()) {
+            Some(arg) => self.dependency(&arg.value, declaration.line),
+            None => Ok(Some(Vec::new())),
+        }
+    }
+
+    fn dependency(
+        &self,
+        expr: &Expr,
+        line: usize,
+    ) -> Result<Option<Vec<DependencySpec>>, MetadataError> {
+        match expr {
+            Expr::Call { name, args } => {
+                let positional = |index: usize| {
+                    args.iter()
+                        .filter(|arg| arg.name.is_none())
+                        .nth(index)
+                        .map(|arg| &arg.value)
+                };
+                match name.as_str() {
+                    // Platforms and test fixtures of a dependency are declared with the dependency
+                    "platform" | "enforcedPlatform" | "testFixtures" => match positional(0) {
+                        Some(inner) => self.dependency(inner, line),
+                        None => Ok(None),
+                    },
+                    "project" => {
+                        let path = args
+                            .iter()
+                            .find(|arg| arg.name.as_deref() == Some("path"))
+                            .map(|arg| &arg.value)
+                            .or(positional(0))
+                            .and_then(|path| self.resolve(path, 0));
+                        Ok(path.map(|path| {
+                            let name = path.rsplit(':').next().unwrap_or(&path);
+                            vec![DependencySpec::new(name, DependencyKind::Runtime)
+                                .source(DependencySource::Workspace)]
+                        }))
+                    }
+                    // `kotlin("reflect")` is `org.jetbrains.kotlin:kotlin-reflect`
+                    "kotlin" => {
+                        let Some(module) = positional(0).and_then(|module| self.resolve(module, 0))
+                        else {
+                            return Ok(None);
+                        };
+                        let name = format!("org.jetbrains.kotlin:kotlin-{module}");
+                        let version = match positional(1).map(|version| self.resolve(version, 0)) {
+                            Some(Some(version)) => Some(self.version(&name, &version)?),
+                            Some(None) => return Ok(None),
+                            None => None,
+                        };
+                        let dependency = DependencySpec::new(&name, DependencyKind::Runtime)
+                            .with_version(version);
+                        Ok(Some(vec![dependency]))
+                    }
+                    // Local files and the APIs of Gradle itself are not packages
+                    "files" | "fileTree" | "gradleApi" | "gradleTestKit" | "localGroovy" => {
+                        Ok(Some(Vec::new()))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Expr::Ref(path) if path.first().map(String::as_str) == Some("libs") => {
+                self.catalog_dependency(&path[1..], line).map(Some)
+            }
+            expr => match self.resolve(expr, 0) {
+                Some(notation) => self
+                    .notation(&notation, line)
+                    .map(|dependency| Some(vec![dependency])),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// `libs.foo.bar` or `libs.bundles.foo`
+    fn catalog_dependency(
+        &self,
+        path: &[String],
+        line: usize,
+    ) -> Result<Vec<DependencySpec>, MetadataError> {
+        let accessor = path.join(".");
+        let unknown = || {
+            MetadataError::ParseError(
+                "Gradle build".to_string(),
+                located(
+                    self.script_path,
+                    format!("{line}: `libs.{accessor}` is not in the version catalog"),
+                ),
+            )
+        };
+        let catalog = self.catalog.ok_or_else(unknown)?;
+        let aliases = match path.split_first() {
+            Some((bundles, bundle)) if bundles == "bundles" => catalog
+                .bundles
+                .get(&bundle.join("."))
+                .ok_or_else(unknown)?
+                .clone(),
+            _ => vec![accessor.clone()],
+        };
+        let mut dependencies = Vec::new();
+        for alias in aliases {
+            let library = catalog.libraries.get(&alias).ok_or_else(unknown)?;
+            let name = format!("{}:{}", library.group, library.name);
+            let version = library
+                .version
+                .as_ref()
+                .and_then(|version| self.catalog_version(&name, version).transpose())
+                .transpose()?;
+            dependencies
+                .push(DependencySpec::new(&name, DependencyKind::Runtime).with_version(version));
+        }
+        Ok(dependencies)
+    }
+
+    /// `group:name:version`, with an optional classifier and `@extension`
+    fn notation(&self, notation: &str, line: usize) -> Result<DependencySpec, MetadataError> {
+        let coordinate = notation.split('@').next().unwrap_or(notation);
+        let parts: Vec<&str> = coordinate.split(':').collect();
+        let (group, name, version) = match parts.as_slice() {
+            [group, name] => (group, name, None),
+            [group, name, version, ..] => (group, name, Some(*version)),
+            _ => {
+                return Err(MetadataError::ParseError(
+                    "Gradle build".to_string(),
+                    located(
+                        self.script_path,
+                        format!("{line}: `{notation}` is not a `group:name:version` dependency"),
+                    ),
+                ))
+            }
+        };
+        let name = format!("{group}:{name}");
+        let version = version
+            .filter(|version| !version.is_empty())
+            .map(|version| self.version(&name, version))
+            .transpose()?;
+        Ok(DependencySpec::new(&name, DependencyKind::Runtime).with_version(version))
+    }
+
+    fn version(&self, name: &str, version: &str) -> Result<VersionReq, MetadataError> {
+        gradle_version(version).map_err(|reason| {
+            MetadataError::DependencySpecifierError(
+                name.to_string(),
+                SpecifierError::InvalidRange(version.to_string(), reason),
+            )
+        })
+    }
+
+    /// A catalog version: `strictly` constraints are exact, the others are minimums
+    fn catalog_version(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> Result<Option<VersionReq>, MetadataError> {
+        let Some(constraint) = version.constraint() else {
+            return Ok(None);
+        };
+        match version {
+            Version::Rich {
+                strictly: Some(_), ..
+            } => strict_version(constraint).map(Some).map_err(|reason| {
+                MetadataError::DependencySpecifierError(
+                    name.to_string(),
+                    SpecifierError::InvalidRange(constraint.to_string(), reason),
+                )
+            }),
+            _ => self.version(name, constraint).map(Some),
+        }
+    }
+
+    /// Plugin applied by a call in `plugins`, `Some(None)` for the core plugins that ship with
+    /// Gradle and `None` when the plugin is computed
+    fn plugin(
+        &self,
+        plugin: &Located<script::Plugin>,
+    ) -> Result<Option<Option<DependencySpec>>, MetadataError> {
+        let Expr::Call { name, args } = &plugin.value.call else {
+            return Ok(None);
+        };
+        let Some(Arg { value, .. }) = args.first() else {
+            return Ok(None);
+        };
+        let id = match (name.as_str(), value) {
+            ("alias", Expr::Ref(path)) => {
+                let alias = match path.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+                    ["libs", "plugins", ref alias @ ..] if !alias.is_empty() => alias.join("."),
+                    _ => return Ok(None),
+                };
+                let catalog_plugin = self
+                    .catalog
+                    .and_then(|catalog| catalog.plugins.get(&alias))
+                    .ok_or_else(|| {
+                        MetadataError::ParseError(
+                            "Gradle build".to_string(),
+                            located(
+                                self.script_path,
+                                format!(
+                                    "{}: `libs.plugins.{alias}` is not in the version catalog",
+                                    plugin.line
+                                ),
+                            ),
+                        )
+                    })?;
+                let version = catalog_plugin
+                    .version
+                    .as_ref()
+                    .and_then(|version| {
+                        self.catalog_version(&catalog_plugin.id, version)
+                            .transpose()
+                    })
+                    .transpose()?;
+                let dependency = DependencySpec::new(&catalog_plugin.id, DependencyKind::Build)
+                    .with_version(version)
+                    .group(Some("plugins"));
+                return Ok(Some(Some(dependency)));
+            }
+            ("id", value) => match self.resolve(value, 0) {
+                Some(id) => id,
+                None => return Ok(None),
+            },
+            ("kotlin", value) => match self.resolve(value, 0) {
+                Some(id) => format!("org.jetbrains.kotlin.{id}"),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        if !id.contains('.') {
+            return Ok(Some(None));
+        }
+        let version = match &plugin.value.version {
+            Some(version) => match self.resolve(version, 0) {
+                Some(version) => Some(version),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+        let version = version
+            .map(|version| self.version(&id, &version))
+            .transpose()?;
+        let dependency = DependencySpec::new(&id, DependencyKind::Build)
+            .with_version(version)
+            .group(Some("plugins"));
+        Ok(Some(Some(dependency)))
+    }
+
+    /// `17`, `JavaLanguageVersion.of(17)`, `JavaVersion.VERSION_1_8` or `JavaVersion.toVersion(11)`
+    fn java_version(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Call { name, args }
+                if name == "JavaLanguageVersion.of" || name == "JavaVersion.toVersion" =>
+            {
+                self.resolve(&args.first()?.value, 0)
+            }
+            Expr::Ref(path) if path.len() == 2 && path[0] == "JavaVersion" => {
+                let version = path[1].strip_prefix("VERSION_")?;
+                Some(version.replace('_', "."))
+            }
+            expr => self.resolve(expr, 0),
+        }
+    }
+}
+
+/// Parse a Gradle version: a bare version is a minimum, `1.2.+` takes the latest `1.2.x`, `+` and
+/// `latest.release` any version, `1.2!!` is strict and ranges use the interval notation
+fn gradle_version(version: &str) -> Result<VersionReq, String> {
+    let trimmed = version.trim();
+    if let Some(strict) = trimmed.strip_suffix("!!") {
+        return strict_version(strict).map(|parsed| parsed.with_raw(version));
+    }
+    match trimmed.strip_suffix('+') {
+        Some("") => Ok(VersionReq::new(version, vec![Vec::new()])),
+        Some(prefix) => {
+            let prefix = prefix.trim_end_matches('.');
+            Ok(VersionReq::parse(&format!("~{prefix}"), VersionSyntax::Minimum).with_raw(version))
+        }
+        None if trimmed.starts_with("latest.") => Ok(VersionReq::new(version, vec![Vec::new()])),
+        None => VersionReq::parse_intervals(version, VersionSyntax::Minimum),
+    }
+}
+
+/// A `strictly` constraint, where a bare version is the only one accepted
+fn strict_version(version: &str) -> Result<VersionReq, String> {
+    VersionReq::parse_intervals(version, VersionSyntax::Exact)
+}
+
+/// Entries of a Java properties file, such as `gradle.properties`
+fn java_properties(contents: &str) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+    let mut lines = contents.lines();
+    while let Some(line) = lines.next() {
+        let mut line = line.trim_start().to_string();
+        if line.is_empty() || line.starts_with(['#', '!']) {
+            continue;
+        }
+        // A line ending with an odd number of backslashes continues on the next one
+        while (line.len() - line.trim_end_matches('\\').len()) % 2 == 1 {
+            line.pop();
+            match lines.next() {
+                Some(next) => line.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+        let split = line
+            .char_indices()
+            .find(|&(index, c)| {
+                matches!(c, '=' | ':' | ' ' | '\t') && !line[..index].ends_with('\\')
+            })
+            .map_or(line.len(), |(index, _)| index);
+        let key = line[..split].replace('\\', "");
+        let value = line[split..]
+            .trim_start_matches([' ', '\t'])
+            .strip_prefix(['=', ':'])
+            .unwrap_or(line[split..].trim_start_matches([' ', '\t']))
+            .trim();
+        entries.push((key, value.to_string()));
+    }
+    entries
+}
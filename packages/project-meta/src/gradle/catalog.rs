@@ -0,0 +1,198 @@
+//! Version catalogs, `gradle/libs.versions.toml`, which build scripts refer to through the
+//! generated `libs` accessors.
+use std::collections::HashMap;
+
+/// A version declaration: a plain version, or a rich version with its constraints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Version {
+    Plain(String),
+    Rich {
+        strictly: Option<String>,
+        require: Option<String>,
+        prefer: Option<String>,
+    },
+}
+
+impl Version {
+    /// The constraint the resolution honours: `strictly`, then `require`, then `prefer`
+    pub(super) fn constraint(&self) -> Option<&str> {
+        match self {
+            Version::Plain(version) => Some(version),
+            Version::Rich {
+                strictly,
+                require,
+                prefer,
+            } => strictly
+                .as_deref()
+                .or(require.as_deref())
+                .or(prefer.as_deref()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Library {
+    pub(super) group: String,
+    pub(super) name: String,
+    pub(super) version: Option<Version>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Plugin {
+    pub(super) id: String,
+    pub(super) version: Option<Version>,
+}
+
+/// A parsed catalog, keyed by accessor: aliases with `-` and `_` replaced by `.`, as the
+/// generated `libs.foo.bar` accessors spell them
+#[derive(Debug, Default)]
+pub(super) struct Catalog {
+    pub(super) versions: HashMap<String, Version>,
+    pub(super) libraries: HashMap<String, Library>,
+    /// Aliases of the libraries in each bundle
+    pub(super) bundles: HashMap<String, Vec<String>>,
+    pub(super) plugins: HashMap<String, Plugin>,
+}
+
+pub(super) fn accessor(alias: &str) -> String {
+    alias.replace(['-', '_'], ".")
+}
+
+impl Catalog {
+    pub(super) fn parse(toml: &toml::Value) -> Result<Self, String> {
+        let table = |key: &str| toml.get(key).and_then(|v| v.as_table());
+        let mut catalog = Catalog::default();
+        for (alias, version) in table("versions").into_iter().flatten() {
+            let version = rich_version(version).ok_or_else(|| {
+                format!("invalid version `{alias}`: expected a string or a table")
+            })?;
+            catalog.versions.insert(accessor(alias), version);
+        }
+        for (alias, library) in table("libraries").into_iter().flatten() {
+            let library = catalog
+                .library(library)
+                .map_err(|reason| format!("invalid library `{alias}`: {reason}"))?;
+            catalog.libraries.insert(accessor(alias), library);
+        }
+        for (alias, bundle) in table("bundles").into_iter().flatten() {
+            let members = bundle
+                .as_array()
+                .ok_or_else(|| format!("invalid bundle `{alias}`: expected an array"))?;
+            let mut libraries = Vec::new();
+            for member in members {
+                let library = member
+                    .as_str()
+                    .map(accessor)
+                    .filter(|library| catalog.libraries.contains_key(library));
+                match library {
+                    Some(library) => libraries.push(library),
+                    None => {
+                        return Err(format!(
+                            "invalid bundle `{alias}`: `{}` is not a library of the catalog",
+                            member
+                                .as_str()
+                                .map_or_else(|| member.to_string(), str::to_string)
+                        ))
+                    }
+                }
+            }
+            catalog.bundles.insert(accessor(alias), libraries);
+        }
+        for (alias, plugin) in table("plugins").into_iter().flatten() {
+            let plugin = catalog
+                .plugin(plugin)
+                .map_err(|reason| format!("invalid plugin `{alias}`: {reason}"))?;
+            catalog.plugins.insert(accessor(alias), plugin);
+        }
+        Ok(catalog)
+    }
+
+    /// `"group:name:version"`, or a table with `module` or `group` and `name`, and a `version`
+    fn library(&self, library: &toml::Value) -> Result<Library, String> {
+        if let Some(notation) = library.as_str() {
+            let mut parts = notation.splitn(3, ':');
+            return match (parts.next(), parts.next(), parts.next()) {
+                (Some(group), Some(name), version) if !group.is_empty() && !name.is_empty() => {
+                    Ok(Library {
+                        group: group.to_string(),
+                        name: name.to_string(),
+                        version: version.map(|version| Version::Plain(version.to_string())),
+                    })
+                }
+                _ => Err(format!(
+                    "`{notation}` is not a `group:name:version` coordinate"
+                )),
+            };
+        }
+        let get = |key: &str| library.get(key).and_then(|v| v.as_str());
+        let (group, name) = match (get("module"), get("group"), get("name")) {
+            (Some(module), ..) => module
+                .split_once(':')
+                .ok_or_else(|| format!("`{module}` is not a `group:name` module"))?,
+            (None, Some(group), Some(name)) => (group, name),
+            _ => return Err("expected a `module`, or a `group` and a `name`".to_string()),
+        };
+        Ok(Library {
+            group: group.to_string(),
+            name: name.to_string(),
+            version: self.version(library.get("version"))?,
+        })
+    }
+
+    /// `"id:version"`, or a table with an `id` and a `version`
+    fn plugin(&self, plugin: &toml::Value) -> Result<Plugin, String> {
+        if let Some(notation) = plugin.as_str() {
+            let (id, version) = notation
+                .split_once(':')
+                .ok_or_else(|| format!("`{notation}` is not an `id:version` notation"))?;
+            return Ok(Plugin {
+                id: id.to_string(),
+                version: Some(Version::Plain(version.to_string())),
+            });
+        }
+        let id = plugin
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "expected an `id`".to_string())?;
+        Ok(Plugin {
+            id: id.to_string(),
+            version: self.version(plugin.get("version"))?,
+        })
+    }
+
+    /// A version, a rich version, or `{ ref = "..." }` naming one of `[versions]`
+    fn version(&self, version: Option<&toml::Value>) -> Result<Option<Version>, String> {
+        let Some(version) = version else {
+            return Ok(None);
+        };
+        if let Some(reference) = version.get("ref").and_then(|v| v.as_str()) {
+            return self
+                .versions
+                .get(&accessor(reference))
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| format!("unknown version reference `{reference}`"));
+        }
+        rich_version(version)
+            .map(Some)
+            .ok_or_else(|| "expected a version string or table".to_string())
+    }
+}
+
+fn rich_version(version: &toml::Value) -> Option<Version> {
+    if let Some(version) = version.as_str() {
+        return Some(Version::Plain(version.to_string()));
+    }
+    let table = version.as_table()?;
+    let get = |key: &str| {
+        table
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+    Some(Version::Rich {
+        strictly: get("strictly"),
+        require: get("require"),
+        prefer: get("prefer"),
+    })
+}
@@ -0,0 +1,188 @@
+//! Tokens of Groovy and Kotlin build scripts, whose syntax is close enough for the statements
+//! read here to share a lexer.
+use crate::syntax::SyntaxError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token {
+    /// Identifiers and keywords, Kotlin backquoted names included
+    Ident(String),
+    Str(Vec<Part>),
+    Number(String),
+    Punct(&'static str),
+    Newline,
+}
+
+/// Piece of a string literal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Part {
+    Text(String),
+    /// `$name` or `${name}`, where the name may be dotted
+    Var(String),
+    /// `${...}` around anything else
+    Expr(String),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Spanned {
+    pub(super) token: Token,
+    /// 1-based line the token starts on
+    pub(super) line: usize,
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+/// Punctuation of more than one character, longest first
+const OPERATORS: [&str; 14] = [
+    "?.", "::", "->", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=", "..", "?:", "!!",
+];
+const SINGLE_PUNCTUATION: &str = "{}()[],.:;=+-*/%<>!?&|@~^";
+
+pub(super) fn lex(contents: &str) -> Result<Vec<Spanned>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+    let error = |line: usize, message: &str| SyntaxError::new(line, message);
+    while let Some(c) = contents[pos..].chars().next() {
+        let start = pos;
+        let token_line = line;
+        let rest = &contents[pos..];
+        let token = if c == '\n' {
+            line += 1;
+            pos += 1;
+            Token::Newline
+        } else if c.is_whitespace() {
+            pos += c.len_utf8();
+            continue;
+        } else if rest.starts_with("//") || (pos == 0 && rest.starts_with("#!")) {
+            pos += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment
+                .find("*/")
+                .ok_or_else(|| error(line, "unterminated comment"))?;
+            line += rest[..end + 4].matches('\n').count();
+            pos += end + 4;
+            continue;
+        } else if c == '\\' && rest[1..].starts_with('\n') {
+            // Line continuation
+            pos += 1;
+            continue;
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            pos += len;
+            Token::Ident(rest[..len].to_string())
+        } else if c == '`' {
+            let end = rest[1..]
+                .find(['`', '\n'])
+                .filter(|end| rest[1 + end..].starts_with('`'))
+                .ok_or_else(|| error(line, "unterminated backquoted name"))?;
+            pos += end + 2;
+            Token::Ident(rest[1..1 + end].to_string())
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            // `1..3` is a range, not a number
+            let len = rest[..len].find("..").unwrap_or(len);
+            pos += len;
+            Token::Number(rest[..len].trim_end_matches('.').to_string())
+        } else if c == '"' || c == '\'' {
+            let (parts, len, lines) =
+                string(rest).ok_or_else(|| error(line, "unterminated string"))?;
+            pos += len;
+            line += lines;
+            Token::Str(parts)
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            pos += op.len();
+            Token::Punct(op)
+        } else if let Some(index) = SINGLE_PUNCTUATION.find(c) {
+            pos += 1;
+            Token::Punct(&SINGLE_PUNCTUATION[index..index + 1])
+        } else {
+            return Err(error(line, &format!("unexpected character `{c}`")));
+        };
+        tokens.push(Spanned {
+            token,
+            line: token_line,
+            start,
+            end: pos,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Read the string literal `rest` starts with: its parts, its length and the newlines in it.
+/// Single quoted Groovy strings do not interpolate, triple quoted ones span lines.
+fn string(rest: &str) -> Option<(Vec<Part>, usize, usize)> {
+    let quote = match rest {
+        _ if rest.starts_with("\"\"\"") => "\"\"\"",
+        _ if rest.starts_with("'''") => "'''",
+        _ if rest.starts_with('"') => "\"",
+        _ => "'",
+    };
+    let interpolates = quote.starts_with('"');
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut lines = 0;
+    let mut offset = quote.len();
+    while let Some(c) = rest[offset..].chars().next() {
+        let after = &rest[offset..];
+        if after.starts_with(quote) {
+            if !text.is_empty() || parts.is_empty() {
+                parts.push(Part::Text(text));
+            }
+            return Some((parts, offset + quote.len(), lines));
+        }
+        match c {
+            '\n' if quote.len() == 1 => return None,
+            '\\' => {
+                let escaped = after[1..].chars().next()?;
+                match escaped {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    '\n' => lines += 1,
+                    escaped => text.push(escaped),
+                }
+                offset += 1 + escaped.len_utf8();
+                continue;
+            }
+            '$' if interpolates && after[1..].starts_with('{') => {
+                let end = after.find('}')?;
+                let inner = after[2..end].trim();
+                let is_name = !inner.is_empty()
+                    && inner.split('.').all(|part| {
+                        !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+                    });
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(match is_name {
+                    true => Part::Var(inner.to_string()),
+                    false => Part::Expr(inner.to_string()),
+                });
+                offset += end + 1;
+                continue;
+            }
+            '$' if interpolates
+                && after[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') =>
+            {
+                let len = after[1..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len() - 1);
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                parts.push(Part::Var(after[1..1 + len].to_string()));
+                offset += 1 + len;
+                continue;
+            }
+            '\n' => lines += 1,
+            _ => {}
+        }
+        text.push(c);
+        offset += c.len_utf8();
+    }
+    None
+}
@@ -0,0 +1,477 @@
+//! Statements of a build or settings script that describe the project, read without running
+//! the script: assignments, calls in the `dependencies` and `plugins` blocks, and the local
+//! variables and extra properties their values refer to.
+use super::lexer::{Part, Spanned, Token};
+use crate::syntax::SyntaxError;
+
+/// An expression as far as it can be read statically
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Expr {
+    Str(Vec<Part>),
+    Number(String),
+    /// Dotted reference, such as `libs.androidx.core` or `kotlinVersion`
+    Ref(Vec<String>),
+    Call {
+        name: String,
+        args: Vec<Arg>,
+    },
+    /// Anything else
+    Other,
+}
+
+/// Argument of a call, named with Groovy `name: value` or Kotlin `name = value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Arg {
+    pub(super) name: Option<String>,
+    pub(super) value: Expr,
+}
+
+/// An expression with where it was written
+#[derive(Debug, Clone)]
+pub(super) struct Located<T> {
+    pub(super) value: T,
+    pub(super) text: String,
+    pub(super) line: usize,
+}
+
+/// Call in a `dependencies` block
+#[derive(Debug, Clone)]
+pub(super) struct Declaration {
+    pub(super) configuration: String,
+    pub(super) args: Vec<Arg>,
+    /// Declared in `buildscript`, for the classpath of the script itself
+    pub(super) buildscript: bool,
+}
+
+/// Call in a `plugins` block: `id("...")`, `kotlin("...")` or `alias(libs.plugins...)`, with
+/// the version given by the infix `version`
+#[derive(Debug, Clone)]
+pub(super) struct Plugin {
+    pub(super) call: Expr,
+    pub(super) version: Option<Expr>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Script {
+    /// Top-level assignments of project properties, such as `group` and `version`
+    pub(super) assignments: Vec<(String, Located<Expr>)>,
+    /// Local variables and `ext` properties, in order
+    pub(super) variables: Vec<(String, Located<Expr>)>,
+    /// Names of the `ext` properties among `variables`
+    pub(super) extra: Vec<String>,
+    pub(super) declarations: Vec<Located<Declaration>>,
+    pub(super) plugins: Vec<Located<Plugin>>,
+    /// JVM toolchain or source compatibility version
+    pub(super) java: Option<Located<Expr>>,
+    /// Projects a settings script `include`s
+    pub(super) includes: Vec<Located<Expr>>,
+}
+
+/// Properties of the project a script may assign, with or without a `project.` prefix
+const PROJECT_PROPERTIES: [&str; 4] = ["group", "version", "description", "name"];
+
+pub(super) fn parse(contents: &str, tokens: &[Spanned]) -> Result<Script, SyntaxError> {
+    let mut parser = Parser {
+        contents,
+        tokens,
+        pos: 0,
+        script: Script::default(),
+    };
+    parser.block(&mut Vec::new())?;
+    Ok(parser.script)
+}
+
+struct Parser<'a> {
+    contents: &'a str,
+    tokens: &'a [Spanned],
+    pos: usize,
+    script: Script,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos).map(|spanned| &spanned.token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens
+            .get(self.pos + offset)
+            .map(|spanned| &spanned.token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |spanned| spanned.line)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let matched = matches!(self.peek(), Some(Token::Punct(p)) if *p == punct);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == name)
+    }
+
+    /// Whether the infix `version` of a plugin follows, rather than a `version:` argument
+    fn at_version(&self) -> bool {
+        self.is_ident("version") && !matches!(self.peek_at(1), Some(Token::Punct(":" | "=")))
+    }
+
+    fn skip_newlines(&mut self) {
+        while matches!(self.peek(), Some(Token::Newline | Token::Punct(";"))) {
+            self.pos += 1;
+        }
+    }
+
+    /// Source text of the tokens from `start` to the current position
+    fn text(&self, start: usize) -> String {
+        let end = self.tokens[start..self.pos.min(self.tokens.len())]
+            .iter()
+            .rev()
+            .find(|spanned| spanned.token != Token::Newline);
+        match (self.tokens.get(start), end) {
+            (Some(first), Some(last)) => self.contents[first.start..last.end].to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn located<T>(&self, value: T, start: usize) -> Located<T> {
+        Located {
+            value,
+            text: self.text(start),
+            line: self.tokens.get(start).map_or(1, |spanned| spanned.line),
+        }
+    }
+
+    /// Read statements up to the `}` closing the block `path` leads to, or to the end of the
+    /// script at the top level
+    fn block(&mut self, path: &mut Vec<String>) -> Result<(), SyntaxError> {
+        loop {
+            self.skip_newlines();
+            match self.peek() {
+                None if path.is_empty() => return Ok(()),
+                None => {
+                    return Err(SyntaxError::new(
+                        self.line(),
+                        format!("unclosed `{}` block", path.join(" { ")),
+                    ))
+                }
+                Some(Token::Punct("}")) if path.is_empty() => {
+                    return Err(SyntaxError::new(self.line(), "unexpected `}`"))
+                }
+                Some(Token::Punct("}")) => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(Token::Ident(_)) => self.statement(path)?,
+                _ => self.skip_statement(path)?,
+            }
+        }
+    }
+
+    fn statement(&mut self, path: &mut Vec<String>) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        if let Some(Token::Ident(keyword)) = self.peek() {
+            if matches!(keyword.as_str(), "val" | "var" | "def") {
+                return self.variable(path);
+            }
+        }
+        let head = self.dotted();
+        let head_name = head.join(".");
+        match self.peek() {
+            Some(Token::Punct("=")) => {
+                self.pos += 1;
+                let value_start = self.pos;
+                let value = self.expression();
+                let value = self.located(value, value_start);
+                self.assignment(path, &head, value);
+            }
+            Some(Token::Punct("{")) => {
+                self.pos += 1;
+                path.push(head_name);
+                self.block(path)?;
+                path.pop();
+            }
+            Some(Token::Punct("(")) => {
+                self.pos += 1;
+                let args = self.arguments(")");
+                self.call(path, &head, args, start);
+                // A trailing closure, configuring what the call returned
+                if self.eat("{") {
+                    path.push(head_name);
+                    self.block(path)?;
+                    path.pop();
+                }
+            }
+            // Groovy calls without parentheses, such as `implementation 'g:a:v'`
+            Some(Token::Str(_) | Token::Ident(_) | Token::Number(_)) => {
+                let args = self.arguments("");
+                self.call(path, &head, args, start);
+                if self.eat("{") {
+                    path.push(head_name);
+                    self.block(path)?;
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+        self.skip_statement(path)
+    }
+
+    /// `val name = value`, `var name: Type = value` or `def name = value`
+    fn variable(&mut self, path: &mut Vec<String>) -> Result<(), SyntaxError> {
+        self.pos += 1;
+        if let Some(Token::Ident(name)) = self.peek() {
+            self.pos += 1;
+            if self.eat(":") {
+                self.dotted();
+            }
+            if self.eat("=") {
+                let start = self.pos;
+                let value = self.expression();
+                let value = self.located(value, start);
+                self.script.variables.push((name.clone(), value));
+            }
+        }
+        self.skip_statement(path)
+    }
+
+    fn assignment(&mut self, path: &[String], head: &[String], value: Located<Expr>) {
+        let head: Vec<&str> = head.iter().map(String::as_str).collect();
+        let head = match head.as_slice() {
+            ["project", rest @ ..] => rest,
+            head => head,
+        };
+        let context: Vec<&str> = path.iter().map(String::as_str).collect();
+        match (context.as_slice(), head) {
+            ([], [property]) if PROJECT_PROPERTIES.contains(property) => {
+                self.script.assignments.push((property.to_string(), value));
+            }
+            ([], ["rootProject", "name"]) => {
+                self.script.assignments.push(("name".to_string(), value));
+            }
+            ([], ["ext", name]) | (["ext"], [name]) => {
+                self.script.extra.push(name.to_string());
+                self.script.variables.push((name.to_string(), value));
+            }
+            (
+                _,
+                ["languageVersion"] | ["sourceCompatibility"] | ["java", "sourceCompatibility"],
+            ) => {
+                self.script.java = Some(value);
+            }
+            _ => {}
+        }
+    }
+
+    fn call(&mut self, path: &[String], head: &[String], args: Vec<Arg>, start: usize) {
+        let context: Vec<&str> = path.iter().map(String::as_str).collect();
+        let name = head.join(".");
+        match (context.as_slice(), name.as_str()) {
+            (["dependencies"] | ["buildscript", "dependencies"], configuration) => {
+                let declaration = Declaration {
+                    configuration: configuration.to_string(),
+                    args,
+                    buildscript: context.len() == 2,
+                };
+                let declaration = self.located(declaration, start);
+                self.script.declarations.push(declaration);
+            }
+            (["plugins"], "id" | "kotlin" | "alias") => {
+                let call = Expr::Call {
+                    name: name.clone(),
+                    args,
+                };
+                // `id("...") version "..." apply false`
+                let version = match self.at_version() {
+                    true => {
+                        self.pos += 1;
+                        self.eat("(");
+                        let version = self.expression();
+                        self.eat(")");
+                        Some(version)
+                    }
+                    false => None,
+                };
+                let plugin = self.located(Plugin { call, version }, start);
+                self.script.plugins.push(plugin);
+            }
+            ([], "include") => {
+                for arg in args {
+                    let value = Located {
+                        value: arg.value,
+                        text: String::new(),
+                        line: self.tokens.get(start).map_or(1, |spanned| spanned.line),
+                    };
+                    self.script.includes.push(value);
+                }
+            }
+            (_, "jvmToolchain") => {
+                if let Some(arg) = args.into_iter().next() {
+                    self.script.java = Some(self.located(arg.value, start));
+                }
+            }
+            (_, "languageVersion.set") => {
+                if let Some(arg) = args.into_iter().next() {
+                    self.script.java = Some(self.located(arg.value, start));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Identifiers joined by `.`
+    fn dotted(&mut self) -> Vec<String> {
+        let mut path = Vec::new();
+        while let Some(Token::Ident(ident)) = self.peek() {
+            path.push(ident.clone());
+            self.pos += 1;
+            let continues = matches!(self.peek(), Some(Token::Punct("." | "?.")))
+                && matches!(self.peek_at(1), Some(Token::Ident(_)));
+            if !continues {
+                break;
+            }
+            self.pos += 1;
+        }
+        path
+    }
+
+    /// Arguments up to `close`, or to the end of the line for Groovy calls without parentheses
+    fn arguments(&mut self, close: &str) -> Vec<Arg> {
+        let mut args = Vec::new();
+        loop {
+            if !close.is_empty() {
+                self.skip_newlines();
+                if self.eat(close) {
+                    break;
+                }
+            } else if self.at_end() || self.at_version() {
+                break;
+            }
+            let name = match (self.peek(), self.peek_at(1)) {
+                (Some(Token::Ident(name)), Some(Token::Punct(":" | "="))) => {
+                    self.pos += 2;
+                    Some(name.clone())
+                }
+                _ => None,
+            };
+            let value = self.expression();
+            args.push(Arg { name, value });
+            if !close.is_empty() {
+                self.skip_newlines();
+            }
+            if !self.eat(",") {
+                if !close.is_empty() {
+                    self.skip_until(close);
+                }
+                break;
+            }
+        }
+        args
+    }
+
+    /// Skip to the `close` ending the current arguments, and past it
+    fn skip_until(&mut self, close: &str) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            self.pos += 1;
+            match token {
+                Token::Punct(p) if depth == 0 && *p == close => return,
+                Token::Punct("(" | "[" | "{") => depth += 1,
+                Token::Punct(")" | "]" | "}") => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether an expression or statement ends here
+    fn at_end(&self) -> bool {
+        matches!(
+            self.peek(),
+            None | Some(Token::Newline | Token::Punct("," | ")" | "]" | "}" | ";" | "{"))
+        )
+    }
+
+    fn expression(&mut self) -> Expr {
+        let expr = self.primary();
+        if self.at_end() || self.at_version() || self.is_ident("apply") {
+            return expr.unwrap_or(Expr::Other);
+        }
+        // An operator or a method call on the value: skip the rest of the expression
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            if depth == 0 && self.at_end() {
+                break;
+            }
+            match token {
+                Token::Punct("(" | "[" | "{") => depth += 1,
+                Token::Punct(")" | "]" | "}") => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Expr::Other
+    }
+
+    fn primary(&mut self) -> Option<Expr> {
+        match self.peek()? {
+            Token::Str(parts) => {
+                self.pos += 1;
+                Some(Expr::Str(parts.clone()))
+            }
+            Token::Number(number) => {
+                self.pos += 1;
+                Some(Expr::Number(number.clone()))
+            }
+            Token::Ident(_) => {
+                let path = self.dotted();
+                if self.eat("(") {
+                    let args = self.arguments(")");
+                    let name = path.join(".");
+                    return Some(Expr::Call { name, args });
+                }
+                // Kotlin providers, such as `libs.versions.kotlin.get()`, are read with `get()`
+                if matches!(self.peek(), Some(Token::Punct(".")))
+                    && self.peek_at(1) == Some(&Token::Ident("get".to_string()))
+                    && self.peek_at(2) == Some(&Token::Punct("("))
+                    && self.peek_at(3) == Some(&Token::Punct(")"))
+                {
+                    self.pos += 4;
+                }
+                Some(Expr::Ref(path))
+            }
+            _ => None,
+        }
+    }
+
+    /// Skip the rest of a statement, over nested blocks
+    fn skip_statement(&mut self, path: &mut Vec<String>) -> Result<(), SyntaxError> {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Newline | Token::Punct(";") if depth == 0 => return Ok(()),
+                Token::Punct("}") if depth == 0 => return Ok(()),
+                // Blocks opened by statements that are not understood are still walked, so
+                // that a `dependencies` block inside them is not mistaken for a top-level one
+                Token::Punct("{") if depth == 0 => {
+                    self.pos += 1;
+                    path.push(String::new());
+                    self.block(path)?;
+                    path.pop();
+                    continue;
+                }
+                Token::Punct("(" | "[") => depth += 1,
+                Token::Punct(")" | "]") => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+}
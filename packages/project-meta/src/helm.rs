@@ -0,0 +1,94 @@
+//! Helm `Chart.yaml` reader.
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec};
+use crate::npm::{self, SpecifierError};
+use crate::{yaml, MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn from_yaml(yaml: &Value, contents: &str) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    for dependency in yaml["dependencies"].as_array().into_iter().flatten() {
+        dependencies.push(chart_dependency(dependency)?);
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let mut urls = HashMap::new();
+    if let Some(home) = yaml["home"].as_str() {
+        urls.insert("homepage".to_string(), home.to_string());
+    }
+    // Charts list the sources of the packaged application, the first being its repository
+    if let Some(source) = yaml["sources"][0].as_str() {
+        urls.insert("repository".to_string(), source.to_string());
+    }
+    let engines = yaml::top_level_string(yaml, contents, "kubeVersion")
+        .map(|constraint| HashMap::from([("kubernetes".to_string(), constraint.to_string())]));
+    // The version of the packaged application, which the chart version is independent from
+    let properties = yaml::top_level_string(yaml, contents, "appVersion")
+        .map(|app_version| HashMap::from([("appVersion".to_string(), app_version.to_string())]));
+
+    Ok(ProjectMetadata {
+        name: yaml["name"].as_str().unwrap_or("").to_string(),
+        version: yaml::top_level_string(yaml, contents, "version").unwrap_or_default(),
+        description: yaml["description"].as_str().map(|s| s.to_string()),
+        keywords: yaml["keywords"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        maintainers: yaml["maintainers"]
+            .as_array()
+            .map(|arr| arr.iter().filter_map(Person::from_json).collect()),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines,
+        properties,
+        ..Default::default()
+    })
+}
+
+/// Read a `dependencies` entry; the chart is installed under its `alias` when given, and only
+/// when its `condition` or `tags` are enabled in the values when they are set
+fn chart_dependency(dependency: &Value) -> Result<DependencySpec, MetadataError> {
+    let chart = dependency["name"].as_str().unwrap_or("");
+    let name = dependency["alias"].as_str().unwrap_or(chart);
+    let repository = dependency["repository"]
+        .as_str()
+        .filter(|url| !url.is_empty());
+    let source = match (repository, dependency["alias"].as_str()) {
+        (Some(url), _) if url.starts_with("file://") => DependencySource::Path {
+            path: url["file://".len()..].to_string(),
+        },
+        (repository, Some(_)) => DependencySource::Alias {
+            registry: repository.map(|s| s.to_string()),
+            package: chart.to_string(),
+            dist_tag: None,
+        },
+        (repository, None) => DependencySource::Registry {
+            registry: repository.map(|s| s.to_string()),
+            dist_tag: None,
+        },
+    };
+    let conditional = !dependency["condition"].is_null() || !dependency["tags"].is_null();
+    let kind = match conditional {
+        true => DependencyKind::Optional,
+        false => DependencyKind::Runtime,
+    };
+    let version = match &dependency["version"] {
+        Value::Null => None,
+        // Helm's semver constraints are node-semver ones, also separating comparators by commas
+        Value::String(constraint) => Some(
+            npm::parse_range(&constraint.replace(',', " "))
+                .map_err(|err| MetadataError::DependencySpecifierError(name.to_string(), err))?
+                .with_raw(constraint),
+        ),
+        version => {
+            return Err(MetadataError::DependencySpecifierError(
+                name.to_string(),
+                SpecifierError::NotAString(version.to_string()),
+            ))
+        }
+    };
+    Ok(DependencySpec::new(name, kind)
+        .with_version(version)
+        .source(source))
+}
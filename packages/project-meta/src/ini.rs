@@ -0,0 +1,73 @@
+//! INI files as Python's `configparser` reads them for `setup.cfg`: `[section]` headers,
+//! `key = value` or `key: value` options, and values continued on indented lines.
+use crate::syntax::SyntaxError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Section {
+    pub(crate) name: String,
+    /// Options in order, keys lowercased as `configparser` does
+    pub(crate) options: Vec<(String, String)>,
+}
+
+impl Section {
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) fn parse(contents: &str) -> Result<Vec<Section>, SyntaxError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let error = |message: &str| SyntaxError::new(index + 1, message);
+        let trimmed = line.trim();
+        if trimmed.starts_with(['#', ';']) {
+            continue;
+        }
+        // Indented lines continue the previous value, keeping blank lines inside it
+        if line.starts_with([' ', '\t']) || trimmed.is_empty() {
+            let option = sections
+                .last_mut()
+                .and_then(|section| section.options.last_mut());
+            match option {
+                Some((_, value)) => {
+                    value.push('\n');
+                    value.push_str(trimmed);
+                }
+                None if trimmed.is_empty() => {}
+                None => return Err(error("continuation line without an option")),
+            }
+            continue;
+        }
+        if let Some(header) = trimmed.strip_prefix('[') {
+            let Some(name) = header.strip_suffix(']') else {
+                return Err(error("unterminated section header"));
+            };
+            sections.push(Section {
+                name: name.trim().to_string(),
+                options: Vec::new(),
+            });
+            continue;
+        }
+        let Some(section) = sections.last_mut() else {
+            return Err(error("option outside of a section"));
+        };
+        let Some(separator) = trimmed.find(['=', ':']) else {
+            return Err(error("expected `key = value`"));
+        };
+        let key = trimmed[..separator].trim().to_lowercase();
+        // `configparser` has no inline comments by default, `#` may be part of a value
+        let value = trimmed[separator + 1..].trim();
+        section.options.push((key, value.to_string()));
+    }
+    // Blank lines trailing a value are not part of it
+    for section in &mut sections {
+        for (_, value) in &mut section.options {
+            value.truncate(value.trim_end().len());
+        }
+    }
+    Ok(sections)
+}
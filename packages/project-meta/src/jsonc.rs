@@ -0,0 +1,104 @@
+//! JSON with comments and trailing commas, as accepted by `deno.json`, `deno.jsonc`,
+//! `tsconfig.json` and the package managers reading `package.json`.
+use crate::syntax::SyntaxError;
+
+/// Parse `contents`, reporting errors at their position in `contents` itself: comments and
+/// trailing commas are blanked out byte for byte before handing the text to `serde_json`
+pub(crate) fn parse(contents: &str) -> Result<serde_json::Value, SyntaxError> {
+    let stripped = without_trailing_commas(&without_comments(contents)?);
+    serde_json::from_str(&stripped).map_err(|err| {
+        let message = err.to_string();
+        // serde_json ends its messages with ` at line L column C`, which `line` and `column`
+        // already hold
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) => message.to_string(),
+            None => message,
+        };
+        SyntaxError::at(err.line(), err.column(), message)
+    })
+}
+
+/// Replace `c` with as many spaces as it has bytes, newlines excepted
+fn blank(stripped: &mut String, c: char) {
+    match c {
+        '\n' => stripped.push('\n'),
+        c => stripped.extend(std::iter::repeat_n(' ', c.len_utf8())),
+    }
+}
+
+fn without_comments(contents: &str) -> Result<String, SyntaxError> {
+    let mut stripped = String::with_capacity(contents.len());
+    let mut chars = contents.char_indices().peekable();
+    let mut in_string = false;
+    while let Some((offset, c)) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next().map(|(_, c)| c)),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek().map(|&(_, c)| c)) {
+            ('/', Some('/')) => {
+                blank(&mut stripped, c);
+                while let Some((_, c)) = chars.next_if(|&(_, c)| c != '\n') {
+                    blank(&mut stripped, c);
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                stripped.push_str("  ");
+                let mut closed = false;
+                while let Some((_, c)) = chars.next() {
+                    if c == '*' && chars.next_if(|&(_, c)| c == '/').is_some() {
+                        stripped.push_str("  ");
+                        closed = true;
+                        break;
+                    }
+                    blank(&mut stripped, c);
+                }
+                if !closed {
+                    let (line, column) = position(contents, offset);
+                    return Err(SyntaxError::at(line, column, "unterminated block comment"));
+                }
+            }
+            _ => {
+                in_string = c == '"';
+                stripped.push(c);
+            }
+        }
+    }
+    Ok(stripped)
+}
+
+fn without_trailing_commas(contents: &str) -> String {
+    let mut stripped = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        in_string = c == '"';
+        let mut lookahead = chars.clone();
+        while lookahead.next_if(|c| c.is_whitespace()).is_some() {}
+        let trailing = c == ',' && matches!(lookahead.peek(), Some('}' | ']'));
+        stripped.push(if trailing { ' ' } else { c });
+    }
+    stripped
+}
+
+/// 1-based line and byte column of `offset`
+fn position(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset];
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    (before.matches('\n').count() + 1, offset - line_start + 1)
+}
@@ -0,0 +1,141 @@
+//! Julia `Project.toml`: the package, its dependencies keyed by name with their UUIDs, and the
+//! `[compat]` bounds on them.
+//!
+//! The UUIDs identify the packages in the registry, so they are kept in `properties` as
+//! `deps.<Name>`, next to the `uuid` of the package itself.
+use crate::dependency::{
+    DependencyKind, DependencySource, DependencySpec, VersionReq, VersionSyntax,
+};
+use crate::{Person, ProjectMetadata};
+use std::collections::HashMap;
+
+pub(crate) fn from_toml(toml: &toml::Value) -> ProjectMetadata {
+    let table = |key: &str| toml.get(key).and_then(|v| v.as_table());
+    let compat: HashMap<&str, &str> = table("compat")
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, spec)| Some((name.as_str(), spec.as_str()?)))
+        .collect();
+
+    let mut properties = HashMap::new();
+    if let Some(uuid) = toml.get("uuid").and_then(|v| v.as_str()) {
+        properties.insert("uuid".to_string(), uuid.to_string());
+    }
+    // `[extras]` only holds the packages `[targets]` name
+    let mut targets: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (target, names) in table("targets").into_iter().flatten() {
+        for name in names
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+        {
+            targets.entry(name).or_default().push(target);
+        }
+    }
+    let mut dependencies = Vec::new();
+    for key in ["deps", "weakdeps", "extras"] {
+        for (name, uuid) in table(key).into_iter().flatten() {
+            if let Some(uuid) = uuid.as_str() {
+                properties.insert(format!("deps.{name}"), uuid.to_string());
+            }
+            let (kind, groups) = match key {
+                "deps" => (DependencyKind::Runtime, vec![None]),
+                "weakdeps" => (DependencyKind::Optional, vec![Some(key)]),
+                _ => match targets.get(name.as_str()) {
+                    Some(targets) => (
+                        DependencyKind::Dev,
+                        targets.iter().map(|t| Some(*t)).collect(),
+                    ),
+                    None => continue,
+                },
+            };
+            let version = compat.get(name.as_str()).map(|spec| compat_version(spec));
+            let source = source(toml.get("sources").and_then(|sources| sources.get(name)));
+            for group in groups {
+                let dependency = DependencySpec::new(name, kind)
+                    .with_version(version.clone())
+                    .source(source.clone())
+                    .group(group);
+                dependencies.push(dependency);
+            }
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let engines = compat
+        .get("julia")
+        .map(|spec| HashMap::from([("julia".to_string(), spec.to_string())]));
+
+    ProjectMetadata {
+        name: toml
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        version: toml
+            .get("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        authors: toml
+            .get("authors")
+            .and_then(|v| v.as_array())
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(|author| author.as_str())
+                    .map(Person::parse)
+                    .collect()
+            }),
+        engines,
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    }
+}
+
+/// `[sources]` entry of a package: a `url` with an optional `rev`, or a `path`
+fn source(entry: Option<&toml::Value>) -> DependencySource {
+    let get = |key: &str| entry?.get(key)?.as_str().map(|s| s.to_string());
+    match (get("url"), get("path")) {
+        (Some(url), _) => DependencySource::Git {
+            url,
+            branch: None,
+            tag: None,
+            rev: get("rev"),
+        },
+        (None, Some(path)) => DependencySource::Path { path },
+        (None, None) => DependencySource::default(),
+    }
+}
+
+/// Parse a compat entry: comma separated alternatives, where bare versions are caret
+/// requirements as in Cargo and `1.2 - 1.5` is a range including all of `1.5.x`
+fn compat_version(spec: &str) -> VersionReq {
+    let alternatives: Vec<String> = spec
+        .split(',')
+        .map(|alternative| match alternative.split_once(" - ") {
+            Some((low, high)) => format!(">={}, <{}", low.trim(), next_version(high.trim())),
+            None => alternative.trim().to_string(),
+        })
+        .collect();
+    VersionReq::parse(&alternatives.join(" || "), VersionSyntax::Cargo).with_raw(spec)
+}
+
+/// Smallest version above all the versions `version` is a prefix of: `1.5` gives `1.6.0`
+fn next_version(version: &str) -> String {
+    let mut parts: Vec<u64> = version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    if let Some(last) = parts.last_mut() {
+        *last += 1;
+    }
+    parts.resize(3, 0);
+    parts
+        .iter()
+        .map(|part| part.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -1,36 +1,151 @@
 use anyhow::Result;
 use confique::Config;
 use serde::{Deserialize, Serialize};
+use source::builtin;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
+use std::path::Path;
 use thiserror::Error;
 
-#[derive(Serialize, Deserialize, Debug, Config, Eq, PartialEq, Clone)]
+mod bower;
+mod cabal;
+mod cargo;
+mod citation;
+mod codemeta;
+mod composer;
+mod conda;
+mod dcf;
+mod deno;
+mod dependency;
+mod dune;
+mod gemfile;
+mod gemspec;
+mod go_mod;
+mod gradle;
+mod helm;
+mod ini;
+mod jsonc;
+mod julia;
+mod msbuild;
+mod npm;
+mod opam;
+mod package_json;
+mod person;
+mod pixi;
+mod pom;
+mod pubspec;
+mod pyproject;
+mod r_description;
+mod ruby;
+mod setuptools;
+mod sexp;
+mod source;
+mod syntax;
+mod util;
+mod vcpkg;
+mod xml;
+mod yaml;
+mod zig;
+mod zon;
+
+pub use dependency::{DependencyKind, DependencySource, DependencySpec, VersionReq, VersionSyntax};
+pub use npm::SpecifierError;
+pub use person::Person;
+pub use source::{MetadataSource, Registry};
+
+#[derive(Serialize, Deserialize, Debug, Config, Eq, PartialEq, Clone, Default)]
 pub struct ProjectMetadata {
     name: String,
     version: String,
     description: Option<String>,
     #[serde(alias = "author")]
-    authors: Option<Vec<String>>,
+    authors: Option<Vec<Person>>,
     license: Option<String>,
     keywords: Option<Vec<String>>,
-    dependencies: Option<Dependencies>,
+    dependencies: Option<Vec<DependencySpec>>,
     scripts: Option<HashMap<String, String>>,
+    maintainers: Option<Vec<Person>>,
+    contributors: Option<Vec<Person>>,
+    /// Path of the license file, when the license is given by reference rather than by name
+    license_file: Option<String>,
+    /// Path of the readme file, or its inline text when no file is given
+    readme: Option<String>,
+    /// Project links keyed by label (homepage, repository, documentation, ...)
+    urls: Option<HashMap<String, String>>,
+    classifiers: Option<Vec<String>>,
+    /// Runtime version requirements keyed by engine (`python`, `node`, ...)
+    engines: Option<HashMap<String, String>>,
+    /// Task runner entries keyed by name, with their dependencies; `scripts` holds their commands
+    tasks: Option<HashMap<String, Task>>,
+    /// Modules exposed to importers keyed by entry point, `.` being the main one
+    exports: Option<HashMap<String, String>>,
+    /// Paths of the workspace members
+    workspace: Option<Vec<String>>,
+    /// Files packaged on publish, when restricted
+    include: Option<Vec<String>>,
+    /// Files left out of the published package
+    exclude: Option<Vec<String>>,
+    /// Format specific properties keyed by name: Maven and MSBuild properties, with their
+    /// references to other properties resolved, Gradle `ext` and `gradle.properties` entries, the
+    /// `metadata` of a gemspec, or the `appVersion` of a Helm chart
+    properties: Option<HashMap<String, String>>,
+    /// Fields the manifest declares as computed by the build backend, or that a gemspec computes
+    dynamic: Option<Vec<String>>,
+    /// Where each field was read from, when the format has several places to look
+    sources: Option<HashMap<String, FieldValue>>,
 }
 
+/// Provenance of a [`ProjectMetadata`] field
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-#[serde(untagged)]
-pub enum Dependencies {
-    Simple(HashMap<String, String>),
-    Detailed(HashMap<String, DependencyDetails>),
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldValue {
+    /// Written in the manifest, in the `source` table
+    Static { source: String },
+    /// Declared dynamic and computed from the `source` file, the way the build backend does
+    Resolved { source: String },
+    /// Declared dynamic and only known once the build backend runs `source` (VCS tags, hooks, ...)
+    Dynamic { source: String },
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
-pub struct DependencyDetails {
-    version: Option<String>,
-    url: Option<String>,
-    // Ajoutez d'autres champs si nécessaire
+impl FieldValue {
+    fn from_table(source: &str) -> Self {
+        FieldValue::Static {
+            source: source.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Task {
+    command: Option<String>,
+    /// Tasks to run before this one
+    dependencies: Option<Vec<String>>,
+    description: Option<String>,
+}
+
+impl Task {
+    pub fn new(
+        command: Option<&str>,
+        dependencies: Vec<String>,
+        description: Option<&str>,
+    ) -> Self {
+        Task {
+            command: command.map(|command| command.to_string()),
+            dependencies: (!dependencies.is_empty()).then_some(dependencies),
+            description: description.map(|description| description.to_string()),
+        }
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_deref()
+    }
+
+    pub fn dependencies(&self) -> Option<&[String]> {
+        self.dependencies.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
 }
 
 #[derive(Error, Debug, Serialize, Deserialize)]
@@ -45,6 +160,20 @@ pub enum MetadataError {
     TomlParseError(String),
     #[error("No configuration files found")]
     NoFilesFound,
+    #[error("Failed to resolve workspace inheritance: {0}")]
+    WorkspaceInheritanceError(String),
+    #[error("Failed to resolve parent POM: {0}")]
+    ParentPomError(String),
+    #[error("Invalid specifier for dependency `{0}`: {1}")]
+    DependencySpecifierError(String, SpecifierError),
+    /// Syntax error of a manifest: the name of its format and the message, located in the file
+    /// when it was read from one
+    #[error("Failed to parse {0}: {1}")]
+    ParseError(String, String),
+    #[error("No registered format reads {0}")]
+    UnknownFormat(String),
+    #[error("Writing {0} is not supported")]
+    WriteUnsupported(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -52,7 +181,33 @@ pub enum ConfigFile {
     PackageJson(String),
     CargoToml(String),
     DenoJson(String),
+    DenoJsonc(String),
+    JsrJson(String),
+    BowerJson(String),
+    ComposerJson(String),
+    GoMod(String),
+    PomXml(String),
+    BuildGradle(String),
+    BuildGradleKts(String),
+    PubspecYaml(String),
+    ChartYaml(String),
+    Csproj(String),
+    Gemspec(String),
+    Gemfile(String),
     PyprojectToml(String),
+    SetupCfg(String),
+    SetupPy(String),
+    CitationCff(String),
+    CodemetaJson(String),
+    VcpkgJson(String),
+    RDescription(String),
+    JuliaProjectToml(String),
+    Cabal(String),
+    EnvironmentYml(String),
+    PixiToml(String),
+    BuildZigZon(String),
+    DuneProject(String),
+    Opam(String),
 }
 
 impl ConfigFile {
@@ -61,165 +216,292 @@ impl ConfigFile {
             ConfigFile::PackageJson(path) => path,
             ConfigFile::CargoToml(path) => path,
             ConfigFile::DenoJson(path) => path,
+            ConfigFile::DenoJsonc(path) => path,
+            ConfigFile::JsrJson(path) => path,
+            ConfigFile::BowerJson(path) => path,
+            ConfigFile::ComposerJson(path) => path,
+            ConfigFile::GoMod(path) => path,
+            ConfigFile::PomXml(path) => path,
+            ConfigFile::BuildGradle(path) => path,
+            ConfigFile::BuildGradleKts(path) => path,
+            ConfigFile::PubspecYaml(path) => path,
+            ConfigFile::ChartYaml(path) => path,
+            ConfigFile::Csproj(path) => path,
+            ConfigFile::Gemspec(path) => path,
+            ConfigFile::Gemfile(path) => path,
             ConfigFile::PyprojectToml(path) => path,
+            ConfigFile::SetupCfg(path) => path,
+            ConfigFile::SetupPy(path) => path,
+            ConfigFile::CitationCff(path) => path,
+            ConfigFile::CodemetaJson(path) => path,
+            ConfigFile::VcpkgJson(path) => path,
+            ConfigFile::RDescription(path) => path,
+            ConfigFile::JuliaProjectToml(path) => path,
+            ConfigFile::Cabal(path) => path,
+            ConfigFile::EnvironmentYml(path) => path,
+            ConfigFile::PixiToml(path) => path,
+            ConfigFile::BuildZigZon(path) => path,
+            ConfigFile::DuneProject(path) => path,
+            ConfigFile::Opam(path) => path,
+        }
+    }
+
+    /// Built-in format of the file
+    pub fn source(&self) -> &'static dyn MetadataSource {
+        match self {
+            ConfigFile::PackageJson(_) => &builtin::PACKAGE_JSON,
+            ConfigFile::CargoToml(_) => &builtin::CARGO_TOML,
+            ConfigFile::DenoJson(_) => &builtin::DENO_JSON,
+            ConfigFile::DenoJsonc(_) => &builtin::DENO_JSON,
+            ConfigFile::JsrJson(_) => &builtin::JSR_JSON,
+            ConfigFile::BowerJson(_) => &builtin::BOWER_JSON,
+            ConfigFile::ComposerJson(_) => &builtin::COMPOSER_JSON,
+            ConfigFile::GoMod(_) => &builtin::GO_MOD,
+            ConfigFile::PomXml(_) => &builtin::POM_XML,
+            ConfigFile::BuildGradle(_) => &builtin::BUILD_GRADLE,
+            ConfigFile::BuildGradleKts(_) => &builtin::BUILD_GRADLE,
+            ConfigFile::PubspecYaml(_) => &builtin::PUBSPEC_YAML,
+            ConfigFile::ChartYaml(_) => &builtin::CHART_YAML,
+            ConfigFile::Csproj(_) => &builtin::CSPROJ,
+            ConfigFile::Gemspec(_) => &builtin::GEMSPEC,
+            ConfigFile::Gemfile(_) => &builtin::GEMFILE,
+            ConfigFile::PyprojectToml(_) => &builtin::PYPROJECT_TOML,
+            ConfigFile::SetupCfg(_) => &builtin::SETUP_CFG,
+            ConfigFile::SetupPy(_) => &builtin::SETUP_PY,
+            ConfigFile::CitationCff(_) => &builtin::CITATION_CFF,
+            ConfigFile::CodemetaJson(_) => &builtin::CODEMETA_JSON,
+            ConfigFile::VcpkgJson(_) => &builtin::VCPKG_JSON,
+            ConfigFile::RDescription(_) => &builtin::R_DESCRIPTION,
+            ConfigFile::JuliaProjectToml(_) => &builtin::JULIA_PROJECT_TOML,
+            ConfigFile::Cabal(_) => &builtin::CABAL,
+            ConfigFile::EnvironmentYml(_) => &builtin::ENVIRONMENT_YML,
+            ConfigFile::PixiToml(_) => &builtin::PIXI_TOML,
+            ConfigFile::BuildZigZon(_) => &builtin::BUILD_ZIG_ZON,
+            ConfigFile::DuneProject(_) => &builtin::DUNE_PROJECT,
+            ConfigFile::Opam(_) => &builtin::OPAM,
         }
     }
 }
 
 impl ProjectMetadata {
-    pub fn from_config(config_file: &ConfigFile) -> Result<Self, MetadataError> {
-        let file_path = config_file.file_path();
-        let mut file = File::open(file_path)
-            .map_err(|_| MetadataError::FileNotFound(file_path.to_string()))?;
-        let mut contents = String::new();
-        file.read_to_string(&mut contents)
-            .map_err(|_| MetadataError::ReadError(file_path.to_string()))?;
-
-        let metadata: ProjectMetadata = match config_file {
-            ConfigFile::PackageJson(_) => {
-                let json: serde_json::Value = serde_json::from_str(&contents)
-                    .map_err(|_| MetadataError::JsonParseError(file_path.to_string()))?;
-                let metadata = ProjectMetadata {
-                    name: json["name"].as_str().unwrap_or("").to_string(),
-                    version: json["version"].as_str().unwrap_or("").to_string(),
-                    description: json["description"].as_str().map(|s| s.to_string()),
-                    authors: json["author"].as_str().map(|s| vec![s.to_string()]),
-                    license: json["license"].as_str().map(|s| s.to_string()),
-                    keywords: json["keywords"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    }),
-                    dependencies: json["dependencies"].as_object().map(|deps| {
-                        Dependencies::Simple(HashMap::from_iter(deps.iter().map(|(k, v)| {
-                            (k.to_string(), v.as_str().map(|s| s.to_string()).unwrap())
-                        })))
-                    }),
-                    scripts: json["scripts"].as_object().map(|scripts| {
-                        scripts
-                            .clone()
-                            .into_iter()
-                            .map(|(k, v)| (k, v.as_str().unwrap_or("").to_string()))
-                            .collect()
-                    }),
-                };
-                Ok::<ProjectMetadata, MetadataError>(metadata)
-            }
-            ConfigFile::CargoToml(_) => {
-                let toml: toml::Value = toml::from_str(&contents)
-                    .map_err(|_| MetadataError::TomlParseError(file_path.to_string()))?;
-                let metadata = ProjectMetadata {
-                    name: toml["package"]["name"].as_str().unwrap_or("").to_string(),
-                    version: toml["package"]["version"]
-                        .as_str()
-                        .unwrap_or("")
-                        .to_string(),
-                    description: toml["package"]["description"]
-                        .as_str()
-                        .map(|s| s.to_string()),
-                    authors: toml["package"]["authors"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    }),
-                    license: toml["package"]["license"].as_str().map(|s| s.to_string()),
-                    keywords: toml["package"]["keywords"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    }),
-                    dependencies: toml["dependencies"].as_table().map(|deps| {
-                        let mut detailed_deps = HashMap::new();
-                        for (key, value) in deps {
-                            let details = DependencyDetails {
-                                version: value
-                                    .get("version")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string()),
-                                url: None, // Cargo.toml does not have a URL field
-                            };
-                            detailed_deps.insert(key.clone(), details);
-                        }
-                        Dependencies::Detailed(detailed_deps)
-                    }),
-                    scripts: None, // Cargo.toml does not have a scripts field
-                };
-                Ok(metadata)
-            }
-            ConfigFile::DenoJson(_) => {
-                let json: serde_json::Value = serde_json::from_str(&contents)
-                    .map_err(|_| MetadataError::JsonParseError(file_path.to_string()))?;
-                let metadata = ProjectMetadata {
-                    name: json["name"].as_str().unwrap_or("").to_string(),
-                    version: json["version"].as_str().unwrap_or("").to_string(),
-                    description: json["description"].as_str().map(|s| s.to_string()),
-                    authors: None, // deno.json does not have an authors field
-                    dependencies: json["imports"].as_object().map(|deps| {
-                        Dependencies::Simple(HashMap::from_iter(deps.iter().map(|(k, v)| {
-                            (k.to_string(), v.as_str().map(|s| s.to_string()).unwrap())
-                        })))
-                    }),
-                    scripts: json["scripts"].as_object().map(|scripts| {
-                        scripts
-                            .clone()
-                            .into_iter()
-                            .map(|(k, v)| (k, v.as_str().unwrap_or("").to_string()))
-                            .collect()
-                    }),
-                    license: json["license"].as_str().map(|s| s.to_string()),
-                    // FIXME https://github.com/denoland/deno/blob/1d49b3cb0f54eb8184acc00ec4bb3bd519653441/cli/schemas/config-file.v1.json#L4
-                    keywords: json["keywords"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    }),
-                };
-                Ok(metadata)
-            }
-            ConfigFile::PyprojectToml(_) => {
-                let toml: toml::Value = toml::from_str(&contents)
-                    .map_err(|_| MetadataError::TomlParseError(file_path.to_string()))?;
-                let metadata = ProjectMetadata {
-                    name: toml["project"]["name"].as_str().unwrap_or("").to_string(),
-                    version: toml["project"]["version"]
-                        .as_str()
-                        .unwrap_or("")
-                        .to_string(),
-                    description: toml["project"]["description"]
-                        .as_str()
-                        .map(|s| s.to_string()),
-                    authors: toml["project"]["authors"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    }),
-                    license: toml["project"]["license"].as_str().map(|s| s.to_string()),
-                    keywords: toml["project"]["keywords"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|v| v.as_str().unwrap_or("").to_string())
-                            .collect()
-                    }),
-                    dependencies: toml["project"]["dependencies"].as_table().map(|deps| {
-                        let mut detailed_deps = HashMap::new();
-                        for (key, value) in deps {
-                            let details = DependencyDetails {
-                                version: value
-                                    .get("version")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string()),
-                                url: None, // pyproject.toml does not have a URL field
-                            };
-                            detailed_deps.insert(key.clone(), details);
-                        }
-                        Dependencies::Detailed(detailed_deps)
-                    }),
-                    scripts: None, // pyproject.toml does not have a scripts field
-                };
-                Ok(metadata)
-            }
-        }?;
+    /// Metadata of the `name` project at `version`, the other fields being set by the `with_*`
+    /// methods, for the formats registered downstream
+    pub fn new(name: &str, version: &str) -> Self {
+        ProjectMetadata {
+            name: name.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn with_authors(mut self, authors: Vec<Person>) -> Self {
+        self.authors = (!authors.is_empty()).then_some(authors);
+        self
+    }
+
+    pub fn with_maintainers(mut self, maintainers: Vec<Person>) -> Self {
+        self.maintainers = (!maintainers.is_empty()).then_some(maintainers);
+        self
+    }
+
+    pub fn with_contributors(mut self, contributors: Vec<Person>) -> Self {
+        self.contributors = (!contributors.is_empty()).then_some(contributors);
+        self
+    }
+
+    pub fn with_license(mut self, license: &str) -> Self {
+        self.license = Some(license.to_string());
+        self
+    }
+
+    pub fn with_license_file(mut self, license_file: &str) -> Self {
+        self.license_file = Some(license_file.to_string());
+        self
+    }
+
+    pub fn with_readme(mut self, readme: &str) -> Self {
+        self.readme = Some(readme.to_string());
+        self
+    }
+
+    pub fn with_keywords(mut self, keywords: Vec<String>) -> Self {
+        self.keywords = (!keywords.is_empty()).then_some(keywords);
+        self
+    }
+
+    pub fn with_classifiers(mut self, classifiers: Vec<String>) -> Self {
+        self.classifiers = (!classifiers.is_empty()).then_some(classifiers);
+        self
+    }
+
+    /// Set the dependencies, in the order the built-in formats give them
+    pub fn with_dependencies(mut self, mut dependencies: Vec<DependencySpec>) -> Self {
+        DependencySpec::sort(&mut dependencies);
+        self.dependencies = (!dependencies.is_empty()).then_some(dependencies);
+        self
+    }
+
+    pub fn with_script(mut self, name: &str, command: &str) -> Self {
+        let scripts = self.scripts.get_or_insert_with(HashMap::new);
+        scripts.insert(name.to_string(), command.to_string());
+        self
+    }
+
+    pub fn with_url(mut self, label: &str, url: &str) -> Self {
+        let urls = self.urls.get_or_insert_with(HashMap::new);
+        urls.insert(label.to_string(), url.to_string());
+        self
+    }
+
+    pub fn with_engine(mut self, engine: &str, requirement: &str) -> Self {
+        let engines = self.engines.get_or_insert_with(HashMap::new);
+        engines.insert(engine.to_string(), requirement.to_string());
+        self
+    }
+
+    pub fn with_export(mut self, entry_point: &str, module: &str) -> Self {
+        let exports = self.exports.get_or_insert_with(HashMap::new);
+        exports.insert(entry_point.to_string(), module.to_string());
+        self
+    }
+
+    pub fn with_workspace(mut self, members: Vec<String>) -> Self {
+        self.workspace = (!members.is_empty()).then_some(members);
+        self
+    }
+
+    pub fn with_include(mut self, include: Vec<String>) -> Self {
+        self.include = (!include.is_empty()).then_some(include);
+        self
+    }
+
+    pub fn with_exclude(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = (!exclude.is_empty()).then_some(exclude);
+        self
+    }
+
+    pub fn with_property(mut self, name: &str, value: &str) -> Self {
+        let properties = self.properties.get_or_insert_with(HashMap::new);
+        properties.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_task(mut self, name: &str, task: Task) -> Self {
+        let tasks = self.tasks.get_or_insert_with(HashMap::new);
+        tasks.insert(name.to_string(), task);
+        self
+    }
+
+    pub fn with_dynamic(mut self, fields: Vec<String>) -> Self {
+        self.dynamic = (!fields.is_empty()).then_some(fields);
+        self
+    }
+
+    pub fn with_source(mut self, field: &str, source: FieldValue) -> Self {
+        let sources = self.sources.get_or_insert_with(HashMap::new);
+        sources.insert(field.to_string(), source);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn authors(&self) -> Option<&[Person]> {
+        self.authors.as_deref()
+    }
+
+    pub fn maintainers(&self) -> Option<&[Person]> {
+        self.maintainers.as_deref()
+    }
+
+    pub fn contributors(&self) -> Option<&[Person]> {
+        self.contributors.as_deref()
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
+    pub fn license_file(&self) -> Option<&str> {
+        self.license_file.as_deref()
+    }
+
+    pub fn readme(&self) -> Option<&str> {
+        self.readme.as_deref()
+    }
+
+    pub fn keywords(&self) -> Option<&[String]> {
+        self.keywords.as_deref()
+    }
 
-        Ok(metadata)
+    pub fn classifiers(&self) -> Option<&[String]> {
+        self.classifiers.as_deref()
+    }
+
+    pub fn dependencies(&self) -> Option<&[DependencySpec]> {
+        self.dependencies.as_deref()
+    }
+
+    pub fn scripts(&self) -> Option<&HashMap<String, String>> {
+        self.scripts.as_ref()
+    }
+
+    pub fn urls(&self) -> Option<&HashMap<String, String>> {
+        self.urls.as_ref()
+    }
+
+    pub fn engines(&self) -> Option<&HashMap<String, String>> {
+        self.engines.as_ref()
+    }
+
+    pub fn tasks(&self) -> Option<&HashMap<String, Task>> {
+        self.tasks.as_ref()
+    }
+
+    pub fn exports(&self) -> Option<&HashMap<String, String>> {
+        self.exports.as_ref()
+    }
+
+    pub fn workspace(&self) -> Option<&[String]> {
+        self.workspace.as_deref()
+    }
+
+    pub fn include(&self) -> Option<&[String]> {
+        self.include.as_deref()
+    }
+
+    pub fn exclude(&self) -> Option<&[String]> {
+        self.exclude.as_deref()
+    }
+
+    pub fn properties(&self) -> Option<&HashMap<String, String>> {
+        self.properties.as_ref()
+    }
+
+    pub fn dynamic(&self) -> Option<&[String]> {
+        self.dynamic.as_deref()
+    }
+
+    pub fn sources(&self) -> Option<&HashMap<String, FieldValue>> {
+        self.sources.as_ref()
+    }
+
+    pub fn from_config(config_file: &ConfigFile) -> Result<Self, MetadataError> {
+        source::load(config_file.source(), Path::new(config_file.file_path()))
     }
 
     pub fn load_from_config_files(
@@ -242,35 +524,27 @@ impl ProjectMetadata {
         Ok(metadata_list)
     }
     pub fn from_detected_config_files(cwd: &str) -> Result<Vec<Self>, MetadataError> {
-        let config_files = vec![
-            ConfigFile::PackageJson(format!("{}/package.json", cwd)),
-            ConfigFile::CargoToml(format!("{}/Cargo.toml", cwd)),
-            ConfigFile::DenoJson(format!("{}/deno.json", cwd)),
-            ConfigFile::PyprojectToml(format!("{}/pyproject.toml", cwd)),
-        ];
-
-        Self::load_from_config_files(config_files)
+        Registry::new().load_detected(Path::new(cwd))
+    }
+    /// Compare the metadata itself, regardless of which table each field came from
+    fn same_metadata(&self, other: &Self) -> bool {
+        let without_sources = |metadata: &Self| Self {
+            sources: None,
+            ..metadata.clone()
+        };
+        without_sources(self) == without_sources(other)
     }
     pub fn check_config_files_equality(cwd: &str) -> Result<(), MetadataError> {
-        let config_files = vec![
-            ConfigFile::PackageJson(format!("{}/package.json", cwd)),
-            ConfigFile::CargoToml(format!("{}/Cargo.toml", cwd)),
-            ConfigFile::DenoJson(format!("{}/deno.json", cwd)),
-            ConfigFile::PyprojectToml(format!("{}/pyproject.toml", cwd)),
-        ];
-
-        let metadata_list = Self::load_from_config_files(config_files.clone())?;
-
-        if metadata_list.is_empty() {
-            return Err(MetadataError::NoFilesFound);
-        }
+        let registry = Registry::new();
+        let detected = registry.detect(Path::new(cwd));
+        let metadata_list = source::load_all(&detected)?;
 
         let first_metadata = &metadata_list[0];
-        let first_config_path = config_files[0].file_path().to_string();
+        let first_config_path = detected[0].0.display().to_string();
 
         for (index, metadata) in metadata_list.iter().enumerate().skip(1) {
-            let current_config_path = config_files[index].file_path().to_string();
-            if first_metadata != metadata {
+            let current_config_path = detected[index].0.display().to_string();
+            if !first_metadata.same_metadata(metadata) {
                 println!(
                     "Difference found between first config file '{}' and config file '{}':",
                     first_config_path, current_config_path
@@ -320,6 +594,84 @@ impl ProjectMetadata {
                         first_metadata.scripts, metadata.scripts
                     );
                 }
+                if first_metadata.maintainers != metadata.maintainers {
+                    println!(
+                        "Maintainers: {:?} vs {:?}",
+                        first_metadata.maintainers, metadata.maintainers
+                    );
+                }
+                if first_metadata.contributors != metadata.contributors {
+                    println!(
+                        "Contributors: {:?} vs {:?}",
+                        first_metadata.contributors, metadata.contributors
+                    );
+                }
+                if first_metadata.license_file != metadata.license_file {
+                    println!(
+                        "License file: {:?} vs {:?}",
+                        first_metadata.license_file, metadata.license_file
+                    );
+                }
+                if first_metadata.readme != metadata.readme {
+                    println!(
+                        "Readme: {:?} vs {:?}",
+                        first_metadata.readme, metadata.readme
+                    );
+                }
+                if first_metadata.urls != metadata.urls {
+                    println!("Urls: {:?} vs {:?}", first_metadata.urls, metadata.urls);
+                }
+                if first_metadata.classifiers != metadata.classifiers {
+                    println!(
+                        "Classifiers: {:?} vs {:?}",
+                        first_metadata.classifiers, metadata.classifiers
+                    );
+                }
+                if first_metadata.engines != metadata.engines {
+                    println!(
+                        "Engines: {:?} vs {:?}",
+                        first_metadata.engines, metadata.engines
+                    );
+                }
+                if first_metadata.tasks != metadata.tasks {
+                    println!("Tasks: {:?} vs {:?}", first_metadata.tasks, metadata.tasks);
+                }
+                if first_metadata.exports != metadata.exports {
+                    println!(
+                        "Exports: {:?} vs {:?}",
+                        first_metadata.exports, metadata.exports
+                    );
+                }
+                if first_metadata.workspace != metadata.workspace {
+                    println!(
+                        "Workspace: {:?} vs {:?}",
+                        first_metadata.workspace, metadata.workspace
+                    );
+                }
+                if first_metadata.include != metadata.include {
+                    println!(
+                        "Include: {:?} vs {:?}",
+                        first_metadata.include, metadata.include
+                    );
+                }
+                if first_metadata.exclude != metadata.exclude {
+                    println!(
+                        "Exclude: {:?} vs {:?}",
+                        first_metadata.exclude, metadata.exclude
+                    );
+                }
+                if first_metadata.properties != metadata.properties {
+                    println!(
+                        "Properties: {:?} vs {:?}",
+                        first_metadata.properties, metadata.properties
+                    );
+                }
+                if first_metadata.dynamic != metadata.dynamic {
+                    println!(
+                        "Dynamic: {:?} vs {:?}",
+                        first_metadata.dynamic, metadata.dynamic
+                    );
+                }
             }
         }
 
@@ -341,7 +693,13 @@ mod tests {
                     Ok(ok_result) =>
                     assert_yaml_snapshot!(ok_result, {
                         "[].scripts" => insta::sorted_redaction(),
-                        "[].dependencies" => insta::sorted_redaction()
+                        "[].dependencies" => insta::sorted_redaction(),
+                        "[].urls" => insta::sorted_redaction(),
+                        "[].engines" => insta::sorted_redaction(),
+                        "[].tasks" => insta::sorted_redaction(),
+                        "[].exports" => insta::sorted_redaction(),
+                        "[].properties" => insta::sorted_redaction(),
+                        "[].sources" => insta::sorted_redaction()
                     })
                 }
             }
@@ -410,6 +768,55 @@ mod tests {
         ]
     );
 
+    generate_snapshot_test!(
+        test_pyproject_pep621,
+        vec![ConfigFile::PyprojectToml(
+            "tests/pyproject-pep621.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_pyproject_httpx,
+        vec![ConfigFile::PyprojectToml(
+            "tests/pyproject-httpx.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_pyproject_poetry,
+        vec![ConfigFile::PyprojectToml(
+            "tests/pyproject-poetry.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_pyproject_flit,
+        vec![ConfigFile::PyprojectToml(
+            "tests/pyproject-flit.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_pyproject_setuptools_dynamic,
+        vec![ConfigFile::PyprojectToml(
+            "tests/setuptools-dynamic/pyproject.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_pyproject_pdm_scm,
+        vec![ConfigFile::PyprojectToml(
+            "tests/pyproject-pdm-scm.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_cargo_workspace_root,
+        vec![ConfigFile::CargoToml(
+            "tests/cargo-workspace/Cargo.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_cargo_workspace_member,
+        vec![ConfigFile::CargoToml(
+            "tests/cargo-workspace/crates/app/Cargo.toml".to_string()
+        )]
+    );
+
     generate_snapshot_test!(
         test_error_path_1,
         vec![ConfigFile::PackageJson(
@@ -437,4 +844,422 @@ mod tests {
             ConfigFile::CargoToml("tests/nonexistent.toml".to_string())
         ]
     );
+    generate_snapshot_test!(
+        test_package_json_specifiers,
+        vec![ConfigFile::PackageJson(
+            "tests/package-specifiers.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_package_json_dependency_groups,
+        vec![ConfigFile::PackageJson(
+            "tests/package-groups.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_package_json_people,
+        vec![ConfigFile::PackageJson(
+            "tests/package-people.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_deno_jsonc_jsr_package,
+        vec![ConfigFile::DenoJsonc("tests/deno-jsr.jsonc".to_string())]
+    );
+    generate_snapshot_test!(
+        test_error_path_deno_jsonc_syntax_error,
+        vec![ConfigFile::DenoJsonc(
+            "tests/deno-invalid.jsonc".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_deno_jsonc_unterminated_comment,
+        vec![ConfigFile::DenoJsonc(
+            "tests/deno-unterminated-comment.jsonc".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_package_json_with_comments,
+        vec![ConfigFile::PackageJson(
+            "tests/package-comments.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_composer_json,
+        vec![ConfigFile::ComposerJson("tests/composer.json".to_string())]
+    );
+    generate_snapshot_test!(
+        test_go_mod,
+        vec![ConfigFile::GoMod("tests/go.mod".to_string())]
+    );
+    generate_snapshot_test!(
+        test_error_path_go_mod_invalid_version,
+        vec![ConfigFile::GoMod("tests/go-invalid.mod".to_string())]
+    );
+    generate_snapshot_test!(
+        test_pom_xml_parent_inheritance,
+        vec![ConfigFile::PomXml("tests/maven/app/pom.xml".to_string())]
+    );
+    generate_snapshot_test!(
+        test_error_path_pom_xml_syntax_error,
+        vec![ConfigFile::PomXml("tests/pom-invalid.xml".to_string())]
+    );
+    generate_snapshot_test!(
+        test_pubspec_yaml,
+        vec![ConfigFile::PubspecYaml("tests/pubspec.yaml".to_string())]
+    );
+    generate_snapshot_test!(
+        test_chart_yaml,
+        vec![ConfigFile::ChartYaml("tests/Chart.yaml".to_string())]
+    );
+    generate_snapshot_test!(
+        test_yaml_numeric_versions,
+        vec![
+            ConfigFile::PubspecYaml("tests/numeric-versions/pubspec.yaml".to_string()),
+            ConfigFile::ChartYaml("tests/numeric-versions/Chart.yaml".to_string())
+        ]
+    );
+    generate_snapshot_test!(
+        test_error_path_pubspec_yaml_syntax_error,
+        vec![ConfigFile::PubspecYaml(
+            "tests/pubspec-invalid.yaml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_csproj_directory_build_props,
+        vec![ConfigFile::Csproj(
+            "tests/dotnet/src/Confy.Client/Confy.Client.csproj".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_csproj_without_version,
+        vec![ConfigFile::Csproj(
+            "tests/dotnet-unversioned/Scratch.csproj".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_fsproj,
+        vec![ConfigFile::Csproj(
+            "tests/dotnet/src/Confy.Cli/Confy.Cli.fsproj".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_gemspec_version_constant,
+        vec![ConfigFile::Gemspec("tests/ruby/confy.gemspec".to_string())]
+    );
+    generate_snapshot_test!(
+        test_gemfile,
+        vec![ConfigFile::Gemfile("tests/ruby/Gemfile".to_string())]
+    );
+    generate_snapshot_test!(
+        test_error_path_gemspec_unterminated_string,
+        vec![ConfigFile::Gemspec(
+            "tests/ruby-invalid.gemspec".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_setup_cfg_directives,
+        vec![ConfigFile::SetupCfg(
+            "tests/setuptools-legacy/setup.cfg".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_setup_py_literals,
+        vec![ConfigFile::SetupPy(
+            "tests/setuptools-legacy/setup.py".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_setup_cfg_unterminated_section,
+        vec![ConfigFile::SetupCfg("tests/setup-invalid.cfg".to_string())]
+    );
+    generate_snapshot_test!(
+        test_citation_cff,
+        vec![ConfigFile::CitationCff(
+            "tests/citation/CITATION.cff".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_codemeta_json,
+        vec![ConfigFile::CodemetaJson(
+            "tests/citation/codemeta.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_jsr_json,
+        vec![ConfigFile::JsrJson("tests/jsr.json".to_string())]
+    );
+    generate_snapshot_test!(
+        test_bower_json,
+        vec![ConfigFile::BowerJson("tests/bower.json".to_string())]
+    );
+    generate_snapshot_test!(
+        test_vcpkg_json,
+        vec![ConfigFile::VcpkgJson("tests/vcpkg.json".to_string())]
+    );
+    generate_snapshot_test!(
+        test_gradle_kotlin_dsl_catalog,
+        vec![ConfigFile::BuildGradleKts(
+            "tests/gradle/app/build.gradle.kts".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_gradle_groovy_dsl,
+        vec![ConfigFile::BuildGradle(
+            "tests/gradle/core/build.gradle".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_gradle_settings_root,
+        vec![ConfigFile::BuildGradleKts(
+            "tests/gradle/build.gradle.kts".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_gradle_unclosed_block,
+        vec![ConfigFile::BuildGradleKts(
+            "tests/gradle-invalid.gradle.kts".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_r_description,
+        vec![ConfigFile::RDescription("tests/r/DESCRIPTION".to_string())]
+    );
+    generate_snapshot_test!(
+        test_julia_project_toml,
+        vec![ConfigFile::JuliaProjectToml(
+            "tests/julia/Project.toml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_cabal_stanzas,
+        vec![ConfigFile::Cabal("tests/haskell/weather.cabal".to_string())]
+    );
+    generate_snapshot_test!(
+        test_error_path_cabal_unknown_section,
+        vec![ConfigFile::Cabal("tests/cabal-invalid.cabal".to_string())]
+    );
+    generate_snapshot_test!(
+        test_conda_environment_yml,
+        vec![ConfigFile::EnvironmentYml(
+            "tests/conda/environment.yml".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_pixi_toml,
+        vec![ConfigFile::PixiToml("tests/pixi/pixi.toml".to_string())]
+    );
+    generate_snapshot_test!(
+        test_zig_build_zig_zon,
+        vec![ConfigFile::BuildZigZon(
+            "tests/zig/build.zig.zon".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_dune_project,
+        vec![ConfigFile::DuneProject(
+            "tests/ocaml/dune-project".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_opam,
+        vec![ConfigFile::Opam("tests/ocaml/weather.opam".to_string())]
+    );
+    generate_snapshot_test!(
+        test_error_path_zon_missing_comma,
+        vec![ConfigFile::BuildZigZon(
+            "tests/zon-invalid.build.zig.zon".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_dune_unclosed_stanza,
+        vec![ConfigFile::DuneProject(
+            "tests/dune-invalid.dune-project".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_opam_missing_version,
+        vec![ConfigFile::Opam("tests/opam-invalid.opam".to_string())]
+    );
+    generate_snapshot_test!(
+        test_package_json_invalid_specifier,
+        vec![ConfigFile::PackageJson(
+            "tests/package-invalid-specifier.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_package_json_non_string_specifier,
+        vec![ConfigFile::PackageJson(
+            "tests/package-non-string-specifier.json".to_string()
+        )]
+    );
+    generate_snapshot_test!(
+        test_error_path_cargo_workspace_missing_field,
+        vec![ConfigFile::CargoToml(
+            "tests/cargo-workspace/crates/broken/Cargo.toml".to_string()
+        )]
+    );
+
+    /// In-house `key: value` manifest, registered the way a downstream crate would
+    struct ServiceManifest;
+
+    impl MetadataSource for ServiceManifest {
+        fn name(&self) -> &str {
+            "service manifest"
+        }
+
+        fn patterns(&self) -> &[&str] {
+            &["*.meta"]
+        }
+
+        fn parse(&self, contents: &str) -> Result<ProjectMetadata, MetadataError> {
+            let mut fields = HashMap::new();
+            for (index, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let (key, value) = line.split_once(':').ok_or_else(|| {
+                    let message = format!("{}: expected `key: value`", index + 1);
+                    MetadataError::ParseError(self.name().to_string(), message)
+                })?;
+                fields.insert(key.trim(), value.trim());
+            }
+            let field = |key: &str| fields.get(key).copied();
+            let dependencies = field("requires")
+                .unwrap_or_default()
+                .split(',')
+                .filter(|requirement| !requirement.trim().is_empty())
+                .map(|requirement| {
+                    let mut parts = requirement.split_whitespace();
+                    let name = parts.next().unwrap_or_default();
+                    DependencySpec::new(name, DependencyKind::Runtime)
+                        .version(parts.next(), VersionSyntax::Cargo)
+                })
+                .collect();
+            let mut metadata = ProjectMetadata::new(
+                field("name").unwrap_or_default(),
+                field("version").unwrap_or_default(),
+            )
+            .with_dependencies(dependencies);
+            if let Some(description) = field("description") {
+                metadata = metadata.with_description(description);
+            }
+            if let Some(owner) = field("owner") {
+                metadata = metadata.with_maintainers(vec![Person::parse(owner)]);
+            }
+            Ok(metadata)
+        }
+
+        fn write(&self, metadata: &ProjectMetadata) -> Result<String, MetadataError> {
+            let mut written = format!(
+                "name: {}\nversion: {}\n",
+                metadata.name(),
+                metadata.version()
+            );
+            if let Some(description) = metadata.description() {
+                written.push_str(&format!("description: {description}\n"));
+            }
+            Ok(written)
+        }
+    }
+
+    #[test]
+    fn test_registry_detects_fsproj() {
+        let registry = Registry::new();
+        let detected = registry.detect(Path::new("tests/dotnet/src/Confy.Cli"));
+        let paths: Vec<_> = detected.iter().map(|(path, _)| path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [Path::new("tests/dotnet/src/Confy.Cli/Confy.Cli.fsproj")]
+        );
+    }
+
+    #[test]
+    fn test_registry_custom_source() {
+        let mut registry = Registry::new();
+        registry.register(ServiceManifest);
+        let result = registry.load_detected(Path::new("tests/custom"));
+        assert_yaml_snapshot!(result);
+    }
+
+    #[test]
+    fn test_registry_write() {
+        let mut registry = Registry::new();
+        registry.register(ServiceManifest);
+        let metadata = ProjectMetadata::new("billing-service", "2.4.0")
+            .with_description("Invoices and payments");
+        let source = registry.find(Path::new("billing.meta")).unwrap();
+        let written = source.write(&metadata).unwrap();
+        assert_eq!(source.parse(&written).unwrap(), metadata);
+
+        let source = registry.find(Path::new("Cargo.toml")).unwrap();
+        assert!(matches!(
+            source.write(&metadata),
+            Err(MetadataError::WriteUnsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_metadata_accessors() {
+        let metadata = ProjectMetadata::new("billing-service", "2.4.0")
+            .with_task(
+                "deploy",
+                Task::new(Some("./deploy.sh"), vec!["build".to_string()], None),
+            )
+            .with_dynamic(vec!["version".to_string()])
+            .with_source(
+                "version",
+                FieldValue::Dynamic {
+                    source: "setup.py".to_string(),
+                },
+            );
+        let task = &metadata.tasks().unwrap()["deploy"];
+        assert_eq!(task.command(), Some("./deploy.sh"));
+        assert_eq!(task.dependencies(), Some(&["build".to_string()][..]));
+        assert_eq!(metadata.dynamic(), Some(&["version".to_string()][..]));
+        assert!(metadata.sources().unwrap().contains_key("version"));
+        assert_eq!(metadata.description(), None);
+    }
+
+    #[test]
+    fn test_registry_parse_without_path() {
+        let registry = Registry::new();
+        let source = registry.find(Path::new("Cargo.toml")).unwrap();
+        let contents = "[package]\nname = \"member\"\nversion.workspace = true\n";
+        assert!(matches!(
+            source.parse(contents),
+            Err(MetadataError::WorkspaceInheritanceError(_))
+        ));
+        let source = registry.find(Path::new("confy.opam")).unwrap();
+        let metadata = source
+            .parse("opam-version: \"2.0\"\nversion: \"0.3.0\"\n")
+            .unwrap();
+        assert_eq!(metadata.name, "");
+    }
+
+    #[test]
+    fn test_package_json_invalid_specifier_is_unparsed() {
+        let metadata = ProjectMetadata::from_config(&ConfigFile::PackageJson(
+            "tests/package-invalid-specifier.json".to_string(),
+        ))
+        .unwrap();
+        let version = metadata.dependencies.unwrap()[0].version.clone().unwrap();
+        assert_ne!(version, VersionReq::parse(">=1.0.0", VersionSyntax::Exact));
+        assert_eq!(version, VersionReq::unparsed(">=1.0.0 <"));
+    }
+
+    #[test]
+    fn test_empty_license_list_is_no_license() {
+        let metadata = builtin::BOWER_JSON
+            .parse(r#"{"name": "confy", "license": []}"#)
+            .unwrap();
+        assert_eq!(metadata.license(), None);
+    }
+
+    #[test]
+    fn test_composer_tilde_is_compatible_release() {
+        let composer = VersionReq::parse("~1.2", VersionSyntax::Composer);
+        assert_ne!(composer, npm::parse_range("~1.2").unwrap());
+        assert_eq!(composer, VersionReq::parse("~=1.2", VersionSyntax::Exact));
+    }
 }
@@ -0,0 +1,395 @@
+//! MSBuild project files of SDK-style .NET projects (`*.csproj`, `*.fsproj`, `*.vbproj`).
+//!
+//! Properties are evaluated in order like MSBuild does, starting with the `Directory.Build.props`
+//! file the SDK imports from the closest parent directory, and following the imports it makes of
+//! the ones further up. Conditions are only understood when they compare two strings; properties
+//! under other conditions are left out.
+use crate::dependency::{
+    DependencyKind, DependencySource, DependencySpec, VersionReq, VersionSyntax,
+};
+use crate::npm::SpecifierError;
+use crate::util::{canonical, located, relative_to};
+use crate::xml;
+use crate::{FieldValue, MetadataError, Person, ProjectMetadata};
+use roxmltree::Node;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Properties the package metadata is read from, and the field each one sets, by precedence
+const FIELDS: [(&str, &str); 12] = [
+    ("PackageId", "name"),
+    ("Version", "version"),
+    ("VersionPrefix", "version"),
+    ("VersionSuffix", "version"),
+    ("Description", "description"),
+    ("Authors", "authors"),
+    ("PackageLicenseExpression", "license"),
+    ("PackageLicenseFile", "license_file"),
+    ("PackageReadmeFile", "readme"),
+    ("PackageTags", "keywords"),
+    ("PackageProjectUrl", "urls"),
+    ("RepositoryUrl", "urls"),
+];
+
+pub(crate) fn from_xml(
+    contents: &str,
+    project_path: Option<&Path>,
+) -> Result<ProjectMetadata, MetadataError> {
+    let project_dir = project_path.map(|path| path.parent().unwrap_or(Path::new("")));
+    let dir = project_dir.unwrap_or(Path::new(""));
+    let project_name = project_path
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut evaluation = Evaluation {
+        project_dir: project_dir.map(Path::to_path_buf),
+        properties: HashMap::new(),
+        references: Vec::new(),
+        visited: project_path.map(canonical).into_iter().collect(),
+    };
+    evaluation.reserve("MSBuildProjectName", &project_name);
+    evaluation.reserve("MSBuildProjectDirectory", &dir.display().to_string());
+    // The SDK imports the closest `Directory.Build.props` before the project's own content
+    if let Some(props) = project_dir.and_then(|dir| file_above(dir, "Directory.Build.props")) {
+        evaluation.import(&props)?;
+    }
+    let document = xml::parse(contents)
+        .map_err(|err| MetadataError::ParseError("XML".to_string(), located(project_path, err)))?;
+    evaluation.evaluate(document.root_element(), dir, "project")?;
+
+    let property = |name: &str| evaluation.get(name).filter(|value| !value.is_empty());
+    let mut sources = HashMap::new();
+    for (name, field) in FIELDS {
+        if let Some(property) = evaluation.properties.get(&name.to_ascii_lowercase()) {
+            sources
+                .entry(field.to_string())
+                .or_insert_with(|| FieldValue::from_table(&property.source));
+        }
+    }
+    // The SDK builds the version from its prefix and suffix unless it is set. Without either,
+    // the version is the SDK's `1.0.0` default, which the project does not declare.
+    let version = match (property("Version"), property("VersionPrefix")) {
+        (Some(version), _) => version.to_string(),
+        (None, Some(prefix)) => match property("VersionSuffix") {
+            Some(suffix) => format!("{prefix}-{suffix}"),
+            None => prefix.to_string(),
+        },
+        (None, None) => String::new(),
+    };
+    if version.is_empty() {
+        sources.remove("version");
+    }
+    let list = |name: &str| {
+        property(name).map(|value| {
+            value
+                .split(';')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+    let mut urls = HashMap::new();
+    for (key, name) in [
+        ("homepage", "PackageProjectUrl"),
+        ("repository", "RepositoryUrl"),
+    ] {
+        if let Some(url) = property(name) {
+            urls.insert(key.to_string(), url.to_string());
+        }
+    }
+    let engines = property("TargetFrameworks")
+        .or_else(|| property("TargetFramework"))
+        .map(|frameworks| HashMap::from([("dotnet".to_string(), frameworks.to_string())]));
+    let properties: HashMap<String, String> = evaluation
+        .properties
+        .values()
+        .filter(|property| property.source != "reserved")
+        .map(|property| (property.name.clone(), property.value.clone()))
+        .collect();
+    let mut dependencies = evaluation.references.clone();
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        name: property("PackageId")
+            .or_else(|| property("AssemblyName"))
+            .unwrap_or(&project_name)
+            .to_string(),
+        version,
+        description: property("Description").map(|s| s.to_string()),
+        authors: list("Authors")
+            .map(|authors| authors.iter().map(|author| Person::parse(author)).collect()),
+        license: property("PackageLicenseExpression").map(|s| s.to_string()),
+        license_file: property("PackageLicenseFile").map(|s| s.to_string()),
+        readme: property("PackageReadmeFile").map(|s| s.to_string()),
+        keywords: list("PackageTags"),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines,
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties: (!properties.is_empty()).then_some(properties),
+        sources: (!sources.is_empty()).then_some(sources),
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, Clone)]
+struct Property {
+    /// Name as last written, property names being case insensitive
+    name: String,
+    value: String,
+    /// Path of the file setting it, relative to the project directory
+    source: String,
+}
+
+/// State of the evaluation of a project and the files it imports
+struct Evaluation {
+    /// Directory of the project, without which imports are not followed
+    project_dir: Option<PathBuf>,
+    /// Properties keyed by their lowercase name
+    properties: HashMap<String, Property>,
+    references: Vec<DependencySpec>,
+    visited: HashSet<PathBuf>,
+}
+
+impl Evaluation {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.properties
+            .get(&name.to_ascii_lowercase())
+            .map(|property| property.value.as_str())
+    }
+
+    fn set(&mut self, name: &str, value: String, source: &str) {
+        self.properties.insert(
+            name.to_ascii_lowercase(),
+            Property {
+                name: name.to_string(),
+                value,
+                source: source.to_string(),
+            },
+        );
+    }
+
+    /// Set a property MSBuild defines itself
+    fn reserve(&mut self, name: &str, value: &str) {
+        self.set(name, value.to_string(), "reserved");
+    }
+
+    /// Evaluate an imported file, whose `MSBuildThisFileDirectory` is its own directory
+    fn import(&mut self, path: &Path) -> Result<(), MetadataError> {
+        let Some(project_dir) = &self.project_dir else {
+            return Ok(());
+        };
+        let source = relative_to(path, project_dir);
+        if !self.visited.insert(canonical(path)) {
+            return Ok(());
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|_| MetadataError::ReadError(path.display().to_string()))?;
+        let document = xml::parse(&contents).map_err(|err| {
+            MetadataError::ParseError("XML".to_string(), format!("{}:{err}", path.display()))
+        })?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        self.evaluate(document.root_element(), dir, &source)
+    }
+
+    /// Evaluate the property groups, item groups and imports of `project` in order
+    fn evaluate(&mut self, project: Node, dir: &Path, source: &str) -> Result<(), MetadataError> {
+        let this_dir = format!("{}/", dir.display());
+        self.reserve("MSBuildThisFileDirectory", &this_dir);
+        for node in project.children().filter(|node| node.is_element()) {
+            let condition = node.attribute("Condition");
+            match node.tag_name().name() {
+                "PropertyGroup" if self.condition(condition) == Some(true) => {
+                    for property in node.children().filter(|node| node.is_element()) {
+                        if self.condition(property.attribute("Condition")) != Some(true) {
+                            continue;
+                        }
+                        let value = self.expand(property.text().unwrap_or("").trim());
+                        self.set(property.tag_name().name(), value, source);
+                    }
+                }
+                "ItemGroup" => {
+                    // Multi-targeting projects condition references on the framework, which only
+                    // the inner builds set
+                    let target =
+                        condition.filter(|condition| condition.contains("TargetFramework"));
+                    if target.is_none() && self.condition(condition) != Some(true) {
+                        continue;
+                    }
+                    for item in node.children().filter(|node| node.is_element()) {
+                        if self.condition(item.attribute("Condition")) == Some(true) {
+                            self.item(item, target)?;
+                        }
+                    }
+                }
+                "Import" if self.condition(condition) == Some(true) => {
+                    if let Some(path) =
+                        self.import_path(node.attribute("Project").unwrap_or(""), dir)
+                    {
+                        self.import(&path)?;
+                        // Imports evaluate with their own directory
+                        self.reserve("MSBuildThisFileDirectory", &this_dir);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a `PackageReference` or `ProjectReference` item; `Update` items change the references
+    /// already included
+    fn item(&mut self, item: Node, target: Option<&str>) -> Result<(), MetadataError> {
+        // Metadata is written as an attribute or as a child element
+        let metadata = |name: &str| {
+            item.attribute(name)
+                .map(|value| value.to_string())
+                .or_else(|| xml::text(item, name))
+                .map(|value| self.expand(&value))
+        };
+        match item.tag_name().name() {
+            "PackageReference" => {
+                let version = metadata("Version")
+                    .or_else(|| metadata("VersionOverride"))
+                    .map(|version| nuget_version(&version))
+                    .transpose()
+                    .map_err(|(raw, reason)| {
+                        MetadataError::DependencySpecifierError(
+                            item.attribute("Include")
+                                .or(item.attribute("Update"))
+                                .unwrap_or("")
+                                .to_string(),
+                            SpecifierError::InvalidRange(raw, reason),
+                        )
+                    })?;
+                // Assets kept private do not flow to the consumers of the package
+                let kind = match metadata("PrivateAssets") {
+                    Some(assets) if assets.eq_ignore_ascii_case("all") => DependencyKind::Dev,
+                    _ => DependencyKind::Runtime,
+                };
+                if let Some(name) = metadata("Include") {
+                    self.references.push(
+                        DependencySpec::new(&name, kind)
+                            .with_version(version)
+                            .target(target),
+                    );
+                } else if let Some(name) = metadata("Update") {
+                    for reference in self
+                        .references
+                        .iter_mut()
+                        .filter(|reference| reference.name.eq_ignore_ascii_case(&name))
+                    {
+                        if version.is_some() {
+                            reference.version.clone_from(&version);
+                        }
+                        if kind == DependencyKind::Dev {
+                            reference.kind = kind;
+                        }
+                    }
+                }
+            }
+            "ProjectReference" => {
+                if let Some(path) = metadata("Include") {
+                    let path = path.replace('\\', "/");
+                    let name = Path::new(&path)
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.clone());
+                    self.references.push(
+                        DependencySpec::new(&name, DependencyKind::Runtime)
+                            .source(DependencySource::Path { path })
+                            .target(target),
+                    );
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// File an `Import` refers to: `Directory.Build.props` files chain to the next one up with
+    /// `GetPathOfFileAbove`, other imports are paths relative to the importing file
+    fn import_path(&self, project: &str, dir: &Path) -> Option<PathBuf> {
+        self.project_dir.as_ref()?;
+        if project.contains("GetPathOfFileAbove") {
+            let file = project.split('\'').nth(1)?;
+            return file_above(dir.parent()?, file);
+        }
+        let project = self.expand(project).replace('\\', "/");
+        // Property functions and SDK imports are beyond a static reading
+        if project.contains("$(") || project.is_empty() {
+            return None;
+        }
+        let path = dir.join(project);
+        path.is_file().then_some(path)
+    }
+
+    /// Replace `$(Name)` references, undefined properties being empty; property functions are left
+    /// as written
+    fn expand(&self, value: &str) -> String {
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("$(") {
+            let Some(end) = rest[start..].find(')').map(|end| start + end) else {
+                break;
+            };
+            expanded.push_str(&rest[..start]);
+            let name = &rest[start + 2..end];
+            match name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                true => expanded.push_str(self.get(name).unwrap_or("")),
+                false => expanded.push_str(&rest[start..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+
+    /// Evaluate `'left' == 'right'` and `'left' != 'right'` conditions, which compare case
+    /// insensitively; `None` for any other condition
+    fn condition(&self, condition: Option<&str>) -> Option<bool> {
+        let Some(condition) = condition.map(str::trim).filter(|c| !c.is_empty()) else {
+            return Some(true);
+        };
+        let (left, equal, right) = match (condition.split_once("=="), condition.split_once("!=")) {
+            (Some((left, right)), _) => (left, true, right),
+            (None, Some((left, right))) => (left, false, right),
+            (None, None) => return None,
+        };
+        let quoted = |side: &str| {
+            let side = side.trim();
+            side.strip_prefix('\'')?
+                .strip_suffix('\'')
+                .filter(|inner| !inner.contains('\''))
+                .map(|inner| self.expand(inner))
+        };
+        let (left, right) = (quoted(left)?, quoted(right)?);
+        Some(left.eq_ignore_ascii_case(&right) == equal)
+    }
+}
+
+/// Parse a NuGet version: a bare version is a minimum, `1.2.*` floats to the latest `1.2.x`,
+/// and ranges use the interval notation
+fn nuget_version(version: &str) -> Result<VersionReq, (String, String)> {
+    let floating = version
+        .trim()
+        .strip_suffix('*')
+        .map(|prefix| prefix.trim_end_matches('.'));
+    let parsed = match floating {
+        Some("") => Ok(VersionReq::new(version, vec![Vec::new()])),
+        Some(prefix) => {
+            Ok(VersionReq::parse(&format!("~{prefix}"), VersionSyntax::Minimum).with_raw(version))
+        }
+        None => VersionReq::parse_intervals(version, VersionSyntax::Minimum),
+    };
+    parsed.map_err(|reason| (version.to_string(), reason))
+}
+
+/// Closest `file` in `dir` or its ancestors
+fn file_above(dir: &Path, file: &str) -> Option<PathBuf> {
+    let dir = canonical(dir);
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(file))
+        .find(|candidate| candidate.is_file())
+}
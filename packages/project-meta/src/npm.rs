@@ -0,0 +1,438 @@
+//! npm dependency specifier parser, following the grammar of [npm-package-arg] and the
+//! [node-semver] ranges, plus the `workspace:`, `link:` and `portal:` protocols of pnpm and
+//! Yarn. Other protocols, such as pnpm's `catalog:` or Yarn's `patch:`, are kept as URLs.
+//!
+//! [npm-package-arg]: https://github.com/npm/npm-package-arg
+//! [node-semver]: https://github.com/npm/node-semver#ranges
+use crate::dependency::{
+    Comparator, DependencyKind, DependencySource, DependencySpec, Op, VersionReq,
+};
+use crate::MetadataError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SpecifierError {
+    #[error("expected a string, found `{0}`")]
+    NotAString(String),
+    #[error("invalid version range `{0}`: {1}")]
+    InvalidRange(String, String),
+    #[error("invalid alias `{0}`: {1}")]
+    InvalidAlias(String, String),
+    #[error("invalid git specifier `{0}`: no repository")]
+    InvalidGit(String),
+}
+
+/// Git hosts and protocols npm clones from
+const GIT_PREFIXES: [&str; 9] = [
+    "git+ssh://",
+    "git+https://",
+    "git+http://",
+    "git+file://",
+    "git://",
+    "github:",
+    "gitlab:",
+    "bitbucket:",
+    "gist:",
+];
+
+/// Parse a map of dependency names to specifiers, as found in `package.json`
+pub(crate) fn dependencies(
+    deps: &serde_json::Map<String, serde_json::Value>,
+    kind: DependencyKind,
+    group: Option<&str>,
+) -> Result<Vec<DependencySpec>, MetadataError> {
+    deps.iter()
+        .map(|(name, value)| match value.as_str() {
+            Some(specifier) => Ok(dependency(name, kind, specifier).group(group)),
+            None => Err(MetadataError::DependencySpecifierError(
+                name.clone(),
+                SpecifierError::NotAString(value.to_string()),
+            )),
+        })
+        .collect()
+}
+
+/// Parse the `specifier` of the `name` dependency. An invalid one is kept verbatim as an unparsed
+/// requirement, since package managers only reject it when installing.
+pub(crate) fn dependency(name: &str, kind: DependencyKind, specifier: &str) -> DependencySpec {
+    parse_specifier(name, kind, specifier).unwrap_or_else(|_| {
+        DependencySpec::new(name, kind).with_version(Some(VersionReq::unparsed(specifier)))
+    })
+}
+
+/// Parse the `specifier` of the `name` dependency
+pub(crate) fn parse_specifier(
+    name: &str,
+    kind: DependencyKind,
+    specifier: &str,
+) -> Result<DependencySpec, SpecifierError> {
+    let specifier = specifier.trim();
+    let dependency = DependencySpec::new(name, kind);
+    if let Some((registry, aliased)) = ["npm", "jsr"].into_iter().find_map(|registry| {
+        Some((
+            registry,
+            specifier.strip_prefix(registry)?.strip_prefix(':')?,
+        ))
+    }) {
+        return alias(registry, aliased)
+            .map(|(version, source)| dependency.source(source).with_version(version));
+    }
+    if let Some(range) = specifier.strip_prefix("workspace:") {
+        // `workspace:*`, `workspace:^` and `workspace:~` stand for the version of the local package
+        let version = match range {
+            "*" | "^" | "~" | "" => None,
+            range => Some(parse_range(range)?),
+        };
+        return Ok(dependency
+            .source(DependencySource::Workspace)
+            .with_version(version));
+    }
+    if let Some(path) = ["file:", "link:", "portal:"]
+        .into_iter()
+        .find_map(|prefix| specifier.strip_prefix(prefix))
+    {
+        return Ok(dependency.source(DependencySource::Path {
+            path: path.to_string(),
+        }));
+    }
+    if GIT_PREFIXES
+        .iter()
+        .any(|prefix| specifier.starts_with(prefix))
+        || is_scp_git(specifier)
+    {
+        return git(specifier, specifier)
+            .map(|(version, source)| dependency.source(source).with_version(version));
+    }
+    if specifier.starts_with("http://")
+        || specifier.starts_with("https://")
+        || has_protocol(specifier)
+    {
+        return Ok(dependency.source(DependencySource::Url {
+            url: specifier.to_string(),
+        }));
+    }
+    if is_path(specifier) {
+        return Ok(dependency.source(DependencySource::Path {
+            path: specifier.to_string(),
+        }));
+    }
+    if is_github_shorthand(specifier) {
+        return git(&format!("github:{specifier}"), specifier)
+            .map(|(version, source)| dependency.source(source).with_version(version));
+    }
+    registry_version(specifier, None)
+        .map(|(version, source)| dependency.source(source).with_version(version))
+}
+
+/// `npm:package@range`, where the package may be scoped
+fn alias(
+    registry: &str,
+    aliased: &str,
+) -> Result<(Option<VersionReq>, DependencySource), SpecifierError> {
+    let invalid =
+        |reason: &str| SpecifierError::InvalidAlias(aliased.to_string(), reason.to_string());
+    // The version follows the last `@`, the first one may start a scope
+    let (package, range) = match aliased.rfind('@') {
+        Some(at) if at > 0 => (&aliased[..at], &aliased[at + 1..]),
+        _ => (aliased, ""),
+    };
+    let bare = package.strip_prefix('@').unwrap_or(package);
+    if bare.is_empty() || bare.starts_with('/') || bare.ends_with('/') {
+        return Err(invalid("missing package name"));
+    }
+    if package.starts_with('@') != package.contains('/') {
+        return Err(invalid("scoped package names take the `@scope/name` form"));
+    }
+    let (version, source) = registry_version(range, Some(registry)).map_err(|err| match err {
+        SpecifierError::InvalidRange(_, reason) => invalid(&reason),
+        err => err,
+    })?;
+    let dist_tag = match source {
+        DependencySource::Registry { dist_tag, .. } => dist_tag,
+        _ => None,
+    };
+    Ok((
+        version,
+        DependencySource::Alias {
+            registry: Some(registry.to_string()),
+            package: package.to_string(),
+            dist_tag,
+        },
+    ))
+}
+
+/// A range, or a dist-tag such as `latest` when it is not one
+fn registry_version(
+    specifier: &str,
+    registry: Option<&str>,
+) -> Result<(Option<VersionReq>, DependencySource), SpecifierError> {
+    let source = |dist_tag: Option<&str>| DependencySource::Registry {
+        registry: registry.map(|s| s.to_string()),
+        dist_tag: dist_tag.map(|s| s.to_string()),
+    };
+    match parse_range(specifier) {
+        Ok(version) => Ok(((!version.accepts_any()).then_some(version), source(None))),
+        Err(_) if is_dist_tag(specifier) => Ok((None, source(Some(specifier)))),
+        Err(err) => Err(err),
+    }
+}
+
+/// `url#committish`, where a `semver:` committish selects a tag by range
+fn git(
+    url: &str,
+    specifier: &str,
+) -> Result<(Option<VersionReq>, DependencySource), SpecifierError> {
+    let (url, committish) = match url.split_once('#') {
+        Some((url, committish)) => (url, Some(committish)),
+        None => (url, None),
+    };
+    let repository = GIT_PREFIXES
+        .iter()
+        .find_map(|prefix| url.strip_prefix(prefix))
+        .unwrap_or(url);
+    if repository.is_empty() {
+        return Err(SpecifierError::InvalidGit(specifier.to_string()));
+    }
+    let (version, rev) = match committish.map(|c| (c, c.strip_prefix("semver:"))) {
+        Some((_, Some(range))) => (Some(parse_range(range)?), None),
+        Some((committish, None)) => (None, Some(committish.to_string())),
+        None => (None, None),
+    };
+    Ok((
+        version,
+        DependencySource::Git {
+            url: url.to_string(),
+            branch: None,
+            tag: None,
+            rev,
+        },
+    ))
+}
+
+/// scp-like `user@host:path` git remote, such as `git@github.com:user/repo.git`
+fn is_scp_git(specifier: &str) -> bool {
+    let Some((remote, _)) = specifier.split_once(':') else {
+        return false;
+    };
+    match remote.split_once('@') {
+        Some((user, host)) => {
+            let valid = |part: &str| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            };
+            valid(user) && valid(host)
+        }
+        None => false,
+    }
+}
+
+/// `protocol:...` for a protocol this parser does not know, a single letter being a Windows
+/// drive rather than a protocol
+fn has_protocol(specifier: &str) -> bool {
+    specifier.split_once(':').is_some_and(|(protocol, _)| {
+        protocol.len() > 1
+            && protocol.starts_with(|c: char| c.is_ascii_alphabetic())
+            && protocol
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
+fn is_path(specifier: &str) -> bool {
+    ["./", "../", "/", "~/"]
+        .iter()
+        .any(|prefix| specifier.starts_with(prefix))
+        || [".tgz", ".tar.gz", ".tar"]
+            .iter()
+            .any(|extension| specifier.ends_with(extension))
+}
+
+/// `user/repo` or `user/repo#ref`
+fn is_github_shorthand(specifier: &str) -> bool {
+    let repository = specifier.split('#').next().unwrap_or("");
+    match repository.split_once('/') {
+        Some((user, repo)) => {
+            let valid = |part: &str| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            };
+            valid(user) && valid(repo) && !user.starts_with('.')
+        }
+        None => false,
+    }
+}
+
+/// Tags may not look like versions, npm refuses to publish them otherwise
+fn is_dist_tag(specifier: &str) -> bool {
+    let looks_like_version = specifier
+        .strip_prefix(['v', 'V'])
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()));
+    specifier.starts_with(|c: char| c.is_ascii_alphabetic())
+        && !looks_like_version
+        && specifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+}
+
+/// Parse a node-semver range set. Partial and x-ranges become tilde ranges, `1.2` and `1.2.x`
+/// both meaning `~1.2`, and hyphen ranges become a pair of inclusive bounds.
+pub(crate) fn parse_range(raw: &str) -> Result<VersionReq, SpecifierError> {
+    let invalid = |reason: String| SpecifierError::InvalidRange(raw.to_string(), reason);
+    let mut any_of = Vec::new();
+    for alternative in raw.split("||") {
+        let tokens: Vec<&str> = alternative.split_whitespace().collect();
+        let comparators = match tokens.as_slice() {
+            [lower, "-", upper] => {
+                let lower = Partial::parse(lower).map_err(invalid)?;
+                let upper = Partial::parse(upper).map_err(invalid)?;
+                let mut comparators = Vec::new();
+                if !lower.is_any() {
+                    comparators.push(Comparator::new(Op::GreaterEq, lower.to_string()));
+                }
+                if !upper.is_any() {
+                    comparators.push(match upper.is_complete() {
+                        true => Comparator::new(Op::LessEq, upper.to_string()),
+                        false => Comparator::new(Op::Less, upper.bump()),
+                    });
+                }
+                comparators
+            }
+            tokens => {
+                let mut comparators = Vec::new();
+                let mut pending_op: Option<&str> = None;
+                for token in tokens {
+                    // `>= 1.0` puts a space between the operator and the version
+                    if Op::ALL.iter().any(|(symbol, _)| symbol == token) {
+                        if pending_op.replace(token).is_some() {
+                            return Err(invalid(format!("dangling operator before `{token}`")));
+                        }
+                        continue;
+                    }
+                    let token = match pending_op.take() {
+                        Some(op) => format!("{op}{token}"),
+                        None => token.to_string(),
+                    };
+                    comparators.extend(comparator(&token).map_err(invalid)?);
+                }
+                if let Some(op) = pending_op {
+                    return Err(invalid(format!("operator `{op}` has no version")));
+                }
+                comparators
+            }
+        };
+        any_of.push(comparators);
+    }
+    Ok(VersionReq::new(raw, any_of))
+}
+
+/// A single comparator; `None` for `*`, which accepts any version
+fn comparator(token: &str) -> Result<Option<Comparator>, String> {
+    let (op, version) = match Op::split(token) {
+        // PEP 440 operators look like npm ones but are not npm syntax
+        Some((Op::Compatible | Op::Arbitrary, _)) => {
+            return Err(format!("unknown operator in `{token}`"))
+        }
+        Some((op, version)) => (Some(op), version),
+        None => (None, token),
+    };
+    let partial = Partial::parse(version)?;
+    Ok(match (op, partial.is_any()) {
+        (None | Some(Op::Exact), true) => None,
+        (None | Some(Op::Exact), false) if !partial.is_complete() => {
+            Some(Comparator::new(Op::Tilde, partial.to_string()))
+        }
+        (None, false) => Some(Comparator::new(Op::Exact, partial.to_string())),
+        (Some(Op::Caret | Op::Tilde | Op::GreaterEq), true) => None,
+        (Some(_), true) => return Err(format!("`{token}` matches no version")),
+        (Some(op), false) => Some(Comparator::new(op, partial.to_string())),
+    })
+}
+
+/// Version whose trailing components may be missing or wildcards
+struct Partial<'a> {
+    /// Numeric components before the first wildcard
+    numbers: Vec<&'a str>,
+    /// Whether all three components are given
+    complete: bool,
+    /// Prerelease and build metadata, only allowed on complete versions
+    qualifier: &'a str,
+}
+
+impl<'a> Partial<'a> {
+    fn parse(version: &'a str) -> Result<Self, String> {
+        let version = version.strip_prefix(['v', '=']).unwrap_or(version);
+        let (core, qualifier) = match version.find(['-', '+']) {
+            Some(at) => version.split_at(at),
+            None => (version, ""),
+        };
+        let components: Vec<&str> = core.split('.').collect();
+        if components.len() > 3 {
+            return Err(format!("`{version}` has more than three components"));
+        }
+        let mut numbers = Vec::new();
+        let mut wildcard = false;
+        for component in &components {
+            match *component {
+                "x" | "X" | "*" => wildcard = true,
+                number if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => {
+                    if wildcard {
+                        return Err(format!("`{version}` has a number after a wildcard"));
+                    }
+                    numbers.push(number);
+                }
+                component => return Err(format!("`{component}` is not a version component")),
+            }
+        }
+        let complete = numbers.len() == 3;
+        if !qualifier.is_empty() {
+            let valid = qualifier[1..]
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '+'));
+            if !complete || qualifier.len() == 1 || !valid {
+                return Err(format!("`{version}` has an invalid prerelease or build"));
+            }
+        }
+        Ok(Partial {
+            numbers,
+            complete,
+            qualifier,
+        })
+    }
+
+    fn is_any(&self) -> bool {
+        self.numbers.is_empty()
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Smallest version above every version the partial matches, `1.2` giving `1.3.0`
+    fn bump(&self) -> String {
+        let mut numbers: Vec<u64> = self
+            .numbers
+            .iter()
+            .map(|number| number.parse().unwrap_or(u64::MAX - 1))
+            .collect();
+        if let Some(last) = numbers.last_mut() {
+            *last += 1;
+        }
+        numbers.resize(3, 0);
+        numbers
+            .iter()
+            .map(|number| number.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+impl std::fmt::Display for Partial<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.numbers.join("."), self.qualifier)
+    }
+}
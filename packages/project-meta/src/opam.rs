@@ -0,0 +1,659 @@
+//! opam package files: `field: value` pairs where values are strings, identifiers, lists, and
+//! values followed by `{ ... }` options, which on dependencies hold their version constraints
+//! and flags such as `with-test`. `url { ... }` like sections are skipped.
+//!
+//! The constraints and flags are shared with the `depends` of `dune-project`, that generates
+//! opam files.
+use crate::dependency::{Comparator, DependencyKind, DependencySpec, Op, VersionReq};
+use crate::syntax::SyntaxError;
+use crate::util::any_license;
+use crate::{Person, ProjectMetadata};
+use std::collections::HashMap;
+
+/// Relational operators of version constraints and filters
+const RELOPS: [&str; 6] = ["=", "!=", "<", "<=", ">", ">="];
+
+/// Fields read into [`ProjectMetadata`] fields rather than `properties`
+const FIELDS: [&str; 19] = [
+    "name",
+    "version",
+    "synopsis",
+    "description",
+    "maintainer",
+    "authors",
+    "license",
+    "homepage",
+    "bug-reports",
+    "dev-repo",
+    "doc",
+    "tags",
+    "depends",
+    "depopts",
+    "conflicts",
+    "build",
+    "install",
+    "run-test",
+    "remove",
+];
+
+/// A version constraint of a dependency, with the flags telling when it is needed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Formula {
+    /// Operator and version, `>= "1.0"`
+    Constraint(String, String),
+    /// `with-test`, `build`, or a filter on other variables
+    Flag(String),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+    Not(Box<Formula>),
+}
+
+impl Formula {
+    fn flags(&self) -> Vec<&str> {
+        match self {
+            Formula::Flag(flag) => vec![flag.as_str()],
+            Formula::And(formulas) => formulas.iter().flat_map(Formula::flags).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Version comparators as alternatives of requirements, `None` if the version is not
+    /// constrained
+    fn any_of(&self) -> Option<Vec<Vec<Comparator>>> {
+        match self {
+            Formula::Constraint(op, version) => {
+                let (op, _) = Op::split(op)?;
+                Some(vec![vec![Comparator::new(op, version.clone())]])
+            }
+            Formula::And(formulas) => {
+                formulas
+                    .iter()
+                    .filter_map(Formula::any_of)
+                    .reduce(|all, any_of| {
+                        all.iter()
+                            .flat_map(|left| {
+                                any_of
+                                    .iter()
+                                    .map(|right| [left.clone(), right.clone()].concat())
+                            })
+                            .collect()
+                    })
+            }
+            // An alternative without constraint accepts any version
+            Formula::Or(formulas) => formulas
+                .iter()
+                .map(Formula::any_of)
+                .collect::<Option<Vec<_>>>()
+                .map(|alternatives| alternatives.concat()),
+            Formula::Flag(_) | Formula::Not(_) => None,
+        }
+    }
+
+    /// The constraint as opam writes it, flags left out
+    fn render(&self) -> Option<String> {
+        match self {
+            Formula::Constraint(op, version) => Some(format!("{op} {version}")),
+            Formula::And(formulas) => {
+                let constraints: Vec<String> = formulas
+                    .iter()
+                    .filter_map(|formula| match formula {
+                        Formula::Or(_) => Some(format!("({})", formula.render()?)),
+                        formula => formula.render(),
+                    })
+                    .collect();
+                (!constraints.is_empty()).then(|| constraints.join(" & "))
+            }
+            Formula::Or(formulas) => formulas
+                .iter()
+                .map(Formula::render)
+                .collect::<Option<Vec<_>>>()
+                .map(|alternatives| alternatives.join(" | ")),
+            Formula::Flag(_) | Formula::Not(_) => None,
+        }
+    }
+}
+
+/// A dependency of a `depends` or `depopts` field, its kind given by its flags
+pub(crate) fn dependency(name: &str, formula: Option<&Formula>, field: &str) -> DependencySpec {
+    let flags = formula.map(Formula::flags).unwrap_or_default();
+    let flag = ["with-test", "with-doc", "with-dev-setup", "build"]
+        .into_iter()
+        .find(|flag| flags.contains(flag));
+    let (kind, group) = match (field, flag) {
+        ("depopts", _) => (DependencyKind::Optional, Some(field)),
+        (_, Some("build")) => (DependencyKind::Build, Some("build")),
+        (_, Some(flag)) => (DependencyKind::Dev, Some(flag)),
+        (_, None) => (DependencyKind::Runtime, None),
+    };
+    let version = formula.and_then(|formula| {
+        let raw = formula.render()?;
+        Some(VersionReq::new(&raw, formula.any_of()?))
+    });
+    DependencySpec::new(name, kind)
+        .with_version(version)
+        .group(group)
+}
+
+/// Constraint of the `ocaml` dependency, the version of the compiler
+pub(crate) fn compiler_version(formula: Option<&Formula>) -> Option<String> {
+    formula.and_then(Formula::render)
+}
+
+/// Parse an opam file, `<name>.opam` naming the package when it has no `name` field
+pub(crate) fn parse(
+    contents: &str,
+    file_name: Option<&str>,
+) -> Result<ProjectMetadata, SyntaxError> {
+    let tokens = tokens(contents)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        index: 0,
+    };
+    let fields = parser.fields(false)?;
+    let get = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    };
+    let string = |name: &str| {
+        get(name)
+            .and_then(|value| value.as_str())
+            .map(|s| s.to_string())
+    };
+    let strings = |name: &str| -> Option<Vec<String>> {
+        match get(name)? {
+            OpamValue::List(items) => Some(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .map(|item| item.to_string())
+                    .collect(),
+            ),
+            value => Some(vec![value.as_str()?.to_string()]),
+        }
+    };
+    let people = |name: &str| {
+        strings(name).map(|people| people.iter().map(|person| Person::parse(person)).collect())
+    };
+
+    let mut engines = HashMap::new();
+    let mut dependencies = Vec::new();
+    for field in ["depends", "depopts"] {
+        for (name, formula) in get(field).map(packages).unwrap_or_default() {
+            // The compiler is a package like any other for opam
+            if name == "ocaml" {
+                if let Some(version) = compiler_version(formula) {
+                    engines.insert("ocaml".to_string(), version);
+                }
+                continue;
+            }
+            dependencies.push(dependency(name, formula, field));
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let mut urls = HashMap::new();
+    for (field, key) in [
+        ("homepage", "homepage"),
+        ("bug-reports", "issues"),
+        ("doc", "documentation"),
+        ("dev-repo", "repository"),
+    ] {
+        if let Some(url) = string(field) {
+            let url = url.strip_prefix("git+").unwrap_or(&url);
+            urls.insert(key.to_string(), url.to_string());
+        }
+    }
+
+    // Commands are lists of arguments, variables among them
+    let mut scripts = HashMap::new();
+    for field in ["build", "install", "run-test", "remove"] {
+        let commands: Vec<String> = match get(field) {
+            Some(OpamValue::List(commands)) => commands
+                .iter()
+                .filter_map(|command| match command.unfiltered() {
+                    OpamValue::List(args) => Some(
+                        args.iter()
+                            .filter_map(|arg| arg.unfiltered().as_word())
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    ),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        if !commands.is_empty() {
+            scripts.insert(field.to_string(), commands.join(" && "));
+        }
+    }
+
+    let properties: HashMap<String, String> = fields
+        .iter()
+        .filter(|(field, _)| !FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, value)| Some((field.clone(), value.as_str()?.to_string())))
+        .collect();
+
+    Ok(ProjectMetadata {
+        name: string("name")
+            .or_else(|| {
+                file_name?
+                    .strip_suffix(".opam")
+                    .map(|name| name.to_string())
+            })
+            .unwrap_or_default(),
+        version: string("version").unwrap_or_default(),
+        description: string("synopsis").or_else(|| string("description")),
+        authors: people("authors"),
+        maintainers: people("maintainer"),
+        license: strings("license").and_then(|licenses| any_license(&licenses)),
+        keywords: strings("tags"),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: (!engines.is_empty()).then_some(engines),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        scripts: (!scripts.is_empty()).then_some(scripts),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    })
+}
+
+/// Packages of a `depends` list, with their constraints; alternatives `"a" | "b"` are listed
+/// as separate packages
+fn packages(value: &OpamValue) -> Vec<(&str, Option<&Formula>)> {
+    match value {
+        OpamValue::List(items) | OpamValue::Logic(items) => {
+            items.iter().flat_map(packages).collect()
+        }
+        OpamValue::Str(name) => vec![(name.as_str(), None)],
+        OpamValue::Filtered(value, formula) => match value.as_ref() {
+            OpamValue::Str(name) => vec![(name.as_str(), Some(formula))],
+            value => packages(value),
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Str(String),
+    Ident(String),
+    Int(String),
+    Symbol(&'static str),
+}
+
+/// A token, with its line
+type Spanned = (Token, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OpamValue {
+    Str(String),
+    Ident(String),
+    Int(String),
+    List(Vec<OpamValue>),
+    /// Values joined by logical or relational operators
+    Logic(Vec<OpamValue>),
+    /// Value followed by its `{ ... }` option
+    Filtered(Box<OpamValue>, Formula),
+}
+
+impl OpamValue {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            OpamValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn unfiltered(&self) -> &OpamValue {
+        match self {
+            OpamValue::Filtered(value, _) => value,
+            value => value,
+        }
+    }
+
+    /// Strings as they are and variables as `%{name}%`, the way they are written in strings
+    fn as_word(&self) -> Option<String> {
+        match self {
+            OpamValue::Str(value) | OpamValue::Int(value) => Some(value.clone()),
+            OpamValue::Ident(name) => Some(format!("%{{{name}}}%")),
+            _ => None,
+        }
+    }
+}
+
+fn tokens(contents: &str) -> Result<Vec<Spanned>, SyntaxError> {
+    const SYMBOLS: [&str; 11] = ["[", "]", "{", "}", "(", ")", ":", "&", "|", "!", "?"];
+    let chars: Vec<char> = contents.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    let mut line = 1;
+    let error = |line: usize, message: &str| SyntaxError::new(line, message);
+    let starts_with = |index: usize, text: &str| {
+        text.chars()
+            .enumerate()
+            .all(|(offset, c)| chars.get(index + offset) == Some(&c))
+    };
+    while let Some(&c) = chars.get(index) {
+        if c == '\n' {
+            line += 1;
+            index += 1;
+        } else if c.is_whitespace() {
+            index += 1;
+        } else if c == '#' {
+            while chars.get(index).is_some_and(|c| *c != '\n') {
+                index += 1;
+            }
+        } else if starts_with(index, "(*") {
+            let start = line;
+            index += 2;
+            while !starts_with(index, "*)") {
+                match chars.get(index) {
+                    Some('\n') => line += 1,
+                    Some(_) => {}
+                    None => return Err(error(start, "unclosed `(*` comment")),
+                }
+                index += 1;
+            }
+            index += 2;
+        } else if c == '"' {
+            let start = line;
+            let delimiter = match starts_with(index, "\"\"\"") {
+                true => "\"\"\"",
+                false => "\"",
+            };
+            index += delimiter.len();
+            let mut value = String::new();
+            loop {
+                if starts_with(index, delimiter) {
+                    index += delimiter.len();
+                    break;
+                }
+                match chars.get(index) {
+                    Some('\\') => {
+                        index += 1;
+                        match chars.get(index) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some('\n') => {
+                                line += 1;
+                                while chars.get(index + 1).is_some_and(|c| *c == ' ') {
+                                    index += 1;
+                                }
+                            }
+                            Some(c @ ('\\' | '"' | '\'')) => value.push(*c),
+                            Some(c) => {
+                                return Err(error(line, &format!("invalid escape `\\{c}`")));
+                            }
+                            None => return Err(error(start, "unterminated string")),
+                        }
+                    }
+                    Some('\n') => {
+                        line += 1;
+                        value.push('\n');
+                    }
+                    Some(c) => value.push(*c),
+                    None => return Err(error(start, "unterminated string")),
+                }
+                index += 1;
+            }
+            tokens.push((Token::Str(value), start));
+        } else if c.is_alphanumeric() || c == '_' || c == '-' {
+            // `pkg:var` is a single identifier, `field:` the name of a field
+            let start = index;
+            while chars
+                .get(index)
+                .is_some_and(|c| c.is_alphanumeric() || "_-+".contains(*c))
+                || (chars.get(index) == Some(&':')
+                    && chars
+                        .get(index + 1)
+                        .is_some_and(|c| c.is_alphanumeric() || *c == '_'))
+            {
+                index += 1;
+            }
+            let word: String = chars[start..index].iter().collect();
+            let token = match word.chars().all(|c| c.is_ascii_digit() || c == '-') {
+                true => Token::Int(word),
+                false => Token::Ident(word),
+            };
+            tokens.push((token, line));
+        } else if let Some(symbol) = RELOPS
+            .iter()
+            .chain(SYMBOLS.iter())
+            .filter(|symbol| starts_with(index, symbol))
+            .max_by_key(|symbol| symbol.len())
+        {
+            index += symbol.len();
+            tokens.push((Token::Symbol(symbol), line));
+        } else {
+            return Err(error(line, &format!("unexpected `{c}`")));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Spanned],
+    index: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    /// Line of the next token, or of the last one at the end of the file
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .or(self.tokens.last())
+            .map(|(_, line)| *line)
+            .unwrap_or(1)
+    }
+
+    fn error(&self, message: &str) -> SyntaxError {
+        SyntaxError::new(self.line(), message)
+    }
+
+    fn eat(&mut self, symbol: &'static str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.index += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Fields of the file or of a section, sections skipped
+    fn fields(&mut self, section: bool) -> Result<Vec<(String, OpamValue)>, SyntaxError> {
+        let mut fields: Vec<(String, OpamValue)> = Vec::new();
+        while let Some(token) = self.peek() {
+            if section && *token == Token::Symbol("}") {
+                break;
+            }
+            let line = self.line();
+            let Token::Ident(name) = token else {
+                return Err(self.error(&format!(
+                    "expected `field: value` or a section, found {}",
+                    describe(token)
+                )));
+            };
+            let name = name.clone();
+            self.index += 1;
+            if self.eat(":") {
+                if fields.iter().any(|(field, _)| *field == name) {
+                    return Err(SyntaxError::new(line, format!("duplicate field `{name}`")));
+                }
+                let value = self.value()?;
+                fields.push((name, value));
+                continue;
+            }
+            // `name "label"? { fields }`
+            if matches!(self.peek(), Some(Token::Str(_))) {
+                self.index += 1;
+            }
+            if !self.eat("{") {
+                return Err(SyntaxError::new(
+                    line,
+                    format!("expected `field: value` or a section, found `{name}`"),
+                ));
+            }
+            self.fields(true)?;
+            if !self.eat("}") {
+                return Err(SyntaxError::new(line, format!("unclosed section `{name}`")));
+            }
+        }
+        Ok(fields)
+    }
+
+    fn value(&mut self) -> Result<OpamValue, SyntaxError> {
+        let mut values = vec![self.term()?];
+        while let Some(Token::Symbol(symbol)) = self.peek() {
+            if *symbol != "&" && *symbol != "|" && !RELOPS.contains(symbol) {
+                break;
+            }
+            self.index += 1;
+            values.push(self.term()?);
+        }
+        Ok(match values.len() {
+            1 => values.remove(0),
+            _ => OpamValue::Logic(values),
+        })
+    }
+
+    fn term(&mut self) -> Result<OpamValue, SyntaxError> {
+        let line = self.line();
+        let value = match self.peek().cloned() {
+            Some(Token::Str(value)) => {
+                self.index += 1;
+                OpamValue::Str(value)
+            }
+            Some(Token::Ident(name)) => {
+                self.index += 1;
+                OpamValue::Ident(name)
+            }
+            Some(Token::Int(value)) => {
+                self.index += 1;
+                OpamValue::Int(value)
+            }
+            Some(Token::Symbol("[")) => {
+                self.index += 1;
+                let mut items = Vec::new();
+                while !self.eat("]") {
+                    if self.peek().is_none() {
+                        return Err(SyntaxError::new(line, "unclosed `[`"));
+                    }
+                    items.push(self.value()?);
+                }
+                OpamValue::List(items)
+            }
+            Some(Token::Symbol("(")) => {
+                self.index += 1;
+                let value = self.value()?;
+                if !self.eat(")") {
+                    return Err(SyntaxError::new(line, "unclosed `(`"));
+                }
+                value
+            }
+            Some(Token::Symbol(symbol)) if symbol == "!" || symbol == "?" => {
+                self.index += 1;
+                self.term()?
+            }
+            Some(Token::Symbol(symbol)) if RELOPS.contains(&symbol) => {
+                self.index += 1;
+                OpamValue::Logic(vec![self.term()?])
+            }
+            Some(token) => {
+                return Err(self.error(&format!("expected a value, found {}", describe(&token))));
+            }
+            None => return Err(self.error("expected a value, found the end of the file")),
+        };
+        if self.eat("{") {
+            let formula = match self.peek() {
+                Some(Token::Symbol("}")) => Formula::And(Vec::new()),
+                _ => self.or()?,
+            };
+            if !self.eat("}") {
+                return Err(SyntaxError::new(line, "unclosed `{`"));
+            }
+            return Ok(OpamValue::Filtered(Box::new(value), formula));
+        }
+        Ok(value)
+    }
+
+    fn or(&mut self) -> Result<Formula, SyntaxError> {
+        let mut formulas = vec![self.and()?];
+        while self.eat("|") {
+            formulas.push(self.and()?);
+        }
+        Ok(match formulas.len() {
+            1 => formulas.remove(0),
+            _ => Formula::Or(formulas),
+        })
+    }
+
+    fn and(&mut self) -> Result<Formula, SyntaxError> {
+        let mut formulas = vec![self.atom()?];
+        while self.eat("&") {
+            formulas.push(self.atom()?);
+        }
+        Ok(match formulas.len() {
+            1 => formulas.remove(0),
+            _ => Formula::And(formulas),
+        })
+    }
+
+    fn atom(&mut self) -> Result<Formula, SyntaxError> {
+        let line = self.line();
+        match self.peek().cloned() {
+            Some(Token::Symbol(symbol)) if symbol == "!" || symbol == "?" => {
+                self.index += 1;
+                Ok(Formula::Not(Box::new(self.atom()?)))
+            }
+            Some(Token::Symbol("(")) => {
+                self.index += 1;
+                let formula = self.or()?;
+                if !self.eat(")") {
+                    return Err(SyntaxError::new(line, "unclosed `(`"));
+                }
+                Ok(formula)
+            }
+            Some(Token::Symbol(op)) if RELOPS.contains(&op) => {
+                self.index += 1;
+                let version = match self.peek().cloned() {
+                    Some(Token::Str(version)) => version,
+                    // `= version` is the version of the package itself
+                    Some(Token::Ident(variable)) => format!("%{{{variable}}}%"),
+                    _ => return Err(self.error(&format!("expected a version after `{op}`"))),
+                };
+                self.index += 1;
+                Ok(Formula::Constraint(op.to_string(), version))
+            }
+            // `os = "linux"` filters on another variable
+            Some(Token::Ident(flag)) | Some(Token::Str(flag)) => {
+                self.index += 1;
+                match self.peek() {
+                    Some(Token::Symbol(op)) if RELOPS.contains(op) => {
+                        // Skip the operator and the value compared with
+                        self.index += 2;
+                        Ok(Formula::Not(Box::new(Formula::Flag(flag))))
+                    }
+                    _ => Ok(Formula::Flag(flag)),
+                }
+            }
+            Some(token) => Err(self.error(&format!(
+                "expected a constraint, found {}",
+                describe(&token)
+            ))),
+            None => Err(self.error("expected a constraint, found the end of the file")),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Str(value) => format!("\"{value}\""),
+        Token::Ident(name) | Token::Int(name) => format!("`{name}`"),
+        Token::Symbol(symbol) => format!("`{symbol}`"),
+    }
+}
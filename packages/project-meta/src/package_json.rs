@@ -0,0 +1,163 @@
+//! `package.json` reader, collecting every npm dependency group along with the overrides npm,
+//! Yarn and pnpm apply to the dependency graph.
+use crate::dependency::{DependencyKind, DependencySpec};
+use crate::npm::{self, SpecifierError};
+use crate::{MetadataError, Person, ProjectMetadata};
+use serde_json::{Map, Value};
+
+/// Dependency tables and the kind of their entries; runtime dependencies are not tagged with a
+/// group since their kind already tells them apart
+const GROUPS: [(&str, DependencyKind); 4] = [
+    ("dependencies", DependencyKind::Runtime),
+    ("devDependencies", DependencyKind::Dev),
+    ("peerDependencies", DependencyKind::Peer),
+    ("optionalDependencies", DependencyKind::Optional),
+];
+
+pub(crate) fn from_json(json: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    for (group, kind) in GROUPS {
+        if let Some(deps) = json[group].as_object() {
+            let group = (kind != DependencyKind::Runtime).then_some(group);
+            dependencies.extend(npm::dependencies(deps, kind, group)?);
+        }
+    }
+    optional_peers(json, &mut dependencies);
+    bundled(json, &mut dependencies);
+    for (group, overrides) in [
+        ("overrides", &json["overrides"]),
+        ("resolutions", &json["resolutions"]),
+        ("pnpm.overrides", &json["pnpm"]["overrides"]),
+    ] {
+        if let Some(overrides) = overrides.as_object() {
+            collect_overrides(json, group, None, overrides, &mut dependencies)?;
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    Ok(ProjectMetadata {
+        name: json["name"].as_str().unwrap_or("").to_string(),
+        version: json["version"].as_str().unwrap_or("").to_string(),
+        description: json["description"].as_str().map(|s| s.to_string()),
+        authors: Person::from_json(&json["author"]).map(|author| vec![author]),
+        contributors: Person::from_json_list(&json["contributors"]),
+        maintainers: Person::from_json_list(&json["maintainers"]),
+        license: json["license"].as_str().map(|s| s.to_string()),
+        keywords: json["keywords"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        scripts: json["scripts"].as_object().map(|scripts| {
+            scripts
+                .clone()
+                .into_iter()
+                .map(|(k, v)| (k, v.as_str().unwrap_or("").to_string()))
+                .collect()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Peers marked `optional` in `peerDependenciesMeta` become optional dependencies; a peer only
+/// listed there accepts any version
+fn optional_peers(json: &Value, dependencies: &mut Vec<DependencySpec>) {
+    let Some(meta) = json["peerDependenciesMeta"].as_object() else {
+        return;
+    };
+    for (name, meta) in meta {
+        if meta["optional"].as_bool() != Some(true) {
+            continue;
+        }
+        let peer = dependencies
+            .iter_mut()
+            .find(|dependency| dependency.kind == DependencyKind::Peer && dependency.name == *name);
+        match peer {
+            Some(peer) => peer.kind = DependencyKind::Optional,
+            None => dependencies.push(
+                DependencySpec::new(name, DependencyKind::Optional).group(Some("peerDependencies")),
+            ),
+        }
+    }
+}
+
+/// `bundleDependencies` (or `bundledDependencies`) names declared dependencies packed into the
+/// tarball, `true` standing for all of `dependencies`
+fn bundled(json: &Value, dependencies: &mut Vec<DependencySpec>) {
+    let bundle = match &json["bundleDependencies"] {
+        Value::Null => &json["bundledDependencies"],
+        bundle => bundle,
+    };
+    let runtime = dependencies
+        .iter()
+        .filter(|dependency| dependency.kind == DependencyKind::Runtime);
+    let bundled: Vec<DependencySpec> = match bundle {
+        Value::Bool(true) => runtime.cloned().collect(),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(|name| name.as_str())
+            .map(|name| {
+                runtime
+                    .clone()
+                    .find(|dependency| dependency.name == name)
+                    .cloned()
+                    .unwrap_or_else(|| DependencySpec::new(name, DependencyKind::Runtime))
+            })
+            .collect(),
+        _ => return,
+    };
+    dependencies.extend(
+        bundled
+            .into_iter()
+            .map(|dependency| dependency.group(Some("bundleDependencies"))),
+    );
+}
+
+/// Read an overrides table; npm nests overrides of transitive dependencies under their parent,
+/// with `.` holding the parent's own override, and they are named `parent>child` like pnpm does
+fn collect_overrides(
+    json: &Value,
+    group: &str,
+    parent: Option<&str>,
+    overrides: &Map<String, Value>,
+    dependencies: &mut Vec<DependencySpec>,
+) -> Result<(), MetadataError> {
+    for (key, value) in overrides {
+        let name = match (parent, key.as_str()) {
+            (Some(parent), ".") => parent.to_string(),
+            (Some(parent), key) => format!("{parent}>{key}"),
+            (None, key) => key.to_string(),
+        };
+        let specifier = match value {
+            Value::Object(nested) => {
+                collect_overrides(json, group, Some(&name), nested, dependencies)?;
+                continue;
+            }
+            // pnpm removes dependencies overridden with `-`
+            Value::String(specifier) if specifier == "-" => continue,
+            Value::String(specifier) => specifier.as_str(),
+            value => {
+                return Err(MetadataError::DependencySpecifierError(
+                    name,
+                    SpecifierError::NotAString(value.to_string()),
+                ))
+            }
+        };
+        // `$name` takes the specifier of the direct dependency, kept as is when there is none
+        let specifier = specifier
+            .strip_prefix('$')
+            .and_then(|reference| direct_specifier(json, reference))
+            .unwrap_or(specifier);
+        let dependency = npm::dependency(&name, DependencyKind::Override, specifier);
+        dependencies.push(dependency.group(Some(group)));
+    }
+    Ok(())
+}
+
+/// Specifier of a direct dependency, which `$name` overrides refer to
+fn direct_specifier<'a>(json: &'a Value, name: &str) -> Option<&'a str> {
+    GROUPS
+        .iter()
+        .find_map(|(group, _)| json[group][name].as_str())
+}
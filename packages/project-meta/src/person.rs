@@ -0,0 +1,101 @@
+//! People credited by a manifest, whatever form the format writes them in.
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone, Default)]
+pub struct Person {
+    pub(crate) name: Option<String>,
+    pub(crate) email: Option<String>,
+    pub(crate) url: Option<String>,
+}
+
+impl Person {
+    pub fn new(name: Option<&str>, email: Option<&str>, url: Option<&str>) -> Self {
+        let owned = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| value.to_string())
+        };
+        Person {
+            name: owned(name),
+            email: owned(email),
+            url: owned(url),
+        }
+    }
+
+    /// Parse the `Name <email> (url)` shorthand of npm, where the email and url are optional;
+    /// Cargo and Poetry authors are the `Name <email>` subset of it
+    pub fn parse(person: &str) -> Self {
+        let delimited = |open: char, close: char| {
+            let start = person.find(open)? + 1;
+            let end = start + person[start..].find(close)?;
+            Some(&person[start..end])
+        };
+        let name_end = person.find(['<', '(']).unwrap_or(person.len());
+        Person::new(
+            Some(&person[..name_end]),
+            delimited('<', '>'),
+            delimited('(', ')'),
+        )
+    }
+
+    /// Read the npm `{ name, email, url }` object or the shorthand string
+    pub(crate) fn from_json(person: &serde_json::Value) -> Option<Self> {
+        match person {
+            serde_json::Value::String(person) => Some(Person::parse(person)),
+            serde_json::Value::Object(_) => Some(Person::new(
+                person["name"].as_str(),
+                person["email"].as_str(),
+                person["url"].as_str(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Read an npm list of people, such as `contributors`
+    pub(crate) fn from_json_list(people: &serde_json::Value) -> Option<Vec<Self>> {
+        people
+            .as_array()
+            .map(|arr| arr.iter().filter_map(Person::from_json).collect())
+    }
+
+    /// Read PEP 621 `{ name, email }` tables, or Poetry `Name <email>` strings
+    pub(crate) fn from_toml_list(people: &toml::Value) -> Option<Vec<Self>> {
+        people.as_array().map(|arr| {
+            arr.iter()
+                .map(|person| match person.as_str() {
+                    Some(person) => Person::parse(person),
+                    None => Person::new(
+                        person.get("name").and_then(|v| v.as_str()),
+                        person.get("email").and_then(|v| v.as_str()),
+                        None,
+                    ),
+                })
+                .collect()
+        })
+    }
+
+    /// Pair comma separated lists of names and emails, such as setuptools' `author` and
+    /// `author_email`, when they have the same length
+    pub(crate) fn from_lists(names: Option<String>, emails: Option<String>) -> Option<Vec<Self>> {
+        let split = |list: &Option<String>| -> Vec<String> {
+            list.iter()
+                .flat_map(|list| list.split(','))
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+        let (name_list, email_list) = (split(&names), split(&emails));
+        if name_list.len() > 1 && name_list.len() == email_list.len() {
+            return Some(
+                name_list
+                    .iter()
+                    .zip(&email_list)
+                    .map(|(name, email)| Person::new(Some(name), Some(email), None))
+                    .collect(),
+            );
+        }
+        (names.is_some() || emails.is_some())
+            .then(|| vec![Person::new(names.as_deref(), emails.as_deref(), None)])
+    }
+}
@@ -0,0 +1,266 @@
+//! `pixi.toml` workspaces: conda `[dependencies]` written as match spec versions or tables,
+//! `[pypi-dependencies]` installed from PyPI, their `[target.<platform>]` and `[feature.<name>]`
+//! variants, and the `[tasks]` pixi runs.
+use crate::conda::{conda_version, pypi};
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec};
+use crate::pyproject::parse_requirement;
+use crate::{Person, ProjectMetadata, Task};
+use std::collections::HashMap;
+use toml::Value;
+
+/// Dependency tables, with the kind and group of their packages
+const DEPENDENCY_TABLES: [(&str, DependencyKind, Option<&str>); 4] = [
+    ("dependencies", DependencyKind::Runtime, None),
+    (
+        "host-dependencies",
+        DependencyKind::Build,
+        Some("host-dependencies"),
+    ),
+    (
+        "build-dependencies",
+        DependencyKind::Build,
+        Some("build-dependencies"),
+    ),
+    ("pypi-dependencies", DependencyKind::Runtime, None),
+];
+
+pub(crate) fn from_toml(toml: &Value) -> ProjectMetadata {
+    // `[workspace]` replaces the older `[project]`
+    let project = toml.get("workspace").or(toml.get("project"));
+    let get = |key: &str| project.and_then(|p| p.get(key)).and_then(|v| v.as_str());
+    let strings = |key: &str| {
+        project
+            .and_then(|p| p.get(key))
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    // Channels may be `{ channel = "...", priority = 1 }` tables
+                    .filter_map(|item| item.as_str().or_else(|| item.get("channel")?.as_str()))
+                    .map(|item| item.to_string())
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    let mut manifest = Manifest::default();
+    manifest.read(toml, None, None);
+    for (feature, table) in toml
+        .get("feature")
+        .and_then(|v| v.as_table())
+        .into_iter()
+        .flatten()
+    {
+        manifest.read(table, Some(feature), None);
+    }
+    DependencySpec::sort(&mut manifest.dependencies);
+
+    if let Some(requirements) = toml.get("system-requirements").and_then(|v| v.as_table()) {
+        for (name, version) in requirements {
+            // `libc = { family = "glibc", version = "2.28" }`
+            let version = version
+                .as_str()
+                .or(version.get("version").and_then(|v| v.as_str()));
+            if let Some(version) = version {
+                manifest.engines.insert(name.clone(), version.to_string());
+            }
+        }
+    }
+
+    let mut urls = HashMap::new();
+    for key in ["homepage", "repository", "documentation"] {
+        if let Some(url) = get(key) {
+            urls.insert(key.to_string(), url.to_string());
+        }
+    }
+    let mut properties = HashMap::new();
+    for key in ["channels", "platforms"] {
+        if let Some(items) = strings(key).filter(|items| !items.is_empty()) {
+            properties.insert(key.to_string(), items.join(", "));
+        }
+    }
+    // Tasks only running their dependencies have no command to compare with other scripts
+    let scripts: HashMap<String, String> = manifest
+        .tasks
+        .iter()
+        .filter_map(|(name, task)| Some((name.clone(), task.command.clone()?)))
+        .collect();
+
+    ProjectMetadata {
+        name: get("name").unwrap_or("").to_string(),
+        version: get("version").unwrap_or("").to_string(),
+        description: get("description").map(|s| s.to_string()),
+        authors: strings("authors")
+            .map(|authors| authors.iter().map(|a| Person::parse(a)).collect()),
+        license: get("license").map(|s| s.to_string()),
+        license_file: get("license-file").map(|s| s.to_string()),
+        readme: get("readme").map(|s| s.to_string()),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: (!manifest.engines.is_empty()).then_some(manifest.engines),
+        dependencies: (!manifest.dependencies.is_empty()).then_some(manifest.dependencies),
+        scripts: (!scripts.is_empty()).then_some(scripts),
+        tasks: (!manifest.tasks.is_empty()).then_some(manifest.tasks),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    }
+}
+
+#[derive(Default)]
+struct Manifest {
+    dependencies: Vec<DependencySpec>,
+    engines: HashMap<String, String>,
+    tasks: HashMap<String, Task>,
+}
+
+impl Manifest {
+    /// Read the dependency tables and tasks of the manifest or of a feature, then those of
+    /// their `[target.<platform>]` tables
+    fn read(&mut self, table: &Value, feature: Option<&str>, platform: Option<&str>) {
+        for (key, kind, group) in DEPENDENCY_TABLES {
+            // Features are optional sets of packages environments are made of
+            let (kind, group) = match feature {
+                Some(feature) => (DependencyKind::Optional, Some(feature)),
+                None => (kind, group),
+            };
+            for (name, spec) in table
+                .get(key)
+                .and_then(|v| v.as_table())
+                .into_iter()
+                .flatten()
+            {
+                let dependency = match key {
+                    "pypi-dependencies" => pypi(pypi_dependency(name, spec)),
+                    _ => conda_dependency(name, spec),
+                };
+                // The interpreter is a package like any other for conda
+                if name == "python"
+                    && key == "dependencies"
+                    && feature.is_none()
+                    && platform.is_none()
+                {
+                    if let Some(version) = spec
+                        .as_str()
+                        .or(spec.get("version").and_then(|v| v.as_str()))
+                    {
+                        self.engines
+                            .insert("python".to_string(), version.to_string());
+                    }
+                    continue;
+                }
+                self.dependencies
+                    .push(dependency.kind(kind).group(group).target(platform));
+            }
+        }
+        for (name, task) in table
+            .get("tasks")
+            .and_then(|v| v.as_table())
+            .into_iter()
+            .flatten()
+        {
+            self.tasks.insert(name.clone(), self::task(task));
+        }
+        if platform.is_none() {
+            for (platform, table) in table
+                .get("target")
+                .and_then(|v| v.as_table())
+                .into_iter()
+                .flatten()
+            {
+                self.read(table, feature, Some(platform));
+            }
+        }
+    }
+}
+
+/// A version spec, or a table with a `version`, `build` and `channel`, or the `path`, `git` or
+/// `url` of a package built from source
+fn conda_dependency(name: &str, spec: &Value) -> DependencySpec {
+    let get = |key: &str| spec.get(key).and_then(|v| v.as_str());
+    let version = spec.as_str().or(get("version"));
+    let raw = match (version, get("build")) {
+        (Some(version), Some(build)) => Some(format!("{version} {build}")),
+        (None, Some(build)) => Some(format!("* {build}")),
+        (version, None) => version.map(|version| version.to_string()),
+    };
+    let version = raw
+        .as_deref()
+        .map(|raw| conda_version(version.unwrap_or("*")).with_raw(raw))
+        .filter(|version| !(version.accepts_any() && get("build").is_none()));
+    DependencySpec::new(name, DependencyKind::Runtime)
+        .with_version(version)
+        .source(source(spec).unwrap_or(DependencySource::Registry {
+            registry: get("channel").map(|channel| channel.to_string()),
+            dist_tag: None,
+        }))
+}
+
+/// A PEP 440 version spec, or a table with a `version` and `extras`, or the `path`, `git` or
+/// `url` of the package
+fn pypi_dependency(name: &str, spec: &Value) -> DependencySpec {
+    let version = spec
+        .as_str()
+        .or(spec.get("version").and_then(|v| v.as_str()));
+    let extras = spec.get("extras").and_then(|v| v.as_array()).map(|extras| {
+        extras
+            .iter()
+            .filter_map(|extra| extra.as_str())
+            .map(|extra| extra.to_string())
+            .collect()
+    });
+    let dependency = match version.filter(|version| *version != "*") {
+        Some(version) => parse_requirement(&format!("{name}{version}")),
+        None => DependencySpec::new(name, DependencyKind::Runtime),
+    };
+    let dependency = dependency.features(extras);
+    match source(spec) {
+        Some(source) => dependency.source(source),
+        None => dependency,
+    }
+}
+
+fn source(spec: &Value) -> Option<DependencySource> {
+    let get = |key: &str| {
+        spec.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+    if let Some(url) = get("git") {
+        return Some(DependencySource::Git {
+            url,
+            branch: get("branch"),
+            tag: get("tag"),
+            rev: get("rev"),
+        });
+    }
+    if let Some(path) = get("path") {
+        return Some(DependencySource::Path { path });
+    }
+    get("url").map(|url| DependencySource::Url { url })
+}
+
+/// A command, or a table with a `cmd`, the tasks it `depends-on` and a `description`
+fn task(task: &Value) -> Task {
+    let list = |value: &Value| match value {
+        Value::String(item) => vec![item.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.as_str().or_else(|| item.get("task")?.as_str()))
+            .map(|item| item.to_string())
+            .collect(),
+        _ => Vec::new(),
+    };
+    match task {
+        Value::String(command) => Task {
+            command: Some(command.clone()),
+            ..Default::default()
+        },
+        task => Task {
+            // Commands may be given as their arguments
+            command: task.get("cmd").map(|cmd| list(cmd).join(" ")),
+            dependencies: task.get("depends-on").or(task.get("depends_on")).map(list),
+            description: task
+                .get("description")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        },
+    }
+}
@@ -0,0 +1,470 @@
+//! Maven `pom.xml` reader. Like Maven building the effective model, the POM is first merged with
+//! the `<parent>` chain found through `relativePath`, then `${property}` references are
+//! interpolated, so a parent may refer to properties its children set.
+use crate::dependency::{
+    DependencyKind, DependencySource, DependencySpec, VersionReq, VersionSyntax,
+};
+use crate::npm::SpecifierError;
+use crate::util::{any_license, canonical, located};
+use crate::xml::{self, child, text};
+use crate::{FieldValue, MetadataError, Person, ProjectMetadata};
+use roxmltree::Node;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Nesting of property references past which they are left as written, which also ends cycles
+const MAX_DEPTH: usize = 16;
+
+/// Read the POM at `pom_path`, inheriting from the parent POMs found from that path
+pub(crate) fn from_xml(
+    contents: &str,
+    pom_path: Option<&Path>,
+) -> Result<ProjectMetadata, MetadataError> {
+    let mut pom = Pom::parse(contents, pom_path)?;
+    let coordinates = pom.parent.clone();
+    let mut sources = HashMap::new();
+    for field in pom.fields() {
+        sources.insert(field.to_string(), FieldValue::from_table("project"));
+    }
+
+    let mut visited: HashSet<PathBuf> = pom_path.map(canonical).into_iter().collect();
+    let mut parent = pom.parent.clone();
+    let mut dir = pom_path.map(|path| path.parent().unwrap_or(Path::new("")).to_path_buf());
+    while let Some(path) = parent
+        .as_ref()
+        .zip(dir.as_deref())
+        .and_then(|(parent, dir)| parent.path(dir))
+    {
+        if !visited.insert(canonical(&path)) {
+            return Err(MetadataError::ParentPomError(located(
+                pom_path,
+                format!("parent cycle through {}", path.display()),
+            )));
+        }
+        let contents = fs::read_to_string(&path)
+            .map_err(|_| MetadataError::ReadError(path.display().to_string()))?;
+        let parent_pom = Pom::parse(&contents, Some(&path))?;
+        // Maven falls back to the repository when the file is another project
+        if parent_pom.artifact_id != parent.and_then(|parent| parent.artifact_id) {
+            break;
+        }
+        for field in parent_pom.fields() {
+            sources
+                .entry(field.to_string())
+                .or_insert_with(|| FieldValue::from_table("parent"));
+        }
+        pom.inherit(&parent_pom);
+        dir = Some(path.parent().unwrap_or(Path::new("")).to_path_buf());
+        parent = parent_pom.parent;
+    }
+    // Coordinates of a parent that could not be read still fill in the group and version
+    if let Some(coordinates) = coordinates {
+        for (field, value, inherited) in [
+            ("name", &mut pom.group_id, coordinates.group_id),
+            ("version", &mut pom.version, coordinates.version),
+        ] {
+            if value.is_none() && inherited.is_some() {
+                *value = inherited;
+                sources.insert(field.to_string(), FieldValue::from_table("parent"));
+            }
+        }
+    }
+
+    let properties = pom.interpolate(pom_path.map(|path| path.parent().unwrap_or(Path::new(""))));
+    let dependencies = pom.dependencies()?;
+    let mut urls = HashMap::new();
+    for (key, url) in [("homepage", &pom.url), ("repository", &pom.scm_url)] {
+        if let Some(url) = url {
+            urls.insert(key.to_string(), url.clone());
+        }
+    }
+
+    Ok(ProjectMetadata {
+        name: match (&pom.group_id, &pom.artifact_id) {
+            (Some(group_id), Some(artifact_id)) => format!("{group_id}:{artifact_id}"),
+            (None, Some(artifact_id)) => artifact_id.clone(),
+            (_, None) => String::new(),
+        },
+        version: pom.version.clone().unwrap_or_default(),
+        description: pom.description.clone(),
+        authors: (!pom.developers.is_empty()).then(|| pom.developers.clone()),
+        contributors: (!pom.contributors.is_empty()).then(|| pom.contributors.clone()),
+        license: any_license(&pom.licenses),
+        urls: (!urls.is_empty()).then_some(urls),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        workspace: (!pom.modules.is_empty()).then(|| pom.modules.clone()),
+        properties: (!properties.is_empty()).then_some(properties),
+        sources: (!sources.is_empty()).then_some(sources),
+        ..Default::default()
+    })
+}
+
+/// `<parent>` element: the coordinates of the parent and where to look for its POM
+#[derive(Debug, Clone, Default)]
+struct Parent {
+    group_id: Option<String>,
+    artifact_id: Option<String>,
+    version: Option<String>,
+    relative_path: Option<String>,
+}
+
+impl Parent {
+    /// Path of the parent POM, `None` when it is only available from a repository
+    fn path(&self, dir: &Path) -> Option<PathBuf> {
+        let relative_path = self.relative_path.as_deref().unwrap_or("../pom.xml");
+        // An empty `<relativePath/>` skips the filesystem lookup
+        if relative_path.is_empty() {
+            return None;
+        }
+        let path = dir.join(relative_path);
+        let path = match path.is_dir() {
+            true => path.join("pom.xml"),
+            false => path,
+        };
+        path.is_file().then_some(path)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Dependency {
+    group_id: String,
+    artifact_id: String,
+    version: Option<String>,
+    scope: Option<String>,
+    optional: Option<String>,
+    system_path: Option<String>,
+}
+
+/// Fields of a single POM, before inheritance and interpolation
+#[derive(Debug, Clone, Default)]
+struct Pom {
+    parent: Option<Parent>,
+    group_id: Option<String>,
+    artifact_id: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    url: Option<String>,
+    scm_url: Option<String>,
+    licenses: Vec<String>,
+    developers: Vec<Person>,
+    contributors: Vec<Person>,
+    properties: HashMap<String, String>,
+    dependencies: Vec<Dependency>,
+    /// `<dependencyManagement>` entries, providing the versions and scopes of dependencies
+    managed: Vec<Dependency>,
+    modules: Vec<String>,
+}
+
+impl Pom {
+    fn parse(contents: &str, path: Option<&Path>) -> Result<Self, MetadataError> {
+        let document = xml::parse(contents)
+            .map_err(|err| MetadataError::ParseError("XML".to_string(), located(path, err)))?;
+        let project = document.root_element();
+        if !project.has_tag_name("project") {
+            return Err(MetadataError::ParseError(
+                "XML".to_string(),
+                located(path, "expected a <project> root element"),
+            ));
+        }
+        let person = |node: Node| {
+            Person::new(
+                text(node, "name").as_deref(),
+                text(node, "email").as_deref(),
+                text(node, "url").as_deref(),
+            )
+        };
+
+        Ok(Pom {
+            parent: child(project, "parent").map(|parent| Parent {
+                group_id: text(parent, "groupId"),
+                artifact_id: text(parent, "artifactId"),
+                version: text(parent, "version"),
+                relative_path: child(parent, "relativePath")
+                    .map(|path| path.text().unwrap_or("").trim().to_string()),
+            }),
+            group_id: text(project, "groupId"),
+            artifact_id: text(project, "artifactId"),
+            version: text(project, "version"),
+            description: text(project, "description"),
+            url: text(project, "url"),
+            scm_url: child(project, "scm").and_then(|scm| text(scm, "url")),
+            licenses: items(project, "licenses", "license")
+                .filter_map(|license| text(license, "name"))
+                .collect(),
+            developers: items(project, "developers", "developer")
+                .map(person)
+                .collect(),
+            contributors: items(project, "contributors", "contributor")
+                .map(person)
+                .collect(),
+            properties: child(project, "properties")
+                .into_iter()
+                .flat_map(|properties| properties.children())
+                .filter(|property| property.is_element())
+                .map(|property| {
+                    let value = property.text().unwrap_or("").trim().to_string();
+                    (property.tag_name().name().to_string(), value)
+                })
+                .collect(),
+            dependencies: items(project, "dependencies", "dependency")
+                .map(dependency)
+                .collect(),
+            managed: child(project, "dependencyManagement")
+                .into_iter()
+                .flat_map(|management| items(management, "dependencies", "dependency"))
+                .map(dependency)
+                .collect(),
+            modules: items(project, "modules", "module")
+                .filter_map(|module| module.text())
+                .map(|module| module.trim().to_string())
+                .collect(),
+        })
+    }
+
+    /// Fields set by this POM itself, as named in [`ProjectMetadata`]
+    fn fields(&self) -> Vec<&'static str> {
+        [
+            ("name", self.group_id.is_some()),
+            ("version", self.version.is_some()),
+            ("description", self.description.is_some()),
+            ("license", !self.licenses.is_empty()),
+            ("authors", !self.developers.is_empty()),
+            ("contributors", !self.contributors.is_empty()),
+            ("urls", self.url.is_some() || self.scm_url.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, set)| set.then_some(field))
+        .collect()
+    }
+
+    /// Fill in what this POM leaves unset from `parent`; dependencies and properties are merged,
+    /// this POM's winning. The artifact and its modules are never inherited.
+    fn inherit(&mut self, parent: &Pom) {
+        let fields = [
+            (&mut self.group_id, &parent.group_id),
+            (&mut self.version, &parent.version),
+            (&mut self.description, &parent.description),
+            (&mut self.scm_url, &parent.scm_url),
+        ];
+        for (value, inherited) in fields {
+            if value.is_none() {
+                value.clone_from(inherited);
+            }
+        }
+        for (values, inherited) in [
+            (&mut self.developers, &parent.developers),
+            (&mut self.contributors, &parent.contributors),
+        ] {
+            if values.is_empty() {
+                values.clone_from(inherited);
+            }
+        }
+        if self.licenses.is_empty() {
+            self.licenses.clone_from(&parent.licenses);
+        }
+        for (name, value) in &parent.properties {
+            self.properties
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+        for (dependencies, inherited) in [
+            (&mut self.dependencies, &parent.dependencies),
+            (&mut self.managed, &parent.managed),
+        ] {
+            for dependency in inherited {
+                let declared = dependencies.iter().any(|declared| {
+                    declared.group_id == dependency.group_id
+                        && declared.artifact_id == dependency.artifact_id
+                });
+                if !declared {
+                    dependencies.push(dependency.clone());
+                }
+            }
+        }
+    }
+
+    /// Resolve `${property}` references in place, returning the interpolated properties;
+    /// `basedir` is the directory of the POM being read, when it is read from a file
+    fn interpolate(&mut self, basedir: Option<&Path>) -> HashMap<String, String> {
+        let mut context = self.properties.clone();
+        if let Some(basedir) = basedir {
+            let basedir = basedir.display().to_string();
+            context.insert("project.basedir".to_string(), basedir.clone());
+            context.insert("basedir".to_string(), basedir);
+        }
+        for (name, value) in [
+            ("groupId", &self.group_id),
+            ("artifactId", &self.artifact_id),
+            ("version", &self.version),
+            ("description", &self.description),
+            ("url", &self.url),
+            (
+                "parent.groupId",
+                &self
+                    .parent
+                    .as_ref()
+                    .and_then(|parent| parent.group_id.clone()),
+            ),
+            (
+                "parent.version",
+                &self
+                    .parent
+                    .as_ref()
+                    .and_then(|parent| parent.version.clone()),
+            ),
+        ] {
+            if let Some(value) = value {
+                // `pom.` is the deprecated spelling of `project.`
+                context.insert(format!("project.{name}"), value.clone());
+                context.insert(format!("pom.{name}"), value.clone());
+            }
+        }
+
+        let resolve = |value: &mut String| *value = expand(value, &context, 0);
+        for value in [
+            &mut self.group_id,
+            &mut self.artifact_id,
+            &mut self.version,
+            &mut self.description,
+            &mut self.url,
+            &mut self.scm_url,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve(value);
+        }
+        self.licenses.iter_mut().for_each(resolve);
+        self.modules.iter_mut().for_each(resolve);
+        for person in self.developers.iter_mut().chain(&mut self.contributors) {
+            for value in [&mut person.name, &mut person.email, &mut person.url]
+                .into_iter()
+                .flatten()
+            {
+                resolve(value);
+            }
+        }
+        for dependency in self.dependencies.iter_mut().chain(&mut self.managed) {
+            resolve(&mut dependency.group_id);
+            resolve(&mut dependency.artifact_id);
+            for value in [
+                &mut dependency.version,
+                &mut dependency.scope,
+                &mut dependency.optional,
+                &mut dependency.system_path,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                resolve(value);
+            }
+        }
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.clone(), expand(value, &context, 0)))
+            .collect()
+    }
+
+    /// Dependencies named `groupId:artifactId`, taking the version and scope
+    /// `<dependencyManagement>` gives when they leave them out
+    fn dependencies(&self) -> Result<Vec<DependencySpec>, MetadataError> {
+        let mut dependencies = Vec::new();
+        for dependency in &self.dependencies {
+            let name = format!("{}:{}", dependency.group_id, dependency.artifact_id);
+            let managed = self.managed.iter().find(|managed| {
+                managed.group_id == dependency.group_id
+                    && managed.artifact_id == dependency.artifact_id
+            });
+            let version = dependency
+                .version
+                .as_ref()
+                .or_else(|| managed?.version.as_ref());
+            let scope = dependency
+                .scope
+                .as_deref()
+                .or_else(|| managed?.scope.as_deref())
+                .unwrap_or("compile");
+            let kind = match scope {
+                "test" => DependencyKind::Dev,
+                // Provided by the environment the artifact runs in, such as a servlet container
+                "provided" => DependencyKind::Peer,
+                _ => DependencyKind::Runtime,
+            };
+            let optional = dependency
+                .optional
+                .as_deref()
+                .or_else(|| managed?.optional.as_deref());
+            let kind = match optional {
+                Some("true") => DependencyKind::Optional,
+                _ => kind,
+            };
+            // A bare version is a soft requirement: Maven uses it unless another part of the graph
+            // asks for a different one, which is read as that version or a later one
+            let version = version
+                .map(|version| {
+                    VersionReq::parse_intervals(version, VersionSyntax::Minimum).map_err(|reason| {
+                        MetadataError::DependencySpecifierError(
+                            name.clone(),
+                            SpecifierError::InvalidRange(version.clone(), reason),
+                        )
+                    })
+                })
+                .transpose()?;
+            let mut spec = DependencySpec::new(&name, kind)
+                .with_version(version)
+                .group((scope != "compile").then_some(scope));
+            if let Some(path) = &dependency.system_path {
+                spec = spec.source(DependencySource::Path { path: path.clone() });
+            }
+            dependencies.push(spec);
+        }
+        DependencySpec::sort(&mut dependencies);
+        Ok(dependencies)
+    }
+}
+
+fn dependency(node: Node) -> Dependency {
+    Dependency {
+        group_id: text(node, "groupId").unwrap_or_default(),
+        artifact_id: text(node, "artifactId").unwrap_or_default(),
+        version: text(node, "version"),
+        scope: text(node, "scope"),
+        optional: text(node, "optional"),
+        system_path: text(node, "systemPath"),
+    }
+}
+
+/// Replace the `${name}` references of `value` with the values of `context`, leaving unknown
+/// ones as written like Maven does
+fn expand(value: &str, context: &HashMap<String, String>, depth: usize) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        match context.get(&rest[start + 2..end]) {
+            Some(value) if depth < MAX_DEPTH => {
+                expanded.push_str(&expand(value, context, depth + 1))
+            }
+            _ => expanded.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// `<item>` elements of the `<list>` child of `node`
+fn items<'a, 'input>(
+    node: Node<'a, 'input>,
+    list: &str,
+    item: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    child(node, list)
+        .into_iter()
+        .flat_map(|list| list.children())
+        .filter(move |child| child.has_tag_name(item))
+}
@@ -0,0 +1,145 @@
+//! `pubspec.yaml` reader for Dart and Flutter packages.
+use crate::dependency::{
+    DependencyKind, DependencySource, DependencySpec, VersionReq, VersionSyntax,
+};
+use crate::npm::SpecifierError;
+use crate::{yaml, MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) fn from_yaml(yaml: &Value, contents: &str) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    for (key, kind) in [
+        ("dependencies", DependencyKind::Runtime),
+        ("dev_dependencies", DependencyKind::Dev),
+        ("dependency_overrides", DependencyKind::Override),
+    ] {
+        let group = (kind != DependencyKind::Runtime).then_some(key);
+        for (name, value) in yaml[key].as_object().into_iter().flatten() {
+            dependencies.push(dependency(name, value, kind)?.group(group));
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let mut urls = HashMap::new();
+    for key in ["homepage", "repository", "issue_tracker", "documentation"] {
+        if let Some(url) = yaml[key].as_str() {
+            urls.insert(key.to_string(), url.to_string());
+        }
+    }
+    // The SDK constraints, `sdk` being Dart's
+    let engines: HashMap<String, String> = yaml["environment"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(engine, constraint)| Some((engine.clone(), constraint.as_str()?.to_string())))
+        .collect();
+
+    Ok(ProjectMetadata {
+        name: yaml["name"].as_str().unwrap_or("").to_string(),
+        version: yaml::top_level_string(yaml, contents, "version").unwrap_or_default(),
+        description: yaml["description"].as_str().map(|s| s.to_string()),
+        // `author` and `authors` are deprecated, pub ignores them but older packages set them
+        authors: match (&yaml["authors"], &yaml["author"]) {
+            (Value::Array(authors), _) => Some(
+                authors
+                    .iter()
+                    .filter_map(|author| author.as_str())
+                    .map(Person::parse)
+                    .collect(),
+            ),
+            (_, Value::String(author)) => Some(vec![Person::parse(author)]),
+            _ => None,
+        },
+        keywords: yaml["topics"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: (!engines.is_empty()).then_some(engines),
+        // Pub workspaces list the paths of their packages
+        workspace: yaml["workspace"].as_array().map(|arr| {
+            arr.iter()
+                .map(|v| v.as_str().unwrap_or("").to_string())
+                .collect()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Read a dependency: a version constraint, or a map naming its `hosted`, `git`, `path` or
+/// `sdk` source, hosted packages being the default
+fn dependency(
+    name: &str,
+    value: &Value,
+    kind: DependencyKind,
+) -> Result<DependencySpec, MetadataError> {
+    let not_a_string = |value: &Value| {
+        MetadataError::DependencySpecifierError(
+            name.to_string(),
+            SpecifierError::NotAString(value.to_string()),
+        )
+    };
+    let constraint = match value {
+        Value::Null => None,
+        Value::String(constraint) => Some(constraint.as_str()),
+        Value::Object(_) => match &value["version"] {
+            Value::Null => None,
+            Value::String(constraint) => Some(constraint.as_str()),
+            version => return Err(not_a_string(version)),
+        },
+        value => return Err(not_a_string(value)),
+    };
+    let source = match (
+        &value["hosted"],
+        &value["git"],
+        &value["path"],
+        &value["sdk"],
+    ) {
+        // The short form only gives the URL, the long one may also rename the package
+        (Value::String(url), ..) => DependencySource::Registry {
+            registry: Some(url.clone()),
+            dist_tag: None,
+        },
+        (hosted @ Value::Object(_), ..) => match hosted["name"].as_str() {
+            Some(package) if package != name => DependencySource::Alias {
+                registry: hosted["url"].as_str().map(|s| s.to_string()),
+                package: package.to_string(),
+                dist_tag: None,
+            },
+            _ => DependencySource::Registry {
+                registry: hosted["url"].as_str().map(|s| s.to_string()),
+                dist_tag: None,
+            },
+        },
+        (_, Value::String(url), ..) => DependencySource::Git {
+            url: url.clone(),
+            branch: None,
+            tag: None,
+            rev: None,
+        },
+        // `ref` is a branch, a tag or a commit alike
+        (_, git @ Value::Object(_), ..) => DependencySource::Git {
+            url: git["url"].as_str().unwrap_or("").to_string(),
+            branch: None,
+            tag: None,
+            rev: git["ref"].as_str().map(|s| s.to_string()),
+        },
+        (_, _, Value::String(path), _) => DependencySource::Path { path: path.clone() },
+        (_, _, _, Value::String(sdk)) => DependencySource::Sdk { sdk: sdk.clone() },
+        _ => DependencySource::default(),
+    };
+    Ok(DependencySpec::new(name, kind)
+        .with_version(constraint.map(version_req))
+        .source(source))
+}
+
+/// Dart constraints are caret, comparison or exact versions, and `any`
+fn version_req(constraint: &str) -> VersionReq {
+    match constraint.trim() {
+        "any" => VersionReq::new(constraint, vec![Vec::new()]),
+        _ => VersionReq::parse(constraint, VersionSyntax::Exact),
+    }
+}
@@ -0,0 +1,520 @@
+//! `pyproject.toml` reader following the [PEP 621] `[project]` table specification, falling
+//! back to the Poetry, PDM, Hatch and Flit `[tool.*]` tables for fields `[project]` lacks.
+//!
+//! [PEP 621]: https://packaging.python.org/en/latest/specifications/pyproject-toml/
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec, VersionSyntax};
+use crate::util::string_array;
+use crate::{FieldValue, Person, ProjectMetadata};
+use std::collections::HashMap;
+use std::path::Path;
+
+mod dynamic;
+
+pub(crate) use dynamic::assignment;
+
+/// Build backend declared in `[build-system].build-backend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BuildBackend {
+    Poetry,
+    Pdm,
+    Hatch,
+    Flit,
+    Setuptools,
+    /// Missing or unrecognised backend, every tool table is probed
+    Unknown,
+}
+
+impl BuildBackend {
+    pub(crate) fn detect(toml: &toml::Value) -> Self {
+        let backend = toml
+            .get("build-system")
+            .and_then(|build_system| build_system.get("build-backend"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        match backend.split(['.', ':']).next().unwrap_or("") {
+            "poetry" => BuildBackend::Poetry,
+            "pdm" => BuildBackend::Pdm,
+            "hatchling" => BuildBackend::Hatch,
+            "flit_core" | "flit" => BuildBackend::Flit,
+            "setuptools" => BuildBackend::Setuptools,
+            _ => BuildBackend::Unknown,
+        }
+    }
+
+    /// Tool tables holding metadata for this backend, in lookup order
+    fn tool_tables(self) -> &'static [BuildBackend] {
+        match self {
+            BuildBackend::Poetry => &[BuildBackend::Poetry],
+            BuildBackend::Pdm => &[BuildBackend::Pdm],
+            BuildBackend::Flit => &[BuildBackend::Flit],
+            BuildBackend::Hatch | BuildBackend::Setuptools => &[],
+            BuildBackend::Unknown => &[BuildBackend::Poetry, BuildBackend::Pdm, BuildBackend::Flit],
+        }
+    }
+}
+
+/// Read `toml`, resolving dynamic fields against the files next to it in `dir`, and leaving
+/// them dynamic without it
+pub(crate) fn from_toml(toml: &toml::Value, dir: Option<&Path>) -> ProjectMetadata {
+    let backend = BuildBackend::detect(toml);
+    let mut layers = Vec::new();
+    if let Some(project) = toml.get("project") {
+        layers.push(("project", project_table(project)));
+    }
+    for tool in backend.tool_tables() {
+        let layer = match tool {
+            BuildBackend::Poetry => {
+                table(toml, &["tool", "poetry"]).map(|t| ("tool.poetry", poetry_table(t)))
+            }
+            BuildBackend::Pdm => {
+                table(toml, &["tool", "pdm"]).map(|t| ("tool.pdm", project_table(t)))
+            }
+            BuildBackend::Flit => table(toml, &["tool", "flit", "metadata"]).map(|t| {
+                (
+                    "tool.flit.metadata",
+                    flit_table(t, table(toml, &["tool", "flit", "scripts"])),
+                )
+            }),
+            _ => None,
+        };
+        layers.extend(layer);
+    }
+    // PEP 735 groups are not tied to any backend
+    if let Some(groups) = toml.get("dependency-groups") {
+        layers.push((
+            "dependency-groups",
+            ProjectMetadata {
+                dependencies: Some(dependency_groups(groups, DependencyKind::Dev)),
+                ..Default::default()
+            },
+        ));
+    }
+
+    let mut metadata = merge(&layers);
+    dynamic::resolve(toml, backend, dir, &mut metadata);
+    metadata
+}
+
+fn table<'a>(toml: &'a toml::Value, path: &[&str]) -> Option<&'a toml::Value> {
+    path.iter().try_fold(toml, |value, key| value.get(key))
+}
+
+/// Take each field from the first layer defining it, recording that layer as its source;
+/// dependencies are the union of every layer, the first declaration of a dependency winning
+fn merge(layers: &[(&str, ProjectMetadata)]) -> ProjectMetadata {
+    let mut merged = ProjectMetadata::default();
+    let mut sources = HashMap::new();
+    if let Some((source, layer)) = layers.iter().find(|(_, layer)| !layer.name.is_empty()) {
+        merged.name = layer.name.clone();
+        sources.insert("name".to_string(), FieldValue::from_table(source));
+    }
+    if let Some((source, layer)) = layers.iter().find(|(_, layer)| !layer.version.is_empty()) {
+        merged.version = layer.version.clone();
+        sources.insert("version".to_string(), FieldValue::from_table(source));
+    }
+    macro_rules! take_first {
+        ($($field:ident),*) => {$(
+            if let Some((source, value)) = layers
+                .iter()
+                .find_map(|(source, layer)| layer.$field.clone().map(|value| (source, value)))
+            {
+                merged.$field = Some(value);
+                sources.insert(stringify!($field).to_string(), FieldValue::from_table(source));
+            }
+        )*};
+    }
+    take_first!(
+        description,
+        authors,
+        license,
+        keywords,
+        scripts,
+        maintainers,
+        license_file,
+        readme,
+        urls,
+        classifiers,
+        engines,
+        dynamic
+    );
+    let mut dependencies: Vec<DependencySpec> = Vec::new();
+    for (source, layer) in layers {
+        let Some(layer_dependencies) = &layer.dependencies else {
+            continue;
+        };
+        sources
+            .entry("dependencies".to_string())
+            .or_insert_with(|| FieldValue::from_table(source));
+        for dependency in layer_dependencies {
+            let declared = dependencies.iter().any(|existing| {
+                (
+                    existing.kind,
+                    &existing.group,
+                    &existing.name,
+                    &existing.markers,
+                ) == (
+                    dependency.kind,
+                    &dependency.group,
+                    &dependency.name,
+                    &dependency.markers,
+                )
+            });
+            if !declared {
+                dependencies.push(dependency.clone());
+            }
+        }
+    }
+    if sources.contains_key("dependencies") {
+        DependencySpec::sort(&mut dependencies);
+        merged.dependencies = Some(dependencies);
+    }
+    merged.sources = (!sources.is_empty()).then_some(sources);
+    merged
+}
+
+/// Read a PEP 621 shaped table, `[project]` or the legacy `[tool.pdm]`
+fn project_table(project: &toml::Value) -> ProjectMetadata {
+    let field = |key: &str| project.get(key);
+    let license = field("license");
+    let readme = field("readme");
+    ProjectMetadata {
+        name: field("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        version: field("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        description: field("description")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        authors: field("authors").and_then(Person::from_toml_list),
+        maintainers: field("maintainers").and_then(Person::from_toml_list),
+        // PEP 639 SPDX expression, or the legacy `{ text = ... }` table
+        license: license
+            .and_then(|v| v.as_str().or_else(|| v.get("text")?.as_str()))
+            .map(|s| s.to_string()),
+        license_file: license
+            .and_then(|v| v.get("file")?.as_str())
+            .map(|s| s.to_string()),
+        readme: readme
+            .and_then(|v| {
+                v.as_str()
+                    .or_else(|| v.get("file")?.as_str())
+                    .or_else(|| v.get("text")?.as_str())
+            })
+            .map(|s| s.to_string()),
+        keywords: field("keywords").and_then(string_array),
+        classifiers: field("classifiers").and_then(string_array),
+        urls: field("urls").and_then(string_table),
+        engines: field("requires-python")
+            .and_then(|v| v.as_str())
+            .map(|spec| HashMap::from([("python".to_string(), spec.to_string())])),
+        dependencies: collect_dependencies([
+            field("dependencies")
+                .and_then(|v| v.as_array())
+                .map(|arr| requirements(arr, DependencyKind::Runtime, None)),
+            field("optional-dependencies")
+                .map(|groups| dependency_groups(groups, DependencyKind::Optional)),
+            // `[tool.pdm.dev-dependencies]`
+            field("dev-dependencies").map(|groups| dependency_groups(groups, DependencyKind::Dev)),
+        ]),
+        scripts: field("scripts").and_then(string_table),
+        dynamic: field("dynamic").and_then(string_array),
+        ..Default::default()
+    }
+}
+
+/// Read `[tool.poetry]`, whose dependencies are a table of Poetry version constraints
+fn poetry_table(poetry: &toml::Value) -> ProjectMetadata {
+    let field = |key: &str| poetry.get(key);
+    let dependencies = field("dependencies").and_then(|v| v.as_table());
+    let mut urls: HashMap<String, String> = ["homepage", "repository", "documentation"]
+        .into_iter()
+        .filter_map(|key| Some((key.to_string(), field(key)?.as_str()?.to_string())))
+        .collect();
+    urls.extend(field("urls").and_then(string_table).unwrap_or_default());
+    // Extras list optional dependencies declared in `[tool.poetry.dependencies]`
+    let extras = field("extras").and_then(|v| v.as_table()).map(|extras| {
+        extras
+            .iter()
+            .flat_map(|(group, names)| {
+                let names = names.as_array().map(Vec::as_slice).unwrap_or_default();
+                names.iter().filter_map(|name| name.as_str()).map(|name| {
+                    let constraint = dependencies.and_then(|deps| deps.get(name));
+                    poetry_constraint(name, constraint.unwrap_or(&toml::Value::Boolean(true)))
+                        .kind(DependencyKind::Optional)
+                        .group(Some(group))
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    ProjectMetadata {
+        name: field("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        version: field("version")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        description: field("description")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        authors: field("authors").and_then(Person::from_toml_list),
+        maintainers: field("maintainers").and_then(Person::from_toml_list),
+        license: field("license")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        readme: field("readme")
+            .and_then(|v| v.as_str().or_else(|| v.get(0)?.as_str()))
+            .map(|s| s.to_string()),
+        keywords: field("keywords").and_then(string_array),
+        classifiers: field("classifiers").and_then(string_array),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: dependencies
+            .and_then(|deps| deps.get("python")?.as_str())
+            .map(|spec| HashMap::from([("python".to_string(), spec.to_string())])),
+        dependencies: collect_dependencies([
+            dependencies.map(|deps| poetry_dependencies(deps, DependencyKind::Runtime, None)),
+            extras,
+            field("dev-dependencies")
+                .and_then(|v| v.as_table())
+                .map(|deps| poetry_dependencies(deps, DependencyKind::Dev, None)),
+            field("group").and_then(|v| v.as_table()).map(|groups| {
+                groups
+                    .iter()
+                    .filter_map(|(group, table)| {
+                        Some((group, table.get("dependencies")?.as_table()?))
+                    })
+                    .flat_map(|(group, deps)| {
+                        poetry_dependencies(deps, DependencyKind::Dev, Some(group))
+                    })
+                    .collect()
+            }),
+        ]),
+        scripts: field("scripts").and_then(|v| v.as_table()).map(|scripts| {
+            scripts
+                .iter()
+                .map(|(name, script)| {
+                    let reference = script
+                        .as_str()
+                        .or_else(|| script.get("reference")?.as_str())
+                        .or_else(|| script.get("callable")?.as_str());
+                    (name.clone(), reference.unwrap_or("").to_string())
+                })
+                .collect()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Read a Poetry dependencies table; `python` is the interpreter constraint, not a dependency,
+/// and optional dependencies are only listed through extras
+fn poetry_dependencies(
+    deps: &toml::map::Map<String, toml::Value>,
+    kind: DependencyKind,
+    group: Option<&str>,
+) -> Vec<DependencySpec> {
+    deps.iter()
+        .filter(|(name, _)| name.as_str() != "python")
+        .map(|(name, constraint)| poetry_constraint(name, constraint))
+        .filter(|dependency| dependency.kind != DependencyKind::Optional)
+        .map(|dependency| dependency.kind(kind).group(group))
+        .collect()
+}
+
+/// Convert a Poetry constraint, `"^2.0"` or `{ version = "^2.0", extras = [...], ... }`; for
+/// multiple-constraint lists the first entry is kept
+fn poetry_constraint(name: &str, constraint: &toml::Value) -> DependencySpec {
+    let constraint = constraint.get(0).unwrap_or(constraint);
+    let dependency = DependencySpec::new(name, DependencyKind::Runtime);
+    if let Some(version) = constraint.as_str() {
+        return dependency.version(Some(version), VersionSyntax::Exact);
+    }
+    let get = |key: &str| constraint.get(key).and_then(|v| v.as_str());
+    let owned = |key: &str| get(key).map(|s| s.to_string());
+    let source = if let Some(url) = get("git") {
+        DependencySource::Git {
+            url: url.to_string(),
+            branch: owned("branch"),
+            tag: owned("tag"),
+            rev: owned("rev"),
+        }
+    } else if let Some(path) = get("path") {
+        DependencySource::Path {
+            path: path.to_string(),
+        }
+    } else if let Some(url) = get("url") {
+        DependencySource::Url {
+            url: url.to_string(),
+        }
+    } else {
+        DependencySource::Registry {
+            registry: owned("source"),
+            dist_tag: None,
+        }
+    };
+    let kind = match constraint.get("optional").and_then(|v| v.as_bool()) {
+        Some(true) => DependencyKind::Optional,
+        _ => DependencyKind::Runtime,
+    };
+    dependency
+        .kind(kind)
+        .version(get("version"), VersionSyntax::Exact)
+        .source(source)
+        .features(constraint.get("extras").and_then(string_array))
+        .markers(owned("markers"))
+}
+
+/// Read the legacy flit `[tool.flit.metadata]` table; the version and description come from
+/// the module itself
+fn flit_table(flit: &toml::Value, scripts: Option<&toml::Value>) -> ProjectMetadata {
+    let get = |key: &str| flit.get(key).and_then(|v| v.as_str());
+    let person = |name: Option<&str>, email: Option<&str>| {
+        (name.is_some() || email.is_some()).then(|| vec![Person::new(name, email, None)])
+    };
+    let mut urls = flit.get("urls").and_then(string_table).unwrap_or_default();
+    if let Some(home) = get("home-page") {
+        urls.insert("homepage".to_string(), home.to_string());
+    }
+    ProjectMetadata {
+        name: get("dist-name")
+            .or_else(|| get("module"))
+            .unwrap_or("")
+            .to_string(),
+        authors: person(get("author"), get("author-email")),
+        maintainers: person(get("maintainer"), get("maintainer-email")),
+        license: get("license").map(|s| s.to_string()),
+        readme: get("description-file").map(|s| s.to_string()),
+        keywords: get("keywords").map(|keywords| {
+            keywords
+                .split([',', ' '])
+                .filter(|keyword| !keyword.is_empty())
+                .map(|keyword| keyword.to_string())
+                .collect()
+        }),
+        classifiers: flit.get("classifiers").and_then(string_array),
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: get("requires-python")
+            .map(|spec| HashMap::from([("python".to_string(), spec.to_string())])),
+        dependencies: collect_dependencies([
+            flit.get("requires")
+                .and_then(|v| v.as_array())
+                .map(|arr| requirements(arr, DependencyKind::Runtime, None)),
+            flit.get("requires-extra")
+                .map(|groups| dependency_groups(groups, DependencyKind::Optional)),
+        ]),
+        scripts: scripts.and_then(string_table),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn requirements(
+    arr: &[toml::Value],
+    kind: DependencyKind,
+    group: Option<&str>,
+) -> Vec<DependencySpec> {
+    arr.iter()
+        .filter_map(|v| v.as_str())
+        .map(|requirement| parse_requirement(requirement).kind(kind).group(group))
+        .collect()
+}
+
+/// Read a table of named requirement lists, such as extras or dependency groups; PEP 735
+/// `{ include-group = ... }` entries are skipped
+fn dependency_groups(groups: &toml::Value, kind: DependencyKind) -> Vec<DependencySpec> {
+    groups
+        .as_table()
+        .into_iter()
+        .flatten()
+        .filter_map(|(group, reqs)| Some((group, reqs.as_array()?)))
+        .flat_map(|(group, reqs)| requirements(reqs, kind, Some(group)))
+        .collect()
+}
+
+/// Concatenate the dependency lists a table declares, `None` when it declares none
+fn collect_dependencies<const N: usize>(
+    lists: [Option<Vec<DependencySpec>>; N],
+) -> Option<Vec<DependencySpec>> {
+    let mut lists = lists.into_iter().flatten().peekable();
+    lists.peek()?;
+    Some(lists.flatten().collect())
+}
+
+/// Parse a [PEP 508] requirement such as `requests[socks] >=2.8.1, ==2.8.* ; python_version < "2.7"`.
+/// Direct references to `git+` URLs become git sources, with the revision after the last `@`.
+///
+/// [PEP 508]: https://peps.python.org/pep-0508/
+pub(crate) fn parse_requirement(requirement: &str) -> DependencySpec {
+    let (requirement, markers) = match requirement.split_once(';') {
+        Some((requirement, markers)) => (requirement, Some(markers.trim().to_string())),
+        None => (requirement, None),
+    };
+    let requirement = requirement.trim();
+    let name_end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let (name, mut rest) = requirement.split_at(name_end);
+    rest = rest.trim_start();
+
+    let mut extras = None;
+    if let Some(stripped) = rest.strip_prefix('[') {
+        if let Some((list, after)) = stripped.split_once(']') {
+            extras = Some(
+                list.split(',')
+                    .map(|extra| extra.trim().to_string())
+                    .filter(|extra| !extra.is_empty())
+                    .collect(),
+            );
+            rest = after.trim_start();
+        }
+    }
+
+    let dependency = DependencySpec::new(name, DependencyKind::Runtime)
+        .features(extras)
+        .markers(markers);
+    match rest.strip_prefix('@').map(str::trim) {
+        Some(url) => dependency.source(match url.strip_prefix("git+") {
+            Some(git) => {
+                // `@` may also introduce credentials, only look for a revision in the path
+                let path_start = git.find("://").map_or(0, |scheme| scheme + 3);
+                let path_start = path_start + git[path_start..].find('/').unwrap_or(0);
+                let (url, rev) = match git[path_start..].rfind('@') {
+                    Some(at) => (
+                        &git[..path_start + at],
+                        Some(git[path_start + at + 1..].to_string()),
+                    ),
+                    None => (git, None),
+                };
+                DependencySource::Git {
+                    url: url.to_string(),
+                    branch: None,
+                    tag: None,
+                    rev,
+                }
+            }
+            None => DependencySource::Url {
+                url: url.to_string(),
+            },
+        }),
+        None => {
+            let spec = rest
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .trim();
+            dependency.version((!spec.is_empty()).then_some(spec), VersionSyntax::Exact)
+        }
+    }
+}
+
+fn string_table(value: &toml::Value) -> Option<HashMap<String, String>> {
+    value.as_table().map(|table| {
+        table
+            .iter()
+            .map(|(k, v)| (k.clone(), v.as_str().unwrap_or("").to_string()))
+            .collect()
+    })
+}
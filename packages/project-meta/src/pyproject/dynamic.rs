@@ -0,0 +1,365 @@
+//! Resolution of `[project].dynamic` fields, following the conventions of each build backend
+//! without running it: values are only read from files on disk, never computed.
+use super::{parse_requirement, table, BuildBackend};
+use crate::dependency::{DependencyKind, DependencySpec};
+use crate::setuptools::Directives;
+use crate::{FieldValue, ProjectMetadata};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Names module-level version assignments are looked up under
+const VERSION_NAMES: [&str; 2] = ["__version__", "VERSION"];
+
+enum Resolution<T> {
+    /// Value read from the `source` file or table
+    Resolved { value: T, source: String },
+    /// Value only known once `source` runs
+    Dynamic { source: String },
+}
+
+pub(super) fn resolve(
+    toml: &toml::Value,
+    backend: BuildBackend,
+    dir: Option<&Path>,
+    metadata: &mut ProjectMetadata,
+) {
+    // Legacy flit always takes the version and description from the module
+    let legacy_flit = toml.get("project").is_none()
+        && matches!(backend, BuildBackend::Flit | BuildBackend::Unknown)
+        && table(toml, &["tool", "flit", "metadata"]).is_some();
+    let mut fields = metadata.dynamic.clone().unwrap_or_default();
+    if legacy_flit {
+        fields.extend(["version".to_string(), "description".to_string()]);
+    }
+    if fields.is_empty() {
+        return;
+    }
+
+    let flit = backend == BuildBackend::Flit || legacy_flit;
+    let resolver = Resolver {
+        toml,
+        backend,
+        flit,
+        dir,
+    };
+    let sources = metadata.sources.get_or_insert_with(HashMap::new);
+    for field in fields {
+        let key = match field.as_str() {
+            "requires-python" => "engines".to_string(),
+            field => field.replace('-', "_"),
+        };
+        let source = match field.as_str() {
+            "version" if metadata.version.is_empty() => {
+                resolver.version().map(|value| metadata.version = value)
+            }
+            "description" if metadata.description.is_none() => resolver
+                .description()
+                .map(|value| metadata.description = Some(value)),
+            "readme" if metadata.readme.is_none() => {
+                resolver.readme().map(|value| metadata.readme = Some(value))
+            }
+            "dependencies" if !declares(&metadata.dependencies, DependencyKind::Runtime) => {
+                resolver
+                    .dependencies()
+                    .map(|value| extend_dependencies(&mut metadata.dependencies, value))
+            }
+            "optional-dependencies"
+                if !declares(&metadata.dependencies, DependencyKind::Optional) =>
+            {
+                resolver
+                    .optional_dependencies()
+                    .map(|value| extend_dependencies(&mut metadata.dependencies, value))
+            }
+            _ if sources.contains_key(&key) => continue,
+            _ => Resolution::Dynamic {
+                source: resolver.backend_source(&field),
+            },
+        };
+        let source = match source {
+            Resolution::Resolved { source, .. } => FieldValue::Resolved { source },
+            Resolution::Dynamic { source } => FieldValue::Dynamic { source },
+        };
+        sources.insert(key, source);
+    }
+}
+
+fn declares(dependencies: &Option<Vec<DependencySpec>>, kind: DependencyKind) -> bool {
+    dependencies
+        .iter()
+        .flatten()
+        .any(|dependency| dependency.kind == kind)
+}
+
+fn extend_dependencies(
+    dependencies: &mut Option<Vec<DependencySpec>>,
+    resolved: Vec<DependencySpec>,
+) {
+    let dependencies = dependencies.get_or_insert_with(Vec::new);
+    dependencies.extend(resolved);
+    DependencySpec::sort(dependencies);
+}
+
+impl<T> Resolution<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Resolution<U> {
+        match self {
+            Resolution::Resolved { value, source } => Resolution::Resolved {
+                value: f(value),
+                source,
+            },
+            Resolution::Dynamic { source } => Resolution::Dynamic { source },
+        }
+    }
+}
+
+struct Resolver<'a> {
+    toml: &'a toml::Value,
+    backend: BuildBackend,
+    /// Whether flit builds the project, including legacy manifests without `[build-system]`
+    flit: bool,
+    dir: Option<&'a Path>,
+}
+
+impl Resolver<'_> {
+    fn version(&self) -> Resolution<String> {
+        if self.flit {
+            if let Some(resolved) = self.module().and_then(|module| self.assignment_in(&module)) {
+                return resolved;
+            }
+        }
+        match self.backend {
+            BuildBackend::Hatch => {
+                if let Some(version) = table(self.toml, &["tool", "hatch", "version"]) {
+                    let source = version.get("source").and_then(|v| v.as_str());
+                    let path = version.get("path").and_then(|v| v.as_str());
+                    // A custom `pattern` is a regex only hatch can evaluate
+                    if let (None | Some("regex"), Some(path), None) =
+                        (source, path, version.get("pattern"))
+                    {
+                        if let Some(resolved) = self.assignment_in(path) {
+                            return resolved;
+                        }
+                    }
+                    return dynamic("tool.hatch.version");
+                }
+            }
+            BuildBackend::Pdm => {
+                if let Some(version) = table(self.toml, &["tool", "pdm", "version"]) {
+                    let source = version.get("source").and_then(|v| v.as_str());
+                    let path = version.get("path").and_then(|v| v.as_str());
+                    if let (Some("file"), Some(path)) = (source, path) {
+                        if let Some(resolved) = self.assignment_in(path) {
+                            return resolved;
+                        }
+                    }
+                    return dynamic("tool.pdm.version");
+                }
+            }
+            BuildBackend::Setuptools | BuildBackend::Unknown => {
+                if let Some(resolved) = self.setuptools("version") {
+                    return resolved;
+                }
+                if table(self.toml, &["tool", "setuptools_scm"]).is_some() {
+                    return dynamic("tool.setuptools_scm");
+                }
+            }
+            BuildBackend::Poetry | BuildBackend::Flit => {}
+        }
+        dynamic(&self.backend_source("version"))
+    }
+
+    fn description(&self) -> Resolution<String> {
+        let resolved = if self.flit {
+            // Flit uses the first line of the module docstring
+            self.module().and_then(|module| {
+                let summary = docstring_summary(&self.read(&module)?)?;
+                Some(Resolution::Resolved {
+                    value: summary,
+                    source: module,
+                })
+            })
+        } else {
+            self.setuptools("description")
+        };
+        resolved.unwrap_or_else(|| dynamic(&self.backend_source("description")))
+    }
+
+    fn readme(&self) -> Resolution<String> {
+        let path = ["tool", "setuptools", "dynamic", "readme"];
+        match table(self.toml, &path).and_then(|readme| readme.get("file")) {
+            Some(file) => match files(file).first() {
+                Some(file) => Resolution::Resolved {
+                    value: file.to_string(),
+                    source: path.join("."),
+                },
+                None => dynamic(&path.join(".")),
+            },
+            None => dynamic(&self.backend_source("readme")),
+        }
+    }
+
+    fn dependencies(&self) -> Resolution<Vec<DependencySpec>> {
+        let path = ["tool", "setuptools", "dynamic", "dependencies"];
+        table(self.toml, &path)
+            .and_then(|spec| self.requirements_files(spec, DependencyKind::Runtime, None))
+            .unwrap_or_else(|| dynamic(&self.backend_source("dependencies")))
+    }
+
+    fn optional_dependencies(&self) -> Resolution<Vec<DependencySpec>> {
+        let path = ["tool", "setuptools", "dynamic", "optional-dependencies"];
+        let Some(groups) = table(self.toml, &path).and_then(|v| v.as_table()) else {
+            return dynamic(&self.backend_source("optional-dependencies"));
+        };
+        let mut resolved = Vec::new();
+        for (group, spec) in groups {
+            match self.requirements_files(spec, DependencyKind::Optional, Some(group)) {
+                Some(Resolution::Resolved { value, .. }) => resolved.extend(value),
+                _ => return dynamic(&path.join(".")),
+            }
+        }
+        Resolution::Resolved {
+            value: resolved,
+            source: path.join("."),
+        }
+    }
+
+    /// Table driving a field we cannot resolve, falling back to the build backend itself
+    fn backend_source(&self, field: &str) -> String {
+        let setuptools = ["tool", "setuptools", "dynamic", field];
+        if table(self.toml, &setuptools).is_some() {
+            return setuptools.join(".");
+        }
+        if self.backend == BuildBackend::Hatch
+            && table(self.toml, &["tool", "hatch", "metadata", "hooks"]).is_some()
+        {
+            return "tool.hatch.metadata.hooks".to_string();
+        }
+        table(self.toml, &["build-system", "build-backend"])
+            .and_then(|v| v.as_str())
+            .unwrap_or("build-system")
+            .to_string()
+    }
+
+    /// `[tool.setuptools.dynamic]` `{ attr = "pkg.__version__" }` or `{ file = [...] }` entry
+    fn setuptools(&self, field: &str) -> Option<Resolution<String>> {
+        let spec = table(self.toml, &["tool", "setuptools", "dynamic", field])?;
+        let root =
+            table(self.toml, &["tool", "setuptools", "package-dir", ""]).and_then(|v| v.as_str());
+        let directives = Directives::new(self.dir, root);
+        if let Some(attr) = spec.get("attr").and_then(|v| v.as_str()) {
+            return Some(match directives.attr(attr) {
+                Some((value, source)) => Resolution::Resolved { value, source },
+                None => dynamic(&format!("attr:{attr}")),
+            });
+        }
+        let (value, source) = directives.files(&files(spec.get("file")?))?;
+        Some(Resolution::Resolved { value, source })
+    }
+
+    /// `{ file = ["requirements.txt"] }` whose lines are PEP 508 requirements
+    fn requirements_files(
+        &self,
+        spec: &toml::Value,
+        kind: DependencyKind,
+        group: Option<&str>,
+    ) -> Option<Resolution<Vec<DependencySpec>>> {
+        let files = files(spec.get("file")?);
+        let mut requirements = Vec::new();
+        for file in &files {
+            for line in self.read(file)?.lines() {
+                let line = line.split(" #").next().unwrap_or("").trim();
+                // Skip comments and pip options such as `-r other.txt` or `--index-url`
+                if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+                    continue;
+                }
+                requirements.push(parse_requirement(line).kind(kind).group(group));
+            }
+        }
+        Some(Resolution::Resolved {
+            value: requirements,
+            source: files.join(", "),
+        })
+    }
+
+    /// Module file for flit, from `[tool.flit.module]`, the legacy metadata or the project name
+    fn module(&self) -> Option<String> {
+        let name = table(self.toml, &["tool", "flit", "module", "name"])
+            .or_else(|| table(self.toml, &["tool", "flit", "metadata", "module"]))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .or_else(|| {
+                let name = table(self.toml, &["project", "name"])?.as_str()?;
+                Some(name.replace('-', "_"))
+            })?;
+        let module = name.replace('.', "/");
+        ["", "src"].into_iter().find_map(|root| {
+            [format!("{module}/__init__.py"), format!("{module}.py")]
+                .into_iter()
+                .map(|candidate| Path::new(root).join(candidate))
+                .find(|path| self.dir.is_some_and(|dir| dir.join(path).is_file()))
+                .map(|path| path.to_string_lossy().into_owned())
+        })
+    }
+
+    fn assignment_in(&self, path: &str) -> Option<Resolution<String>> {
+        let value = assignment(&self.read(path)?, &VERSION_NAMES)?;
+        Some(Resolution::Resolved {
+            value,
+            source: path.to_string(),
+        })
+    }
+
+    fn read(&self, path: &str) -> Option<String> {
+        fs::read_to_string(self.dir?.join(path)).ok()
+    }
+}
+
+fn dynamic<T>(source: &str) -> Resolution<T> {
+    Resolution::Dynamic {
+        source: source.to_string(),
+    }
+}
+
+fn files(spec: &toml::Value) -> Vec<&str> {
+    match spec {
+        toml::Value::String(file) => vec![file.as_str()],
+        spec => spec
+            .as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Find a module level `NAME = "value"` (optionally annotated `NAME: str = "value"`) assignment
+pub(crate) fn assignment(source: &str, names: &[&str]) -> Option<String> {
+    source.lines().find_map(|line| {
+        let rest = names.iter().find_map(|name| line.strip_prefix(name))?;
+        let rest = rest.trim_start();
+        let rest = match rest.strip_prefix(':') {
+            Some(annotated) => annotated.split_once('=')?.1,
+            None => rest.strip_prefix('=')?,
+        }
+        .trim_start();
+        let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let (value, _) = rest[1..].split_once(quote)?;
+        Some(value.to_string())
+    })
+}
+
+/// First line of a module docstring
+fn docstring_summary(source: &str) -> Option<String> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with('#'));
+    let first = lines.next()?;
+    let first = first.trim_start_matches(['r', 'u', 'R', 'U']);
+    let quote = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|quote| first.starts_with(quote))?;
+    std::iter::once(&first[quote.len()..])
+        .chain(lines)
+        .map(|line| line.split(quote).next().unwrap_or("").trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.to_string())
+}
@@ -0,0 +1,352 @@
+//! R package `DESCRIPTION` files. `Authors@R` is read when it is made of `person()` calls with
+//! literal arguments, as it almost always is, and reported as dynamic otherwise.
+use crate::dcf::Field;
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec, VersionSyntax};
+use crate::util::any_license;
+use crate::{FieldValue, Person, ProjectMetadata};
+use std::collections::HashMap;
+
+/// Dependency fields, with the kind and group of their packages
+const DEPENDENCY_FIELDS: [(&str, DependencyKind, Option<&str>); 5] = [
+    ("Depends", DependencyKind::Runtime, None),
+    ("Imports", DependencyKind::Runtime, None),
+    ("LinkingTo", DependencyKind::Build, Some("LinkingTo")),
+    ("Suggests", DependencyKind::Optional, Some("Suggests")),
+    ("Enhances", DependencyKind::Optional, Some("Enhances")),
+];
+
+/// Fields read into [`ProjectMetadata`] fields rather than `properties`
+const FIELDS: [&str; 15] = [
+    "Package",
+    "Version",
+    "Title",
+    "Description",
+    "Authors@R",
+    "Author",
+    "Maintainer",
+    "License",
+    "URL",
+    "BugReports",
+    "Depends",
+    "Imports",
+    "Suggests",
+    "LinkingTo",
+    "Enhances",
+];
+
+/// Packages R ships with
+const BASE_PACKAGES: [&str; 14] = [
+    "base",
+    "compiler",
+    "datasets",
+    "grDevices",
+    "graphics",
+    "grid",
+    "methods",
+    "parallel",
+    "splines",
+    "stats",
+    "stats4",
+    "tcltk",
+    "tools",
+    "utils",
+];
+
+pub(crate) fn from_dcf(fields: &[Field]) -> ProjectMetadata {
+    let get = |name: &str| {
+        fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.value.as_str())
+    };
+    // Continuation lines only wrap the text
+    let text =
+        |name: &str| get(name).map(|value| value.split_whitespace().collect::<Vec<_>>().join(" "));
+
+    let mut dynamic = Vec::new();
+    let mut sources = HashMap::new();
+    let (mut authors, mut maintainers, mut contributors) = (Vec::new(), Vec::new(), Vec::new());
+    match get("Authors@R") {
+        Some(value) => match authors_r(value) {
+            Some(people) => {
+                for (person, roles) in people {
+                    let has = |role: &str| roles.iter().any(|r| r == role);
+                    if has("aut") {
+                        authors.push(person.clone());
+                    }
+                    if has("cre") {
+                        maintainers.push(person.clone());
+                    }
+                    if has("ctb") {
+                        contributors.push(person);
+                    }
+                }
+                sources.insert("authors".to_string(), FieldValue::from_table("Authors@R"));
+            }
+            None => {
+                dynamic.push("authors".to_string());
+                let source = value.to_string();
+                sources.insert("authors".to_string(), FieldValue::Dynamic { source });
+            }
+        },
+        // `Author` is free text, where people may be followed by their roles in brackets
+        None => {
+            for author in text("Author").iter().flat_map(|authors| authors.split(',')) {
+                let name = author.split('[').next().unwrap_or(author);
+                if !name.trim().is_empty() {
+                    authors.push(Person::parse(name));
+                }
+            }
+            maintainers.extend(text("Maintainer").map(|maintainer| Person::parse(&maintainer)));
+        }
+    }
+
+    // `MIT + file LICENSE` adds the terms in `LICENSE` to the license, `|` separates alternatives
+    let mut license_file = None;
+    let licenses: Vec<String> = text("License")
+        .iter()
+        .flat_map(|license| license.split('|'))
+        .filter_map(|license| {
+            let (license, file) = match license.split_once("file ") {
+                Some((license, file)) => (license.trim().trim_end_matches('+').trim(), Some(file)),
+                None => (license.trim(), None),
+            };
+            if let Some(file) = file {
+                license_file.get_or_insert_with(|| file.trim().to_string());
+            }
+            (!license.is_empty()).then(|| license.to_string())
+        })
+        .collect();
+
+    let mut urls = HashMap::new();
+    let links: Vec<String> = text("URL")
+        .iter()
+        .flat_map(|urls| urls.split([',', ' ']))
+        .filter(|url| !url.is_empty())
+        .map(|url| url.to_string())
+        .collect();
+    if let Some(homepage) = links.first() {
+        urls.insert("homepage".to_string(), homepage.clone());
+    }
+    if let Some(repository) = links
+        .iter()
+        .find(|url| url.contains("github.com") || url.contains("gitlab.com"))
+    {
+        urls.insert("repository".to_string(), repository.clone());
+    }
+    if let Some(issues) = text("BugReports") {
+        urls.insert("issues".to_string(), issues);
+    }
+
+    let mut engines = HashMap::new();
+    let mut dependencies = Vec::new();
+    for (field, kind, group) in DEPENDENCY_FIELDS {
+        for dependency in text(field).iter().flat_map(|packages| packages.split(',')) {
+            let dependency = dependency.trim();
+            let (name, constraint) = match dependency.split_once('(') {
+                Some((name, constraint)) => {
+                    (name.trim(), Some(constraint.trim_end_matches(')').trim()))
+                }
+                None => (dependency, None),
+            };
+            if name.is_empty() {
+                continue;
+            }
+            // `Depends: R (>= 4.1)` is the version of R itself
+            if name == "R" {
+                if let Some(constraint) = constraint {
+                    engines.insert("r".to_string(), constraint.to_string());
+                }
+                continue;
+            }
+            let mut spec = DependencySpec::new(name, kind)
+                .version(constraint, VersionSyntax::Exact)
+                .group(group);
+            if BASE_PACKAGES.contains(&name) {
+                spec = spec.source(DependencySource::Sdk {
+                    sdk: "r".to_string(),
+                });
+            }
+            dependencies.push(spec);
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let properties: HashMap<String, String> = fields
+        .iter()
+        .filter(|field| !FIELDS.contains(&field.name.as_str()))
+        .map(|field| (field.name.clone(), field.value.clone()))
+        .collect();
+
+    ProjectMetadata {
+        name: get("Package").unwrap_or_default().to_string(),
+        version: get("Version").unwrap_or_default().to_string(),
+        description: text("Title"),
+        authors: (!authors.is_empty()).then_some(authors),
+        maintainers: (!maintainers.is_empty()).then_some(maintainers),
+        contributors: (!contributors.is_empty()).then_some(contributors),
+        license: any_license(&licenses),
+        license_file,
+        urls: (!urls.is_empty()).then_some(urls),
+        engines: (!engines.is_empty()).then_some(engines),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties: (!properties.is_empty()).then_some(properties),
+        dynamic: (!dynamic.is_empty()).then_some(dynamic),
+        sources: (!sources.is_empty()).then_some(sources),
+        ..Default::default()
+    }
+}
+
+/// R value as far as `Authors@R` needs it
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Str(String),
+    Call {
+        name: String,
+        args: Vec<(Option<String>, Value)>,
+    },
+}
+
+impl Value {
+    fn strings(&self) -> Option<Vec<String>> {
+        match self {
+            Value::Str(value) => Some(vec![value.clone()]),
+            Value::Call { name, args } if name == "c" => args
+                .iter()
+                .map(|(_, value)| match value {
+                    Value::Str(value) => Some(value.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// People of the `Authors@R` expression `c(person(...), ...)` or of a single `person(...)`,
+/// with their roles
+fn authors_r(expression: &str) -> Option<Vec<(Person, Vec<String>)>> {
+    let mut reader = Reader { rest: expression };
+    let value = reader.value()?;
+    if !reader.rest.trim().is_empty() {
+        return None;
+    }
+    let persons = match value {
+        Value::Call { name, args } if name == "c" => {
+            args.into_iter().map(|(_, value)| value).collect()
+        }
+        value => vec![value],
+    };
+    persons.iter().map(person).collect()
+}
+
+/// `person(given, family, middle, email, role, comment)`, arguments being positional or named
+fn person(value: &Value) -> Option<(Person, Vec<String>)> {
+    const PARAMETERS: [&str; 6] = ["given", "family", "middle", "email", "role", "comment"];
+    let Value::Call { name, args } = value else {
+        return None;
+    };
+    if name != "person" {
+        return None;
+    }
+    let mut named: HashMap<&str, &Value> = HashMap::new();
+    let mut positional = PARAMETERS.iter();
+    for (name, value) in args {
+        let parameter = match name {
+            Some(name) => PARAMETERS.iter().find(|parameter| *parameter == name)?,
+            None => positional.find(|parameter| !named.contains_key(**parameter))?,
+        };
+        named.insert(parameter, value);
+    }
+    let strings = |parameter: &str| named.get(parameter).and_then(|value| value.strings());
+    let name = ["given", "middle", "family"]
+        .iter()
+        .filter_map(|parameter| strings(parameter))
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let email = strings("email").and_then(|emails| emails.into_iter().next());
+    // `comment = c(ORCID = "...")` identifies the person
+    let orcid = match named.get("comment") {
+        Some(Value::Call { args, .. }) => args.iter().find_map(|(name, value)| match value {
+            Value::Str(orcid) if name.as_deref() == Some("ORCID") => Some(orcid.clone()),
+            _ => None,
+        }),
+        _ => None,
+    };
+    let url = orcid.map(|orcid| match orcid.starts_with("http") {
+        true => orcid,
+        false => format!("https://orcid.org/{orcid}"),
+    });
+    let roles = strings("role").unwrap_or_else(|| vec!["aut".to_string()]);
+    let person = Person::new(Some(&name), email.as_deref(), url.as_deref());
+    Some((person, roles))
+}
+
+struct Reader<'a> {
+    rest: &'a str,
+}
+
+impl Reader<'_> {
+    fn eat(&mut self, punct: char) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(punct) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        self.rest = self.rest.trim_start();
+        let len = self
+            .rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '.' || c == '_'))
+            .unwrap_or(self.rest.len());
+        let name = &self.rest[..len];
+        self.rest = &self.rest[len..];
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.rest = self.rest.trim_start();
+        let quote = self.rest.chars().next()?;
+        if quote == '"' || quote == '\'' {
+            let mut value = String::new();
+            let mut chars = self.rest[1..].char_indices();
+            while let Some((offset, c)) = chars.next() {
+                match c {
+                    '\\' => value.push(chars.next()?.1),
+                    c if c == quote => {
+                        self.rest = &self.rest[offset + 2..];
+                        return Some(Value::Str(value));
+                    }
+                    c => value.push(c),
+                }
+            }
+            return None;
+        }
+        let name = self.name()?;
+        if !self.eat('(') {
+            return None;
+        }
+        let mut args = Vec::new();
+        while !self.eat(')') {
+            // `name = value`, where `=` is not followed by another `=`
+            let checkpoint = self.rest;
+            let arg_name = self
+                .name()
+                .filter(|_| self.eat('=') && !self.rest.starts_with('='));
+            if arg_name.is_none() {
+                self.rest = checkpoint;
+            }
+            args.push((arg_name, self.value()?));
+            if !self.eat(',') && !self.rest.trim_start().starts_with(')') {
+                return None;
+            }
+        }
+        Some(Value::Call { name, args })
+    }
+}
@@ -0,0 +1,691 @@
+//! A static reading of the Ruby subset `*.gemspec` files and Gemfiles are written in: literals
+//! are evaluated, anything else is kept as its source text for the caller to report.
+use crate::dependency::{Comparator, Op, VersionReq};
+use crate::syntax::SyntaxError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    /// Identifiers and keywords
+    Ident(String),
+    /// Capitalized identifiers
+    Const(String),
+    /// String literal, `None` when it interpolates `#{...}`
+    Str(Option<String>),
+    /// `%w[...]` and `%i[...]` word lists
+    Words(Vec<String>),
+    Symbol(String),
+    Number(String),
+    /// `key:` of a hash or of keyword arguments
+    Label(String),
+    Punct(&'static str),
+    Newline,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Spanned {
+    pub(crate) token: Token,
+    pub(crate) line: usize,
+    /// Byte range of the token in the source
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// Operators, longest first
+const PUNCTUATION: [&str; 33] = [
+    "**=", "<=>", "===", "&.", "::", "=>", "==", "!=", ">=", "<=", "&&", "||", "<<", ">>", "+=",
+    "-=", "||=", "=", ".", ",", "(", ")", "[", "]", "{", "}", "|", ";", "+", "-", "*", "!", "?",
+];
+
+/// Single character operators not in [`PUNCTUATION`]
+const OTHER_PUNCTUATION: [&str; 7] = ["<", ">", "&", "/", "%", ":", "^"];
+
+pub(crate) fn lex(contents: &str) -> Result<Vec<Spanned>, SyntaxError> {
+    Lexer {
+        contents,
+        pos: 0,
+        line: 1,
+        heredoc_resume: None,
+        tokens: Vec::new(),
+    }
+    .run()
+}
+
+struct Lexer<'a> {
+    contents: &'a str,
+    pos: usize,
+    line: usize,
+    /// Offset of the newline ending a line that opened a heredoc, and where to resume after it
+    heredoc_resume: Option<(usize, usize, usize)>,
+    tokens: Vec<Spanned>,
+}
+
+impl Lexer<'_> {
+    fn run(mut self) -> Result<Vec<Spanned>, SyntaxError> {
+        while let Some(c) = self.peek() {
+            let start = self.pos;
+            match c {
+                '\n' => {
+                    self.push(Token::Newline, start, start + 1);
+                    self.line += 1;
+                    self.pos += 1;
+                    if let Some((newline, resume, lines)) = self.heredoc_resume {
+                        if newline == start {
+                            self.pos = resume;
+                            self.line += lines;
+                            self.heredoc_resume = None;
+                        }
+                    }
+                    // `=begin` ... `=end` comments
+                    if self.rest().starts_with("=begin") {
+                        self.block_comment()?;
+                    }
+                }
+                '\\' if self.rest()[1..].starts_with('\n') => {
+                    // Escaped newlines continue the statement
+                    self.pos += 2;
+                    self.line += 1;
+                }
+                c if c.is_whitespace() => self.pos += c.len_utf8(),
+                '#' => {
+                    let end = self
+                        .rest()
+                        .find('\n')
+                        .map_or(self.contents.len(), |end| start + end);
+                    self.pos = end;
+                }
+                '"' | '\'' | '`' => {
+                    self.pos += 1;
+                    let value = self.quoted(c, c, c != '\'')?;
+                    self.push(Token::Str(value), start, self.pos);
+                }
+                '%' if self.percent_literal()? => {}
+                '<' if self.heredoc()? => {}
+                ':' if self.rest()[1..].starts_with('"') => {
+                    self.pos += 2;
+                    let value = self.quoted('"', '"', true)?;
+                    let symbol = value.unwrap_or_default();
+                    self.push(Token::Symbol(symbol), start, self.pos);
+                }
+                ':' if self.rest()[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') => {
+                    self.pos += 1;
+                    let name = self.word();
+                    self.push(Token::Symbol(name), start, self.pos);
+                }
+                c if c.is_ascii_digit() => {
+                    let end = self
+                        .rest()
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                        .map_or(self.contents.len(), |end| start + end);
+                    // `1.method` is a call on an integer
+                    let number = self.contents[start..end].trim_end_matches('.');
+                    self.pos = start + number.len();
+                    self.push(Token::Number(number.to_string()), start, self.pos);
+                }
+                c if c.is_alphabetic() || c == '_' || c == '@' || c == '$' => {
+                    let word = self.word();
+                    let label = self.peek() == Some(':') && !self.rest().starts_with("::");
+                    let token = if label && !word.starts_with(['@', '$']) {
+                        self.pos += 1;
+                        Token::Label(word)
+                    } else if word.starts_with(|c: char| c.is_uppercase()) {
+                        Token::Const(word)
+                    } else {
+                        Token::Ident(word)
+                    };
+                    self.push(token, start, self.pos);
+                }
+                _ => {
+                    let rest = self.rest();
+                    let punct = PUNCTUATION
+                        .iter()
+                        .chain(&OTHER_PUNCTUATION)
+                        .filter(|punct| rest.starts_with(**punct))
+                        .max_by_key(|punct| punct.len())
+                        .ok_or_else(|| self.error(format!("unexpected character `{c}`")))?;
+                    self.pos += punct.len();
+                    self.push(Token::Punct(punct), start, self.pos);
+                }
+            }
+        }
+        Ok(self.tokens)
+    }
+
+    fn rest(&self) -> &str {
+        &self.contents[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn push(&mut self, token: Token, start: usize, end: usize) {
+        self.tokens.push(Spanned {
+            token,
+            line: self.line,
+            start,
+            end,
+        });
+    }
+
+    fn error(&self, message: impl Into<String>) -> SyntaxError {
+        SyntaxError::new(self.line, message)
+    }
+
+    /// Identifier at the current position, with its `?` or `!` suffix
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        let prefix = self.rest().starts_with(['@', '$']) as usize;
+        let end = self.rest()[prefix..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map_or(self.contents.len(), |end| start + prefix + end);
+        self.pos = end;
+        if self.rest().starts_with(['?', '!']) && !self.rest()[1..].starts_with('=') {
+            self.pos += 1;
+        }
+        self.contents[start..self.pos].to_string()
+    }
+
+    fn block_comment(&mut self) -> Result<(), SyntaxError> {
+        let line = self.line;
+        loop {
+            let Some(end) = self.rest().find('\n') else {
+                return Err(SyntaxError::new(line, "unterminated `=begin` comment"));
+            };
+            let done = self.rest().starts_with("=end");
+            self.pos += end + 1;
+            self.line += 1;
+            if done {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Read a string up to `close`, the opening delimiter being consumed already; `None` when
+    /// it interpolates
+    fn quoted(
+        &mut self,
+        open: char,
+        close: char,
+        interpolates: bool,
+    ) -> Result<Option<String>, SyntaxError> {
+        let line = self.line;
+        let mut value = String::new();
+        let mut is_static = true;
+        let mut depth = 0;
+        let rest = &self.contents[self.pos..];
+        let mut chars = rest.char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let Some((_, escaped)) = chars.next() else {
+                        break;
+                    };
+                    match (interpolates, escaped) {
+                        (true, 'n') => value.push('\n'),
+                        (true, 't') => value.push('\t'),
+                        (true, '0') => value.push('\0'),
+                        (true, 's') => value.push(' '),
+                        (true, '\n') => self.line += 1,
+                        (true, escaped) => value.push(escaped),
+                        // Single quotes only escape the delimiter and the backslash
+                        (false, escaped) if escaped == close || escaped == '\\' => {
+                            value.push(escaped)
+                        }
+                        (false, escaped) => {
+                            value.push('\\');
+                            value.push(escaped);
+                        }
+                    }
+                    if escaped == '\n' && !interpolates {
+                        self.line += 1;
+                    }
+                }
+                '#' if interpolates && rest[offset + 1..].starts_with(['{', '@', '$']) => {
+                    is_static = false;
+                    value.push(c);
+                }
+                c if c == close && depth == 0 => {
+                    self.pos += offset + c.len_utf8();
+                    return Ok(is_static.then_some(value));
+                }
+                c => {
+                    // Bracket delimiters nest
+                    if open != close && c == open {
+                        depth += 1;
+                    } else if open != close && c == close {
+                        depth -= 1;
+                    }
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    value.push(c);
+                }
+            }
+        }
+        Err(SyntaxError::new(line, "unterminated string"))
+    }
+
+    /// `%q(...)`, `%Q(...)`, `%(...)`, `%w[...]` and `%i[...]` literals; `false` when `%` is the
+    /// modulo operator
+    fn percent_literal(&mut self) -> Result<bool, SyntaxError> {
+        let start = self.pos;
+        let mut chars = self.rest()[1..].chars();
+        let (kind, open) = match (chars.next(), chars.next()) {
+            (Some(kind @ ('q' | 'Q' | 'w' | 'W' | 'i' | 'I')), Some(open))
+                if !open.is_alphanumeric() && !open.is_whitespace() =>
+            {
+                (kind, open)
+            }
+            (Some(open @ ('(' | '[' | '{' | '<' | '|' | '!')), _) => ('Q', open),
+            _ => return Ok(false),
+        };
+        let close = match open {
+            '(' => ')',
+            '[' => ']',
+            '{' => '}',
+            '<' => '>',
+            open => open,
+        };
+        self.pos += if kind == 'Q' && !self.rest()[1..].starts_with('Q') {
+            2
+        } else {
+            3
+        };
+        let interpolates = matches!(kind, 'Q' | 'W' | 'I');
+        let value = self.quoted(open, close, interpolates)?;
+        let token = match (kind, value) {
+            ('w' | 'W' | 'i' | 'I', Some(words)) => {
+                Token::Words(words.split_whitespace().map(|w| w.to_string()).collect())
+            }
+            ('w' | 'W' | 'i' | 'I', None) => Token::Str(None),
+            (_, value) => Token::Str(value),
+        };
+        self.push(token, start, self.pos);
+        Ok(true)
+    }
+
+    /// `<<~ID`, `<<-ID` and `<<ID` heredocs, whose body starts on the next line; `false` when
+    /// `<<` is an operator
+    fn heredoc(&mut self) -> Result<bool, SyntaxError> {
+        let start = self.pos;
+        let rest = &self.rest()[2.min(self.rest().len())..];
+        if !self.rest().starts_with("<<") {
+            return Ok(false);
+        }
+        let (squiggly, dash, rest) = match rest.chars().next() {
+            Some('~') => (true, false, &rest[1..]),
+            Some('-') => (false, true, &rest[1..]),
+            _ => (false, false, rest),
+        };
+        let (terminator, interpolates, tag_len) = match rest.chars().next() {
+            Some(quote @ ('\'' | '"')) => {
+                let Some(end) = rest[1..].find(quote) else {
+                    return Ok(false);
+                };
+                (&rest[1..end + 1], quote == '"', end + 2)
+            }
+            Some(c)
+                if c.is_uppercase() || (c.is_alphabetic() && (squiggly || dash)) || c == '_' =>
+            {
+                let end = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (&rest[..end], true, end)
+            }
+            _ => return Ok(false),
+        };
+        let tag_end = start + 2 + (squiggly || dash) as usize + tag_len;
+        // The body follows the line the heredoc is opened on, or the body of the heredoc opened
+        // before it on that line
+        let (newline, body_start, previous_lines) = match self.heredoc_resume {
+            Some((newline, resume, previous_lines)) => (newline, resume, previous_lines),
+            None => {
+                let newline = self.contents[tag_end..]
+                    .find('\n')
+                    .map_or(self.contents.len(), |end| tag_end + end);
+                (newline, (newline + 1).min(self.contents.len()), 0)
+            }
+        };
+        let mut lines = Vec::new();
+        let mut offset = body_start;
+        let mut closed = false;
+        for line in self.contents[body_start..].split_inclusive('\n') {
+            offset += line.len();
+            let text = line.trim_end_matches(['\n', '\r']);
+            let candidate = if squiggly || dash {
+                text.trim_start()
+            } else {
+                text
+            };
+            if candidate == terminator {
+                closed = true;
+                break;
+            }
+            lines.push(text);
+        }
+        if !closed {
+            return Err(self.error(format!("unterminated heredoc `{terminator}`")));
+        }
+        let indent = match squiggly {
+            true => lines
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start().len())
+                .min()
+                .unwrap_or(0),
+            false => 0,
+        };
+        let mut value = String::new();
+        for line in &lines {
+            value.push_str(line.get(indent..).unwrap_or(""));
+            value.push('\n');
+        }
+        let is_static = !interpolates || !value.contains("#{");
+        let line_count = lines.len() + 1;
+        self.heredoc_resume = Some((newline, offset, previous_lines + line_count));
+        self.pos = tag_end;
+        self.push(Token::Str(is_static.then_some(value)), start, tag_end);
+        Ok(true)
+    }
+}
+
+/// A literal, the only expressions read statically
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Nil,
+    Bool(bool),
+    Str(String),
+    Symbol(String),
+    Number(String),
+    Array(Vec<Value>),
+    Hash(Vec<(Value, Value)>),
+}
+
+impl Value {
+    /// Strings and symbols
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(value) | Value::Symbol(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// A string, or an array of them
+    pub(crate) fn strings(&self) -> Option<Vec<String>> {
+        match self {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.as_str().map(|s| s.to_string()))
+                .collect(),
+            value => Some(vec![value.as_str()?.to_string()]),
+        }
+    }
+}
+
+/// An expression: a literal, or the source text of anything else
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Expr {
+    Static(Value),
+    Dynamic(String),
+}
+
+/// Methods whose result is known statically from the receiving string
+const STRING_METHODS: [&str; 4] = ["freeze", "strip", "chomp", "to_s"];
+
+pub(crate) struct Parser<'a> {
+    contents: &'a str,
+    tokens: &'a [Spanned],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(contents: &'a str, tokens: &'a [Spanned]) -> Self {
+        Parser {
+            contents,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    pub(crate) fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos).map(|spanned| &spanned.token)
+    }
+
+    pub(crate) fn peek_at(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens
+            .get(self.pos + offset)
+            .map(|spanned| &spanned.token)
+    }
+
+    pub(crate) fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(1, |spanned| spanned.line)
+    }
+
+    pub(crate) fn bump(&mut self) -> Option<&'a Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    /// Consume `punct` when it comes next
+    pub(crate) fn eat(&mut self, punct: &str) -> bool {
+        let matched = matches!(self.peek(), Some(Token::Punct(p)) if *p == punct);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    pub(crate) fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == name)
+    }
+
+    pub(crate) fn skip_newlines(&mut self) {
+        while matches!(self.peek(), Some(Token::Newline | Token::Punct(";"))) {
+            self.pos += 1;
+        }
+    }
+
+    /// Source text of the tokens from `start` to the current position
+    pub(crate) fn text(&self, start: usize) -> String {
+        let Some(first) = self.tokens.get(start) else {
+            return String::new();
+        };
+        let end = self.tokens[..self.pos.min(self.tokens.len())]
+            .iter()
+            .rev()
+            .find(|spanned| spanned.token != Token::Newline)
+            .map_or(first.end, |spanned| spanned.end);
+        self.contents[first.start..end.max(first.start)]
+            .trim()
+            .to_string()
+    }
+
+    /// Read an expression ending at the end of the statement or at one of `stops`
+    pub(crate) fn expression(&mut self, stops: &[&str]) -> Expr {
+        let start = self.pos;
+        if let Some(value) = self.literal() {
+            if self.at_end(stops) {
+                return Expr::Static(value);
+            }
+        }
+        self.pos = start;
+        self.skip(stops);
+        Expr::Dynamic(self.text(start))
+    }
+
+    /// Whether the expression ends here
+    pub(crate) fn at_end(&self, stops: &[&str]) -> bool {
+        match self.peek() {
+            None | Some(Token::Newline | Token::Punct(";" | ")" | "]" | "}")) => true,
+            Some(Token::Punct(punct)) => stops.contains(punct),
+            Some(Token::Ident(ident)) => ident == "end" || stops.contains(&ident.as_str()),
+            _ => false,
+        }
+    }
+
+    /// Skip to the end of the expression, over nested brackets and blocks
+    pub(crate) fn skip(&mut self, stops: &[&str]) {
+        let mut depth = 0usize;
+        let mut previous: Option<&Token> = None;
+        while let Some(token) = self.peek() {
+            if depth == 0 && self.at_end(stops) {
+                // Statements go on after a trailing operator or before a leading `.`
+                let continues = *token == Token::Newline
+                    && (matches!(previous, Some(Token::Punct(p)) if !matches!(*p, ")" | "]" | "}"))
+                        || matches!(self.next_significant(), Some(Token::Punct("." | "&."))));
+                if !continues {
+                    return;
+                }
+            }
+            match token {
+                Token::Punct("(" | "[" | "{") => depth += 1,
+                Token::Punct(")" | "]" | "}") => depth = depth.saturating_sub(1),
+                Token::Ident(ident) if ident == "do" => depth += 1,
+                Token::Ident(ident)
+                    if matches!(ident.as_str(), "if" | "unless" | "while" | "until")
+                        && matches!(previous, None | Some(Token::Punct(_) | Token::Newline)) =>
+                {
+                    depth += 1
+                }
+                Token::Ident(ident) if matches!(ident.as_str(), "begin" | "case" | "def") => {
+                    depth += 1
+                }
+                Token::Ident(ident) if ident == "end" => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            previous = Some(token);
+            self.pos += 1;
+        }
+    }
+
+    fn next_significant(&self) -> Option<&'a Token> {
+        self.tokens[self.pos.min(self.tokens.len())..]
+            .iter()
+            .map(|spanned| &spanned.token)
+            .find(|token| **token != Token::Newline)
+    }
+
+    /// Read a literal, with the string methods applied; `None` for anything else
+    fn literal(&mut self) -> Option<Value> {
+        let mut value = match self.bump()? {
+            Token::Str(value) => Value::Str(value.clone()?),
+            Token::Words(words) => {
+                Value::Array(words.iter().map(|word| Value::Str(word.clone())).collect())
+            }
+            Token::Symbol(symbol) => Value::Symbol(symbol.clone()),
+            Token::Number(number) => Value::Number(number.clone()),
+            Token::Ident(ident) => match ident.as_str() {
+                "nil" => Value::Nil,
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => return None,
+            },
+            Token::Punct("[") => {
+                let mut values = Vec::new();
+                loop {
+                    self.skip_newlines();
+                    if self.eat("]") {
+                        break;
+                    }
+                    values.push(self.element()?);
+                    self.skip_newlines();
+                    if !self.eat(",") {
+                        self.skip_newlines();
+                        if !self.eat("]") {
+                            return None;
+                        }
+                        break;
+                    }
+                }
+                Value::Array(values)
+            }
+            Token::Punct("{") => {
+                let mut entries = Vec::new();
+                loop {
+                    self.skip_newlines();
+                    if self.eat("}") {
+                        break;
+                    }
+                    let key = match self.peek()? {
+                        Token::Label(label) => {
+                            self.pos += 1;
+                            Value::Symbol(label.clone())
+                        }
+                        _ => {
+                            let key = self.element()?;
+                            if !self.eat("=>") {
+                                return None;
+                            }
+                            key
+                        }
+                    };
+                    self.skip_newlines();
+                    entries.push((key, self.element()?));
+                    self.skip_newlines();
+                    if !self.eat(",") {
+                        self.skip_newlines();
+                        if !self.eat("}") {
+                            return None;
+                        }
+                        break;
+                    }
+                }
+                Value::Hash(entries)
+            }
+            _ => return None,
+        };
+        while matches!(self.peek(), Some(Token::Punct("." | "&."))) {
+            let Some(Token::Ident(method)) = self.peek_at(1) else {
+                return None;
+            };
+            if !STRING_METHODS.contains(&method.as_str()) {
+                return None;
+            }
+            value = match (value, method.as_str()) {
+                (Value::Str(s), "strip") => Value::Str(s.trim().to_string()),
+                (Value::Str(s), "chomp") => {
+                    Value::Str(s.strip_suffix('\n').unwrap_or(&s).to_string())
+                }
+                (value, "freeze" | "to_s") => value,
+                _ => return None,
+            };
+            self.pos += 2;
+        }
+        Some(value)
+    }
+
+    /// An element of an array or hash literal
+    fn element(&mut self) -> Option<Value> {
+        let value = self.literal()?;
+        self.skip_newlines();
+        match self.peek() {
+            Some(Token::Punct("," | "]" | "}" | "=>")) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// RubyGems requirement from its comparators, such as `"~> 1.2", ">= 1.2.3"`; bare versions are
+/// exact, and `~>` is the pessimistic operator PEP 440 spells `~=`
+pub(crate) fn requirement(comparators: &[String]) -> Option<VersionReq> {
+    if comparators.is_empty() {
+        return None;
+    }
+    let raw = comparators.join(", ");
+    let comparators = comparators
+        .iter()
+        .map(|comparator| comparator.trim())
+        .map(|comparator| match comparator.strip_prefix("~>") {
+            Some(version) => Comparator::new(Op::Compatible, version.trim().to_string()),
+            None => match Op::split(comparator) {
+                Some((op, version)) => Comparator::new(op, version.trim().to_string()),
+                None => Comparator::new(Op::Exact, comparator.to_string()),
+            },
+        })
+        .collect();
+    Some(VersionReq::new(&raw, vec![comparators]))
+}
@@ -0,0 +1,223 @@
+//! Legacy setuptools metadata: the `[metadata]` and `[options]` sections of `setup.cfg`, and the
+//! keywords of a `setup(...)` call in `setup.py`. Both are read into the keywords `setup()`
+//! takes, then mapped the same way; keywords that are computed are listed in `dynamic` with the
+//! expression or directive computing them.
+use crate::dependency::{DependencyKind, DependencySpec};
+use crate::pyproject::parse_requirement;
+use crate::{FieldValue, Person, ProjectMetadata};
+use std::collections::HashMap;
+
+mod directive;
+mod setup_cfg;
+mod setup_py;
+
+pub(crate) use directive::Directives;
+pub(crate) use setup_cfg::from_ini;
+pub(crate) use setup_py::from_python;
+
+/// Value of a `setup()` keyword
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Keyword {
+    Str(String),
+    List(Vec<String>),
+    Dict(Vec<(String, Keyword)>),
+    /// Read from the `source` file, the way setuptools resolves `attr:` and `file:` directives
+    Resolved {
+        value: Box<Keyword>,
+        source: String,
+    },
+    /// Only known once `source` runs
+    Dynamic {
+        source: String,
+    },
+}
+
+impl Keyword {
+    /// Items of a list, or of a string separated by newlines or `separators`
+    fn items(&self, separators: &[char]) -> Option<Vec<String>> {
+        match self {
+            Keyword::List(items) => Some(items.clone()),
+            Keyword::Str(value) => Some(
+                value
+                    .split(|c| c == '\n' || separators.contains(&c))
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty() && !item.starts_with('#'))
+                    .map(|item| item.to_string())
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Keywords read so far, recording the fields that are resolved from files or computed
+struct Keywords<'a> {
+    keywords: &'a [(String, Keyword)],
+    dynamic: Vec<String>,
+    sources: HashMap<String, FieldValue>,
+}
+
+impl<'a> Keywords<'a> {
+    /// Static value of `keyword`, reporting `field` when it is resolved or dynamic
+    fn get(&mut self, keyword: &str, field: &str) -> Option<&'a Keyword> {
+        let value = self
+            .keywords
+            .iter()
+            .rev()
+            .find(|(name, _)| name == keyword)
+            .map(|(_, value)| value)?;
+        let (value, source) = match value {
+            Keyword::Resolved { value, source } => (
+                Some(&**value),
+                FieldValue::Resolved {
+                    source: source.clone(),
+                },
+            ),
+            Keyword::Dynamic { source } => (
+                None,
+                FieldValue::Dynamic {
+                    source: source.clone(),
+                },
+            ),
+            value => return Some(value),
+        };
+        if !self.dynamic.iter().any(|dynamic| dynamic == field) {
+            self.dynamic.push(field.to_string());
+        }
+        self.sources.entry(field.to_string()).or_insert(source);
+        value
+    }
+
+    fn string(&mut self, keyword: &str, field: &str) -> Option<String> {
+        match self.get(keyword, field)? {
+            Keyword::Str(value) => Some(value.trim().to_string()),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn from_keywords(keywords: &[(String, Keyword)]) -> ProjectMetadata {
+    let mut keywords = Keywords {
+        keywords,
+        dynamic: Vec::new(),
+        sources: HashMap::new(),
+    };
+
+    let authors = Person::from_lists(
+        keywords.string("author", "authors"),
+        keywords.string("author_email", "authors"),
+    );
+    let maintainers = Person::from_lists(
+        keywords.string("maintainer", "maintainers"),
+        keywords.string("maintainer_email", "maintainers"),
+    );
+
+    let mut urls = HashMap::new();
+    for (keyword, label) in [("url", "homepage"), ("download_url", "download")] {
+        if let Some(url) = keywords.string(keyword, "urls") {
+            urls.insert(label.to_string(), url);
+        }
+    }
+    match keywords.get("project_urls", "urls") {
+        Some(Keyword::Dict(entries)) => {
+            for (label, url) in entries {
+                if let Keyword::Str(url) = url {
+                    urls.insert(label.clone(), url.clone());
+                }
+            }
+        }
+        // `setup.cfg` writes them as a dangling list of `label = url` lines
+        Some(Keyword::Str(entries)) => urls.extend(pairs(entries)),
+        _ => {}
+    }
+
+    let mut dependencies = Vec::new();
+    for (keyword, kind) in [
+        ("install_requires", DependencyKind::Runtime),
+        ("tests_require", DependencyKind::Dev),
+        ("setup_requires", DependencyKind::Build),
+    ] {
+        let group = (kind != DependencyKind::Runtime).then_some(keyword);
+        let requirements = keywords.get(keyword, "dependencies");
+        for requirement in requirements
+            .and_then(|value| value.items(&[]))
+            .into_iter()
+            .flatten()
+        {
+            dependencies.push(parse_requirement(&requirement).kind(kind).group(group));
+        }
+    }
+    if let Some(Keyword::Dict(extras)) = keywords.get("extras_require", "dependencies") {
+        for (extra, requirements) in extras {
+            for requirement in requirements.items(&[]).into_iter().flatten() {
+                let dependency = parse_requirement(&requirement)
+                    .kind(DependencyKind::Optional)
+                    .group(Some(extra));
+                dependencies.push(dependency);
+            }
+        }
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    // Console and GUI entry points are the commands the package installs
+    let mut scripts = HashMap::new();
+    if let Some(Keyword::Dict(groups)) = keywords.get("entry_points", "scripts") {
+        for (group, entry_points) in groups {
+            if !matches!(group.as_str(), "console_scripts" | "gui_scripts") {
+                continue;
+            }
+            for entry_point in entry_points.items(&[]).into_iter().flatten() {
+                scripts.extend(pairs(&entry_point));
+            }
+        }
+    }
+
+    let name = keywords.string("name", "name");
+    let version = keywords.string("version", "version");
+    let description = keywords.string("description", "description");
+    let readme = keywords.string("long_description", "readme");
+    let license = keywords.string("license", "license");
+    let license_file = match keywords.get("license_files", "license_file") {
+        Some(files) => files
+            .items(&[','])
+            .and_then(|files| files.into_iter().next()),
+        None => keywords.string("license_file", "license_file"),
+    };
+    let keyword_list = keywords
+        .get("keywords", "keywords")
+        .and_then(|value| value.items(&[',', ' ']));
+    let classifiers = keywords
+        .get("classifiers", "classifiers")
+        .and_then(|value| value.items(&[]));
+    let engines = keywords
+        .string("python_requires", "engines")
+        .map(|spec| HashMap::from([("python".to_string(), spec)]));
+
+    ProjectMetadata {
+        name: name.unwrap_or_default(),
+        version: version.unwrap_or_default(),
+        description,
+        authors,
+        maintainers,
+        license,
+        license_file,
+        readme,
+        keywords: keyword_list,
+        classifiers,
+        urls: (!urls.is_empty()).then_some(urls),
+        engines,
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        scripts: (!scripts.is_empty()).then_some(scripts),
+        dynamic: (!keywords.dynamic.is_empty()).then_some(keywords.dynamic),
+        sources: (!keywords.sources.is_empty()).then_some(keywords.sources),
+        ..Default::default()
+    }
+}
+
+/// `key = value` lines
+fn pairs(lines: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    lines.lines().filter_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        Some((key.trim().to_string(), value.trim().to_string()))
+    })
+}
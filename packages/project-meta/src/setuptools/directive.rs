@@ -0,0 +1,51 @@
+//! The `attr:` and `file:` directives setuptools expands, written in `setup.cfg` options and in
+//! the `[tool.setuptools.dynamic]` table of `pyproject.toml`.
+use crate::pyproject::assignment;
+use std::fs;
+use std::path::Path;
+
+/// Reads the files directives refer to, relative to the project directory
+pub(crate) struct Directives<'a> {
+    dir: Option<&'a Path>,
+    /// Directories packages live in, the one `package_dir` sets for the root package first
+    package_roots: Vec<String>,
+}
+
+impl<'a> Directives<'a> {
+    /// Directives of the project in `dir`, whose root package lives in `root` when it is set
+    pub(crate) fn new(dir: Option<&'a Path>, root: Option<&str>) -> Self {
+        let mut package_roots = vec![String::new(), "src".to_string()];
+        if let Some(root) = root {
+            package_roots.insert(0, root.to_string());
+        }
+        Directives { dir, package_roots }
+    }
+
+    /// Value of `attr: package.module.NAME` and the module file assigning it. The attribute may
+    /// be re-exported or computed instead, which only importing it resolves.
+    pub(crate) fn attr(&self, attr: &str) -> Option<(String, String)> {
+        let (module, name) = attr.rsplit_once('.')?;
+        let module = module.replace('.', "/");
+        let candidates = [format!("{module}/__init__.py"), format!("{module}.py")];
+        self.package_roots.iter().find_map(|root| {
+            candidates.iter().find_map(|candidate| {
+                let path = Path::new(root).join(candidate);
+                let value = assignment(&self.read(&path)?, &[name])?;
+                Some((value, path.to_string_lossy().into_owned()))
+            })
+        })
+    }
+
+    /// Contents of `file: a.txt, b.txt` joined, and the files they were read from
+    pub(crate) fn files(&self, files: &[&str]) -> Option<(String, String)> {
+        let contents = files
+            .iter()
+            .map(|file| self.read(Path::new(file)))
+            .collect::<Option<Vec<_>>>()?;
+        Some((contents.join("\n").trim().to_string(), files.join(", ")))
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        fs::read_to_string(self.dir?.join(path)).ok()
+    }
+}
@@ -0,0 +1,99 @@
+//! `setup.cfg` reader, resolving the `attr:` and `file:` directives setuptools expands.
+use super::{from_keywords, Directives, Keyword};
+use crate::ini::Section;
+use crate::ProjectMetadata;
+use std::path::Path;
+
+/// Sections whose options are keywords taking a table, keyed by section name
+const TABLE_SECTIONS: [(&str, &str); 2] = [
+    ("options.extras_require", "extras_require"),
+    ("options.entry_points", "entry_points"),
+];
+
+pub(crate) fn from_ini(sections: &[Section], dir: Option<&Path>) -> ProjectMetadata {
+    let section = |name: &str| sections.iter().find(|section| section.name == name);
+    let package_root = package_root(section("options"));
+    let directives = Directives::new(dir, package_root.as_deref());
+
+    let mut keywords = Vec::new();
+    for section in ["metadata", "options"].into_iter().filter_map(section) {
+        for (key, value) in &section.options {
+            // `home_page` and `summary` are the PKG-INFO spellings setuptools also accepts
+            let key = match key.as_str() {
+                "home_page" => "url",
+                "summary" => "description",
+                key => key,
+            };
+            let value = match key {
+                // The long description is the readme, its path is what other formats record
+                "long_description" => match value.strip_prefix("file:") {
+                    Some(files) => {
+                        Keyword::Str(split_files(files).next().unwrap_or("").to_string())
+                    }
+                    None => Keyword::Str(value.clone()),
+                },
+                _ => directive(value, &directives),
+            };
+            keywords.push((key.to_string(), value));
+        }
+    }
+    for (name, keyword) in TABLE_SECTIONS {
+        if let Some(section) = section(name) {
+            let table = section
+                .options
+                .iter()
+                .map(|(key, value)| (key.clone(), directive(value, &directives)))
+                .collect();
+            keywords.push((keyword.to_string(), Keyword::Dict(table)));
+        }
+    }
+    from_keywords(&keywords)
+}
+
+/// Expand an `attr: package.module.NAME` or `file: a.txt, b.txt` directive
+fn directive(value: &str, directives: &Directives) -> Keyword {
+    if let Some(attr) = value.strip_prefix("attr:") {
+        let attr = attr.trim();
+        return match directives.attr(attr) {
+            Some((value, source)) => Keyword::Resolved {
+                value: Box::new(Keyword::Str(value)),
+                source,
+            },
+            None => Keyword::Dynamic {
+                source: format!("attr:{attr}"),
+            },
+        };
+    }
+    if let Some(files) = value.strip_prefix("file:") {
+        let files: Vec<&str> = split_files(files).collect();
+        return match directives.files(&files) {
+            Some((value, source)) => Keyword::Resolved {
+                value: Box::new(Keyword::Str(value)),
+                source,
+            },
+            None => Keyword::Dynamic {
+                source: format!("file:{}", files.join(", ")),
+            },
+        };
+    }
+    Keyword::Str(value.to_string())
+}
+
+fn split_files(files: &str) -> impl Iterator<Item = &str> {
+    files
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|file| !file.is_empty())
+}
+
+/// Directory of the root package, set by the `package_dir` entry with an empty name, `= src`
+fn package_root(options: Option<&Section>) -> Option<String> {
+    options
+        .and_then(|options| options.get("package_dir"))
+        .into_iter()
+        .flat_map(|package_dir| package_dir.lines())
+        .find_map(|line| {
+            let (package, root) = line.split_once('=')?;
+            package.trim().is_empty().then(|| root.trim().to_string())
+        })
+}
@@ -0,0 +1,316 @@
+//! `setup.py` reader. The script is not run: the keywords of its `setup(...)` call are read
+//! when they are literals, and reported as dynamic with their source text otherwise.
+use super::{from_keywords, Keyword};
+use crate::syntax::SyntaxError;
+use crate::ProjectMetadata;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    /// `None` for f-strings interpolating values
+    Str(Option<String>),
+    Number,
+    Op(&'static str),
+}
+
+struct Spanned {
+    token: Token,
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Operators of more than one character, longest first
+const OPERATORS: [&str; 9] = ["**=", "**", "==", "!=", "<=", ">=", "->", ":=", "//"];
+const SINGLE_OPERATORS: &str = "()[]{},:.;=+-*/%<>|&^~@!";
+
+pub(crate) fn from_python(contents: &str) -> Result<ProjectMetadata, SyntaxError> {
+    let tokens = lex(contents)?;
+    let mut parser = Parser {
+        contents,
+        tokens: &tokens,
+        pos: setup_call(&tokens)
+            .ok_or_else(|| SyntaxError::new(1, "expected a `setup(...)` call"))?,
+    };
+
+    let mut keywords = Vec::new();
+    loop {
+        match parser.peek() {
+            None => {
+                return Err(SyntaxError::new(
+                    tokens.last().map_or(1, |token| token.line),
+                    "unterminated `setup(` call",
+                ))
+            }
+            Some(Token::Op(")")) => break,
+            Some(Token::Name(name)) if parser.peek_at(1) == Some(&Token::Op("=")) => {
+                parser.pos += 2;
+                let start = parser.pos;
+                let value = match parser.literal() {
+                    Some(value) if parser.at_end() => value,
+                    _ => {
+                        parser.pos = start;
+                        parser.skip();
+                        Keyword::Dynamic {
+                            source: parser.text(start),
+                        }
+                    }
+                };
+                keywords.push((name.clone(), value));
+            }
+            // Positional arguments and `**kwargs` spreads
+            _ => parser.skip(),
+        }
+        parser.eat(",");
+    }
+    Ok(from_keywords(&keywords))
+}
+
+/// Position of the first argument of the `setup(` or `setuptools.setup(` call
+fn setup_call(tokens: &[Spanned]) -> Option<usize> {
+    tokens.windows(2).enumerate().find_map(|(index, window)| {
+        let is_call = window[0].token == Token::Name("setup".to_string())
+            && window[1].token == Token::Op("(");
+        // Not the definition of a function named `setup`
+        let defined = index > 0 && tokens[index - 1].token == Token::Name("def".to_string());
+        (is_call && !defined).then_some(index + 2)
+    })
+}
+
+struct Parser<'a> {
+    contents: &'a str,
+    tokens: &'a [Spanned],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos).map(|spanned| &spanned.token)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&'a Token> {
+        self.tokens
+            .get(self.pos + offset)
+            .map(|spanned| &spanned.token)
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        let matched = matches!(self.peek(), Some(Token::Op(o)) if *o == op);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// Whether the argument ends here
+    fn at_end(&self) -> bool {
+        matches!(self.peek(), None | Some(Token::Op("," | ")")))
+    }
+
+    /// Skip to the end of the argument, over nested brackets
+    fn skip(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            if depth == 0 && self.at_end() {
+                return;
+            }
+            match token {
+                Token::Op("(" | "[" | "{") => depth += 1,
+                Token::Op(")" | "]" | "}") => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Source text of the tokens from `start` to the current position
+    fn text(&self, start: usize) -> String {
+        match (
+            self.tokens.get(start),
+            self.tokens.get(self.pos.wrapping_sub(1)),
+        ) {
+            (Some(first), Some(last)) if self.pos > start => {
+                self.contents[first.start..last.end].to_string()
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Read a literal: strings, adjacent strings being concatenated, and lists, tuples and
+    /// dictionaries of them
+    fn literal(&mut self) -> Option<Keyword> {
+        match self.peek()? {
+            Token::Str(_) => {
+                let mut value = String::new();
+                while let Some(Token::Str(part)) = self.peek() {
+                    value.push_str(part.as_deref()?);
+                    self.pos += 1;
+                }
+                Some(Keyword::Str(value))
+            }
+            Token::Op(open @ ("[" | "(")) => {
+                let close = if *open == "[" { "]" } else { ")" };
+                self.pos += 1;
+                let mut items = Vec::new();
+                while !self.eat(close) {
+                    match self.literal()? {
+                        Keyword::Str(item) => items.push(item),
+                        _ => return None,
+                    }
+                    if !self.eat(",") && !matches!(self.peek(), Some(Token::Op(o)) if *o == close) {
+                        return None;
+                    }
+                }
+                Some(Keyword::List(items))
+            }
+            Token::Op("{") => {
+                self.pos += 1;
+                let mut entries = Vec::new();
+                while !self.eat("}") {
+                    let Keyword::Str(key) = self.literal()? else {
+                        return None;
+                    };
+                    if !self.eat(":") {
+                        return None;
+                    }
+                    entries.push((key, self.literal()?));
+                    if !self.eat(",") && self.peek() != Some(&Token::Op("}")) {
+                        return None;
+                    }
+                }
+                Some(Keyword::Dict(entries))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn lex(contents: &str) -> Result<Vec<Spanned>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+    while let Some(c) = contents[pos..].chars().next() {
+        let start = pos;
+        let rest = &contents[pos..];
+        if c == '\n' {
+            line += 1;
+            pos += 1;
+            continue;
+        }
+        if c.is_whitespace() || c == '\\' {
+            pos += c.len_utf8();
+            continue;
+        }
+        if c == '#' {
+            pos += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        let token_line = line;
+        let token = if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            let quote = rest[len..].chars().next();
+            let is_prefix = len <= 2
+                && word
+                    .chars()
+                    .all(|c| matches!(c.to_ascii_lowercase(), 'r' | 'b' | 'u' | 'f'));
+            if is_prefix && matches!(quote, Some('"' | '\'')) {
+                pos += len;
+                let prefix = word.to_ascii_lowercase();
+                string(contents, &mut pos, &mut line, &prefix)?
+            } else {
+                pos += len;
+                Token::Name(word.to_string())
+            }
+        } else if c.is_ascii_digit()
+            || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+        {
+            pos += rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '_')))
+                .unwrap_or(rest.len());
+            Token::Number
+        } else if c == '"' || c == '\'' {
+            string(contents, &mut pos, &mut line, "")?
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            pos += op.len();
+            Token::Op(op)
+        } else if let Some(index) = SINGLE_OPERATORS.find(c) {
+            pos += 1;
+            Token::Op(&SINGLE_OPERATORS[index..index + 1])
+        } else {
+            return Err(SyntaxError::new(
+                line,
+                format!("unexpected character `{c}`"),
+            ));
+        };
+        tokens.push(Spanned {
+            token,
+            line: token_line,
+            start,
+            end: pos,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Read a string literal starting at `pos`, after its prefix
+fn string(
+    contents: &str,
+    pos: &mut usize,
+    line: &mut usize,
+    prefix: &str,
+) -> Result<Token, SyntaxError> {
+    let start_line = *line;
+    let rest = &contents[*pos..];
+    let quote = if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+        &rest[..3]
+    } else {
+        &rest[..1]
+    };
+    let raw = prefix.contains('r');
+    let mut value = String::new();
+    let mut chars = rest[quote.len()..].char_indices();
+    while let Some((offset, c)) = chars.next() {
+        let offset = quote.len() + offset;
+        match c {
+            _ if rest[offset..].starts_with(quote) => {
+                *pos += offset + quote.len();
+                let interpolates = prefix.contains('f') && value.contains('{');
+                return Ok(Token::Str((!interpolates).then_some(value)));
+            }
+            '\n' if quote.len() == 1 => break,
+            '\\' => {
+                let Some((_, escaped)) = chars.next() else {
+                    break;
+                };
+                if escaped == '\n' {
+                    *line += 1;
+                }
+                match (raw, escaped) {
+                    (true, escaped) => {
+                        value.push('\\');
+                        value.push(escaped);
+                    }
+                    // A backslash before a newline continues the line
+                    (false, '\n') => {}
+                    (false, 'n') => value.push('\n'),
+                    (false, 't') => value.push('\t'),
+                    (false, escaped @ ('\\' | '\'' | '"')) => value.push(escaped),
+                    (false, escaped) => {
+                        value.push('\\');
+                        value.push(escaped);
+                    }
+                }
+            }
+            c => {
+                if c == '\n' {
+                    *line += 1;
+                }
+                value.push(c);
+            }
+        }
+    }
+    Err(SyntaxError::new(start_line, "unterminated string"))
+}
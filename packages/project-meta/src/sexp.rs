@@ -0,0 +1,183 @@
+//! S-expressions as dune writes them: atoms, quoted strings and parenthesized lists, with `;`
+//! line comments, `#| ... |#` block comments and `#;` commenting out the next expression.
+use crate::syntax::SyntaxError;
+
+/// An expression, with the line it starts on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Sexp {
+    pub(crate) value: Value,
+    pub(crate) line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    /// Atom or quoted string
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+impl Sexp {
+    pub(crate) fn as_atom(&self) -> Option<&str> {
+        match &self.value {
+            Value::Atom(atom) => Some(atom),
+            Value::List(_) => None,
+        }
+    }
+
+    /// Head atom and arguments of a `(head args...)` list
+    pub(crate) fn as_form(&self) -> Option<(&str, &[Sexp])> {
+        match &self.value {
+            Value::List(items) => {
+                let (head, args) = items.split_first()?;
+                Some((head.as_atom()?, args))
+            }
+            Value::Atom(_) => None,
+        }
+    }
+}
+
+/// Expressions of a file
+pub(crate) fn parse(contents: &str) -> Result<Vec<Sexp>, SyntaxError> {
+    let mut parser = Parser {
+        chars: contents.chars().collect(),
+        index: 0,
+        line: 1,
+    };
+    let mut expressions = Vec::new();
+    while let Some(sexp) = parser.next()? {
+        expressions.push(sexp);
+    }
+    Ok(expressions)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, line: usize, message: &str) -> SyntaxError {
+        SyntaxError::new(line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), SyntaxError> {
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(c), _) if c.is_whitespace() => {
+                    self.bump();
+                }
+                (Some(';'), _) => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                (Some('#'), Some('|')) => {
+                    let line = self.line;
+                    self.index += 2;
+                    while !(self.peek() == Some('|') && self.peek_at(1) == Some('#')) {
+                        if self.bump().is_none() {
+                            return Err(self.error(line, "unclosed `#|` comment"));
+                        }
+                    }
+                    self.index += 2;
+                }
+                (Some('#'), Some(';')) => {
+                    let line = self.line;
+                    self.index += 2;
+                    if self.next()?.is_none() {
+                        return Err(self.error(line, "expected an expression after `#;`"));
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Next expression, or `None` at the end of the file
+    fn next(&mut self) -> Result<Option<Sexp>, SyntaxError> {
+        self.skip_trivia()?;
+        let line = self.line;
+        let value = match self.peek() {
+            None => return Ok(None),
+            Some(')') => return Err(self.error(line, "unexpected `)`")),
+            Some('(') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_trivia()?;
+                    match self.peek() {
+                        Some(')') => {
+                            self.bump();
+                            break;
+                        }
+                        None => return Err(self.error(line, "unclosed `(`")),
+                        Some(_) => items.extend(self.next()?),
+                    }
+                }
+                Value::List(items)
+            }
+            Some('"') => Value::Atom(self.string()?),
+            Some(_) => {
+                let start = self.index;
+                while self
+                    .peek()
+                    .is_some_and(|c| !c.is_whitespace() && !"()\";".contains(c))
+                {
+                    self.bump();
+                }
+                Value::Atom(self.chars[start..self.index].iter().collect())
+            }
+        };
+        Ok(Some(Sexp { value, line }))
+    }
+
+    fn string(&mut self) -> Result<String, SyntaxError> {
+        let line = self.line;
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some('b') => value.push('\u{8}'),
+                    // A backslash at the end of a line continues the string on the next one,
+                    // without its indentation
+                    Some('\n') => {
+                        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                            self.bump();
+                        }
+                    }
+                    Some(c @ ('\\' | '"' | '%' | ' ')) => value.push(c),
+                    Some(c) => {
+                        return Err(self.error(self.line, &format!("invalid escape `\\{c}`")));
+                    }
+                    None => return Err(self.error(line, "unterminated string")),
+                },
+                Some(c) => value.push(c),
+                None => return Err(self.error(line, "unterminated string")),
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Manifest formats as [`MetadataSource`]s, and the [`Registry`] detecting and loading them.
+//!
+//! The built-in formats are registered by [`Registry::new`]; downstream crates register their
+//! own formats next to them rather than adding [`ConfigFile`](crate::ConfigFile) variants.
+use crate::{MetadataError, ProjectMetadata};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub(crate) mod builtin;
+
+/// A manifest format metadata is read from
+pub trait MetadataSource: Send + Sync {
+    /// Name of the format, used in error messages
+    fn name(&self) -> &str;
+
+    /// Names of the files holding the format, where `*` matches any characters: `Cargo.toml`,
+    /// `*.cabal`
+    fn patterns(&self) -> &[&str];
+
+    /// Whether the file at `path` holds the format, by default whether its name matches one of
+    /// the patterns
+    fn detect(&self, path: &Path) -> bool {
+        let name = path.file_name().and_then(|name| name.to_str());
+        name.is_some_and(|name| self.patterns().iter().any(|pattern| matches(pattern, name)))
+    }
+
+    /// Parse the contents of a manifest
+    fn parse(&self, contents: &str) -> Result<ProjectMetadata, MetadataError>;
+
+    /// Parse the contents of the manifest at `path`, for formats reading the files next to it
+    /// or naming the project after the file
+    fn parse_file(&self, contents: &str, path: &Path) -> Result<ProjectMetadata, MetadataError> {
+        let _ = path;
+        self.parse(contents)
+    }
+
+    /// Write `metadata` in the format, for the formats supporting it
+    fn write(&self, metadata: &ProjectMetadata) -> Result<String, MetadataError> {
+        let _ = metadata;
+        Err(MetadataError::WriteUnsupported(self.name().to_string()))
+    }
+}
+
+/// Formats metadata can be loaded from, in the order a directory is searched for them
+pub struct Registry {
+    sources: Vec<Box<dyn MetadataSource>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registry {
+    /// Registry of the built-in formats
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        for source in builtin::SOURCES {
+            registry.register(*source);
+        }
+        registry
+    }
+
+    /// Registry without any format
+    pub fn empty() -> Self {
+        Registry {
+            sources: Vec::new(),
+        }
+    }
+
+    /// Add a format, searched for after the ones already registered; it takes precedence over
+    /// them for the files they both detect
+    pub fn register(&mut self, source: impl MetadataSource + 'static) -> &mut Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &dyn MetadataSource> {
+        self.sources.iter().map(|source| source.as_ref())
+    }
+
+    /// Format of the file at `path`, the last registered one detecting it
+    pub fn find(&self, path: &Path) -> Option<&dyn MetadataSource> {
+        self.position(path)
+            .map(|index| self.sources[index].as_ref())
+    }
+
+    fn position(&self, path: &Path) -> Option<usize> {
+        self.sources.iter().rposition(|source| source.detect(path))
+    }
+
+    /// Load the file at `path` with its format
+    pub fn load(&self, path: &Path) -> Result<ProjectMetadata, MetadataError> {
+        let source = self
+            .find(path)
+            .ok_or_else(|| MetadataError::UnknownFormat(path.display().to_string()))?;
+        load(source, path)
+    }
+
+    /// Manifests of `dir` with their format, in the order the formats were registered. A format
+    /// gives the first file in name order for each of its patterns.
+    pub fn detect(&self, dir: &Path) -> Vec<(PathBuf, &dyn MetadataSource)> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+
+        let mut detected = Vec::new();
+        for (index, source) in self.sources.iter().enumerate() {
+            for pattern in source.patterns() {
+                let file = files.iter().find(|file| {
+                    let name = file.file_name().and_then(|name| name.to_str());
+                    name.is_some_and(|name| matches(pattern, name))
+                        && self.position(file) == Some(index)
+                });
+                if let Some(file) = file {
+                    detected.push((file.clone(), source.as_ref()));
+                }
+            }
+        }
+        detected
+    }
+
+    /// Load the manifests of `dir`
+    pub fn load_detected(&self, dir: &Path) -> Result<Vec<ProjectMetadata>, MetadataError> {
+        load_all(&self.detect(dir))
+    }
+}
+
+/// Load the manifests [`Registry::detect`] found, in order
+pub(crate) fn load_all(
+    detected: &[(PathBuf, &dyn MetadataSource)],
+) -> Result<Vec<ProjectMetadata>, MetadataError> {
+    let metadata_list = detected
+        .iter()
+        .map(|(path, source)| load(*source, path))
+        .collect::<Result<Vec<_>, _>>()?;
+    if metadata_list.is_empty() {
+        return Err(MetadataError::NoFilesFound);
+    }
+    Ok(metadata_list)
+}
+
+/// Read the file at `path` and parse it with `source`
+pub(crate) fn load(
+    source: &dyn MetadataSource,
+    path: &Path,
+) -> Result<ProjectMetadata, MetadataError> {
+    let file_path = path.display().to_string();
+    let mut file = File::open(path).map_err(|_| MetadataError::FileNotFound(file_path.clone()))?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .map_err(|_| MetadataError::ReadError(file_path))?;
+    source.parse_file(&contents, path)
+}
+
+/// Whether `name` matches `pattern`, `*` matching any characters
+fn matches(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            // `*` takes any number of characters, the rest of the pattern matching what is left
+            (0..=name.len())
+                .filter(|start| name.is_char_boundary(*start))
+                .any(|start| matches(rest, &name[start..]))
+        }
+    }
+}
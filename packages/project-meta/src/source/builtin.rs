@@ -0,0 +1,321 @@
+//! The formats this crate reads, in the order a directory is searched for them.
+//!
+//! A format parsed from contents alone has no path: its errors are not located in a file, and
+//! the files a manifest may refer to, such as a Cargo workspace root, are not looked up.
+use super::MetadataSource;
+use crate::util::located;
+use crate::{
+    bower, cabal, cargo, citation, codemeta, composer, conda, dcf, deno, dune, gemfile, gemspec,
+    go_mod, gradle, helm, ini, jsonc, julia, msbuild, opam, package_json, pixi, pom, pubspec,
+    pyproject, r_description, setuptools, sexp, vcpkg, yaml, zig, zon,
+};
+use crate::{MetadataError, ProjectMetadata};
+use std::fmt;
+use std::path::Path;
+
+type Parse = fn(&str, Option<&Path>) -> Result<ProjectMetadata, MetadataError>;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Builtin {
+    name: &'static str,
+    patterns: &'static [&'static str],
+    parse: Parse,
+}
+
+impl MetadataSource for Builtin {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn patterns(&self) -> &[&str] {
+        self.patterns
+    }
+
+    fn parse(&self, contents: &str) -> Result<ProjectMetadata, MetadataError> {
+        (self.parse)(contents, None)
+    }
+
+    fn parse_file(&self, contents: &str, path: &Path) -> Result<ProjectMetadata, MetadataError> {
+        (self.parse)(contents, Some(path))
+    }
+}
+
+pub(crate) static SOURCES: [&Builtin; 28] = [
+    &PACKAGE_JSON,
+    &CARGO_TOML,
+    &DENO_JSON,
+    &JSR_JSON,
+    &BOWER_JSON,
+    &PYPROJECT_TOML,
+    &SETUP_CFG,
+    &SETUP_PY,
+    &PIXI_TOML,
+    &ENVIRONMENT_YML,
+    &COMPOSER_JSON,
+    &GO_MOD,
+    &POM_XML,
+    &BUILD_GRADLE,
+    &PUBSPEC_YAML,
+    &CHART_YAML,
+    &VCPKG_JSON,
+    &CSPROJ,
+    &GEMSPEC,
+    &GEMFILE,
+    &CITATION_CFF,
+    &CODEMETA_JSON,
+    &R_DESCRIPTION,
+    &JULIA_PROJECT_TOML,
+    &CABAL,
+    &BUILD_ZIG_ZON,
+    &DUNE_PROJECT,
+    &OPAM,
+];
+
+pub(crate) static PACKAGE_JSON: Builtin = Builtin {
+    name: "package.json",
+    patterns: &["package.json"],
+    parse: |contents, path| {
+        let json = jsonc::parse(contents).map_err(|err| json_error(path, err))?;
+        package_json::from_json(&json)
+    },
+};
+
+pub(crate) static CARGO_TOML: Builtin = Builtin {
+    name: "Cargo.toml",
+    patterns: &["Cargo.toml"],
+    parse: |contents, path| cargo::from_toml(&parse_toml(contents, path)?, path),
+};
+
+pub(crate) static DENO_JSON: Builtin = Builtin {
+    name: "deno.json",
+    patterns: &["deno.json", "deno.jsonc"],
+    parse: |contents, path| {
+        let json = jsonc::parse(contents).map_err(|err| json_error(path, err))?;
+        deno::from_json(&json)
+    },
+};
+
+// jsr.json holds the subset of deno.json JSR publishes with, so the deno.json reader covers it
+pub(crate) static JSR_JSON: Builtin = Builtin {
+    name: "jsr.json",
+    patterns: &["jsr.json"],
+    parse: DENO_JSON.parse,
+};
+
+pub(crate) static BOWER_JSON: Builtin = Builtin {
+    name: "bower.json",
+    patterns: &["bower.json"],
+    parse: |contents, path| {
+        let json = jsonc::parse(contents).map_err(|err| json_error(path, err))?;
+        bower::from_json(&json)
+    },
+};
+
+pub(crate) static VCPKG_JSON: Builtin = Builtin {
+    name: "vcpkg.json",
+    patterns: &["vcpkg.json"],
+    parse: |contents, path| {
+        let json = jsonc::parse(contents).map_err(|err| json_error(path, err))?;
+        vcpkg::from_json(&json)
+    },
+};
+
+pub(crate) static COMPOSER_JSON: Builtin = Builtin {
+    name: "composer.json",
+    patterns: &["composer.json"],
+    parse: |contents, path| {
+        let json = jsonc::parse(contents).map_err(|err| json_error(path, err))?;
+        composer::from_json(&json)
+    },
+};
+
+pub(crate) static GO_MOD: Builtin = Builtin {
+    name: "go.mod",
+    patterns: &["go.mod"],
+    parse: |contents, path| {
+        go_mod::parse(contents)
+            .map_err(|err| MetadataError::ParseError("go.mod".to_string(), located(path, err)))
+    },
+};
+
+pub(crate) static POM_XML: Builtin = Builtin {
+    name: "pom.xml",
+    patterns: &["pom.xml"],
+    parse: pom::from_xml,
+};
+
+pub(crate) static BUILD_GRADLE: Builtin = Builtin {
+    name: "build.gradle",
+    patterns: &["build.gradle", "build.gradle.kts"],
+    parse: gradle::from_script,
+};
+
+pub(crate) static PUBSPEC_YAML: Builtin = Builtin {
+    name: "pubspec.yaml",
+    patterns: &["pubspec.yaml"],
+    parse: |contents, path| pubspec::from_yaml(&parse_yaml(contents, path)?, contents),
+};
+
+pub(crate) static CHART_YAML: Builtin = Builtin {
+    name: "Chart.yaml",
+    patterns: &["Chart.yaml"],
+    parse: |contents, path| helm::from_yaml(&parse_yaml(contents, path)?, contents),
+};
+
+pub(crate) static CITATION_CFF: Builtin = Builtin {
+    name: "CITATION.cff",
+    patterns: &["CITATION.cff"],
+    parse: |contents, path| citation::from_yaml(&parse_yaml(contents, path)?, contents),
+};
+
+pub(crate) static CODEMETA_JSON: Builtin = Builtin {
+    name: "codemeta.json",
+    patterns: &["codemeta.json"],
+    parse: |contents, path| {
+        let json = jsonc::parse(contents).map_err(|err| json_error(path, err))?;
+        codemeta::from_json(&json)
+    },
+};
+
+pub(crate) static CSPROJ: Builtin = Builtin {
+    name: "csproj",
+    patterns: &["*.csproj", "*.fsproj", "*.vbproj"],
+    parse: msbuild::from_xml,
+};
+
+pub(crate) static GEMSPEC: Builtin = Builtin {
+    name: "gemspec",
+    patterns: &["*.gemspec"],
+    parse: gemspec::from_ruby,
+};
+
+pub(crate) static GEMFILE: Builtin = Builtin {
+    name: "Gemfile",
+    patterns: &["Gemfile"],
+    parse: gemfile::from_ruby,
+};
+
+pub(crate) static PYPROJECT_TOML: Builtin = Builtin {
+    name: "pyproject.toml",
+    patterns: &["pyproject.toml"],
+    parse: |contents, path| {
+        let toml = parse_toml(contents, path)?;
+        Ok(pyproject::from_toml(&toml, path.map(parent)))
+    },
+};
+
+pub(crate) static SETUP_CFG: Builtin = Builtin {
+    name: "setup.cfg",
+    patterns: &["setup.cfg"],
+    parse: |contents, path| {
+        let sections = ini::parse(contents)
+            .map_err(|err| MetadataError::ParseError("INI".to_string(), located(path, err)))?;
+        Ok(setuptools::from_ini(&sections, path.map(parent)))
+    },
+};
+
+pub(crate) static SETUP_PY: Builtin = Builtin {
+    name: "setup.py",
+    patterns: &["setup.py"],
+    parse: |contents, path| {
+        setuptools::from_python(contents)
+            .map_err(|err| MetadataError::ParseError("setup.py".to_string(), located(path, err)))
+    },
+};
+
+pub(crate) static R_DESCRIPTION: Builtin = Builtin {
+    name: "DESCRIPTION",
+    patterns: &["DESCRIPTION"],
+    parse: |contents, path| {
+        let fields = dcf::parse(contents).map_err(|err| {
+            MetadataError::ParseError("Debian control file".to_string(), located(path, err))
+        })?;
+        Ok(r_description::from_dcf(&fields))
+    },
+};
+
+pub(crate) static JULIA_PROJECT_TOML: Builtin = Builtin {
+    name: "Project.toml",
+    patterns: &["Project.toml"],
+    parse: |contents, path| Ok(julia::from_toml(&parse_toml(contents, path)?)),
+};
+
+pub(crate) static CABAL: Builtin = Builtin {
+    name: "cabal",
+    patterns: &["*.cabal"],
+    parse: |contents, path| {
+        cabal::parse(contents)
+            .map_err(|err| MetadataError::ParseError("Cabal file".to_string(), located(path, err)))
+    },
+};
+
+pub(crate) static ENVIRONMENT_YML: Builtin = Builtin {
+    name: "environment.yml",
+    patterns: &["environment.yml"],
+    parse: |contents, path| conda::from_yaml(&parse_yaml(contents, path)?),
+};
+
+pub(crate) static PIXI_TOML: Builtin = Builtin {
+    name: "pixi.toml",
+    patterns: &["pixi.toml"],
+    parse: |contents, path| Ok(pixi::from_toml(&parse_toml(contents, path)?)),
+};
+
+pub(crate) static BUILD_ZIG_ZON: Builtin = Builtin {
+    name: "build.zig.zon",
+    patterns: &["build.zig.zon"],
+    parse: |contents, path| {
+        zon::parse(contents)
+            .and_then(|zon| zig::from_zon(&zon))
+            .map_err(|err| MetadataError::ParseError("ZON".to_string(), located(path, err)))
+    },
+};
+
+pub(crate) static DUNE_PROJECT: Builtin = Builtin {
+    name: "dune-project",
+    patterns: &["dune-project"],
+    parse: |contents, path| {
+        sexp::parse(contents)
+            .and_then(|sexps| dune::from_sexps(&sexps))
+            .map_err(|err| {
+                MetadataError::ParseError("dune-project".to_string(), located(path, err))
+            })
+    },
+};
+
+pub(crate) static OPAM: Builtin = Builtin {
+    name: "opam",
+    patterns: &["*.opam", "opam"],
+    parse: |contents, path| {
+        // `<name>.opam` names the package
+        let file_name = path
+            .and_then(|path| path.file_name())
+            .and_then(|name| name.to_str());
+        opam::parse(contents, file_name)
+            .map_err(|err| MetadataError::ParseError("opam file".to_string(), located(path, err)))
+    },
+};
+
+fn json_error(path: Option<&Path>, err: impl fmt::Display) -> MetadataError {
+    MetadataError::JsonParseError(located(path, err))
+}
+
+/// TOML errors name the manifest, or tell what is wrong when it has no path
+fn parse_toml(contents: &str, path: Option<&Path>) -> Result<toml::Value, MetadataError> {
+    toml::from_str(contents).map_err(|err: toml::de::Error| {
+        MetadataError::TomlParseError(match path {
+            Some(path) => path.display().to_string(),
+            None => err.message().to_string(),
+        })
+    })
+}
+
+fn parse_yaml(contents: &str, path: Option<&Path>) -> Result<serde_json::Value, MetadataError> {
+    yaml::parse(contents)
+        .map_err(|err| MetadataError::ParseError("YAML".to_string(), located(path, err)))
+}
+
+/// Directory of the manifest, relative paths in it being relative to that directory
+fn parent(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new(""))
+}
@@ -0,0 +1,39 @@
+//! Syntax errors of the manifest readers, with their position in the file read.
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SyntaxError {
+    /// 1-based line of the error, when known
+    pub(crate) line: Option<usize>,
+    /// 1-based column of the error, when the reader tracks it
+    pub(crate) column: Option<usize>,
+    pub(crate) message: String,
+}
+
+impl SyntaxError {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> Self {
+        SyntaxError {
+            line: Some(line),
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        SyntaxError {
+            line: Some(line),
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{line}:{column}: {}", self.message),
+            (Some(line), None) => write!(f, "{line}: {}", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Helpers shared by the readers of several formats.
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// `path` with symbolic links and `..` resolved, or as is when it does not exist
+pub(crate) fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `path` as seen from `dir`, assuming it is in `dir` or one of its ancestors
+pub(crate) fn relative_to(path: &Path, dir: &Path) -> String {
+    let file = path.file_name().unwrap_or_default().to_string_lossy();
+    let parent = canonical(path.parent().unwrap_or(Path::new("")));
+    let depth = canonical(dir)
+        .strip_prefix(&parent)
+        .map(|relative| relative.components().count())
+        .unwrap_or(0);
+    format!("{}{file}", "../".repeat(depth))
+}
+
+/// `err` prefixed with the path of the manifest when it has one, as `path:line: message` when
+/// `err` starts with its position and `path: message` otherwise
+pub(crate) fn located(path: Option<&Path>, err: impl fmt::Display) -> String {
+    let err = err.to_string();
+    match path {
+        Some(path) if err.starts_with(|c: char| c.is_ascii_digit()) => {
+            format!("{}:{err}", path.display())
+        }
+        Some(path) => format!("{}: {err}", path.display()),
+        None => err,
+    }
+}
+
+/// Strings of a TOML array, non-string items being empty
+pub(crate) fn string_array(value: &toml::Value) -> Option<Vec<String>> {
+    value.as_array().map(|arr| {
+        arr.iter()
+            .map(|v| v.as_str().unwrap_or("").to_string())
+            .collect()
+    })
+}
+
+/// SPDX expression of the licenses a manifest lists, which are alternatives the user picks from
+/// rather than terms that all apply
+pub(crate) fn any_license<S: AsRef<str>>(licenses: &[S]) -> Option<String> {
+    let licenses: Vec<&str> = licenses.iter().map(|license| license.as_ref()).collect();
+    (!licenses.is_empty()).then(|| licenses.join(" OR "))
+}
+
+/// A JSON `license` string, or the array of licenses [`any_license`] joins
+pub(crate) fn json_license(license: &serde_json::Value) -> Option<String> {
+    match license {
+        serde_json::Value::String(license) => Some(license.clone()),
+        serde_json::Value::Array(licenses) => any_license(
+            &licenses
+                .iter()
+                .filter_map(|license| license.as_str())
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    }
+}
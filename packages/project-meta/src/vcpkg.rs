@@ -0,0 +1,132 @@
+//! `vcpkg.json` reader for C and C++ projects using vcpkg manifest mode.
+use crate::dependency::{DependencyKind, DependencySpec, VersionSyntax};
+use crate::npm::SpecifierError;
+use crate::{MetadataError, Person, ProjectMetadata};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Fields holding the version, one per versioning scheme
+const VERSION_FIELDS: [&str; 4] = [
+    "version",
+    "version-semver",
+    "version-date",
+    "version-string",
+];
+
+pub(crate) fn from_json(json: &Value) -> Result<ProjectMetadata, MetadataError> {
+    let mut dependencies = Vec::new();
+    for dependency in json["dependencies"].as_array().into_iter().flatten() {
+        dependencies.push(port(dependency, DependencyKind::Runtime)?);
+    }
+    // Features are opt-in parts of the port, pulling in their own dependencies
+    for (feature, spec) in json["features"].as_object().into_iter().flatten() {
+        for dependency in spec["dependencies"].as_array().into_iter().flatten() {
+            dependencies.push(port(dependency, DependencyKind::Optional)?.group(Some(feature)));
+        }
+    }
+    // Overrides pin the exact version of a port, wherever it is in the graph
+    for dependency in json["overrides"].as_array().into_iter().flatten() {
+        let name = dependency["name"].as_str().unwrap_or("");
+        let version = VERSION_FIELDS
+            .iter()
+            .find_map(|field| dependency[*field].as_str())
+            .map(|version| port_version(version, &dependency["port-version"]));
+        dependencies.push(
+            DependencySpec::new(name, DependencyKind::Override)
+                .version(version.as_deref(), VersionSyntax::Exact),
+        );
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let mut properties = HashMap::new();
+    for key in ["port-version", "builtin-baseline", "supports"] {
+        match &json[key] {
+            Value::String(value) => properties.insert(key.to_string(), value.clone()),
+            Value::Number(value) => properties.insert(key.to_string(), value.to_string()),
+            _ => None,
+        };
+    }
+
+    Ok(ProjectMetadata {
+        name: json["name"].as_str().unwrap_or("").to_string(),
+        version: VERSION_FIELDS
+            .iter()
+            .find_map(|field| json[*field].as_str())
+            .unwrap_or("")
+            .to_string(),
+        // The first line is the summary, the others the details
+        description: match &json["description"] {
+            Value::Array(lines) => Some(
+                lines
+                    .iter()
+                    .filter_map(|line| line.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            description => description.as_str().map(|s| s.to_string()),
+        },
+        maintainers: match &json["maintainers"] {
+            Value::Array(maintainers) => Some(
+                maintainers
+                    .iter()
+                    .filter_map(|maintainer| maintainer.as_str())
+                    .map(Person::parse)
+                    .collect(),
+            ),
+            Value::String(maintainer) => Some(vec![Person::parse(maintainer)]),
+            _ => None,
+        },
+        license: json["license"].as_str().map(|s| s.to_string()),
+        urls: json["homepage"]
+            .as_str()
+            .map(|homepage| HashMap::from([("homepage".to_string(), homepage.to_string())])),
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    })
+}
+
+/// Read a dependency: the name of a port, or an object also selecting its features, the
+/// platforms it is needed on and a minimum version
+fn port(dependency: &Value, kind: DependencyKind) -> Result<DependencySpec, MetadataError> {
+    let spec = match dependency {
+        Value::String(name) => return Ok(DependencySpec::new(name, kind)),
+        Value::Object(_) => dependency,
+        dependency => {
+            return Err(MetadataError::DependencySpecifierError(
+                String::new(),
+                SpecifierError::NotAString(dependency.to_string()),
+            ))
+        }
+    };
+    // Host dependencies are tools run during the build, built for the host triplet
+    let kind = match spec["host"].as_bool() {
+        Some(true) => DependencyKind::Build,
+        _ => kind,
+    };
+    let minimum = spec["version>="]
+        .as_str()
+        .map(|version| format!(">={version}"));
+    let features = spec["features"].as_array().map(|features| {
+        features
+            .iter()
+            .filter_map(|feature| feature.as_str().or(feature["name"].as_str()))
+            .map(|feature| feature.to_string())
+            .collect()
+    });
+    Ok(
+        DependencySpec::new(spec["name"].as_str().unwrap_or(""), kind)
+            .version(minimum.as_deref(), VersionSyntax::Exact)
+            .features(features)
+            .default_features(spec["default-features"].as_bool())
+            .target(spec["platform"].as_str()),
+    )
+}
+
+/// vcpkg writes a port version after the version, as in `1.2.3#2`
+fn port_version(version: &str, port_version: &Value) -> String {
+    match port_version.as_u64() {
+        Some(port_version) if port_version > 0 => format!("{version}#{port_version}"),
+        _ => version.to_string(),
+    }
+}
@@ -0,0 +1,29 @@
+//! XML manifests, parsed with `roxmltree`, and the lookups Maven and MSBuild files share.
+use crate::syntax::SyntaxError;
+use roxmltree::{Document, Node};
+
+pub(crate) fn parse(contents: &str) -> Result<Document<'_>, SyntaxError> {
+    Document::parse(contents).map_err(|err| {
+        let message = err.to_string();
+        // roxmltree ends its messages with ` at L:C`, shown from `line` and `column` instead
+        let message = match message.rsplit_once(" at ") {
+            Some((message, _)) => message.to_string(),
+            None => message,
+        };
+        let pos = err.pos();
+        SyntaxError::at(pos.row as usize, pos.col as usize, message)
+    })
+}
+
+/// First child element named `name`, whatever its namespace
+pub(crate) fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+/// Trimmed text of the `name` child, `None` when missing or empty
+pub(crate) fn text(node: Node, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
@@ -0,0 +1,42 @@
+//! YAML manifests, read into a `serde_json::Value` so that formats index them like JSON ones.
+use crate::syntax::SyntaxError;
+
+/// Parse `contents`; an empty document is `null`, and mapping keys must be strings
+pub(crate) fn parse(contents: &str) -> Result<serde_json::Value, SyntaxError> {
+    serde_yaml::from_str(contents).map_err(|err| {
+        let location = err.location();
+        let message = err.to_string();
+        // serde_yaml only appends ` at line L column C` when it knows the location
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) if location.is_some() => message.to_string(),
+            _ => message,
+        };
+        SyntaxError {
+            line: location.as_ref().map(|location| location.line()),
+            column: location.as_ref().map(|location| location.column()),
+            message,
+        }
+    })
+}
+
+/// Top-level `key` as a string, also when it is written unquoted and YAML reads it as a number,
+/// such as `version: 1.10`. The number is taken as written in `contents`, its parsed value
+/// having lost the trailing zero.
+pub(crate) fn top_level_string(
+    yaml: &serde_json::Value,
+    contents: &str,
+    key: &str,
+) -> Option<String> {
+    match &yaml[key] {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Number(number) => {
+            let written = contents.lines().find_map(|line| {
+                let value = line.strip_prefix(key)?.trim_start().strip_prefix(':')?;
+                let value = value.split(" #").next().unwrap_or(value).trim();
+                (!value.is_empty()).then(|| value.to_string())
+            });
+            Some(written.unwrap_or_else(|| number.to_string()))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,155 @@
+//! Zig `build.zig.zon`: the package, the paths it publishes, and its dependencies, fetched from
+//! a `url` and checked against their `hash`, or found at a local `path`.
+//!
+//! Dependencies have no version besides the one in their url, so their hashes are kept in
+//! `properties` as `dependencies.<name>.hash`.
+use crate::dependency::{DependencyKind, DependencySource, DependencySpec};
+use crate::syntax::SyntaxError;
+use crate::zon::{Node, Value};
+use crate::ProjectMetadata;
+use std::collections::HashMap;
+
+pub(crate) fn from_zon(zon: &Node) -> Result<ProjectMetadata, SyntaxError> {
+    let error = |node: &Node, message: String| SyntaxError::new(node.line, message);
+    if !matches!(zon.value, Value::Struct(_)) {
+        return Err(error(
+            zon,
+            "expected a struct literal `.{ ... }`".to_string(),
+        ));
+    }
+    let string = |field: &str| -> Result<Option<String>, SyntaxError> {
+        match zon.get(field) {
+            Some(node) => match node.as_str() {
+                Some(value) => Ok(Some(value.to_string())),
+                None => Err(error(node, format!("expected a string for `.{field}`"))),
+            },
+            None => Ok(None),
+        }
+    };
+
+    // Names are enum literals since Zig 0.14, strings before
+    let name = match zon.get("name") {
+        Some(Node {
+            value: Value::Enum(name) | Value::Str(name),
+            ..
+        }) => name.clone(),
+        Some(node) => {
+            return Err(error(
+                node,
+                "expected an enum literal for `.name`".to_string(),
+            ))
+        }
+        None => String::new(),
+    };
+
+    let mut properties = HashMap::new();
+    if let Some(Node {
+        value: Value::Number(fingerprint),
+        ..
+    }) = zon.get("fingerprint")
+    {
+        properties.insert("fingerprint".to_string(), fingerprint.clone());
+    }
+
+    let mut dependencies = Vec::new();
+    match zon.get("dependencies").map(|node| (node, &node.value)) {
+        Some((_, Value::Struct(entries))) => {
+            for (name, entry) in entries {
+                let field = |key: &str| entry.get(key).and_then(|node| node.as_str());
+                if !matches!(entry.value, Value::Struct(_)) {
+                    return Err(error(
+                        entry,
+                        format!("expected a struct for dependency `{name}`"),
+                    ));
+                }
+                let source = match (field("url"), field("path")) {
+                    (Some(url), None) => url_source(url),
+                    (None, Some(path)) => DependencySource::Path {
+                        path: path.to_string(),
+                    },
+                    (Some(_), Some(_)) => {
+                        let message = format!("dependency `{name}` has both a `url` and a `path`");
+                        return Err(error(entry, message));
+                    }
+                    (None, None) => {
+                        let message = format!("dependency `{name}` needs a `url` or a `path`");
+                        return Err(error(entry, message));
+                    }
+                };
+                if let Some(hash) = field("hash") {
+                    properties.insert(format!("dependencies.{name}.hash"), hash.to_string());
+                }
+                // Lazy dependencies are only fetched when the build script uses them
+                let lazy = matches!(
+                    entry.get("lazy"),
+                    Some(Node {
+                        value: Value::Bool(true),
+                        ..
+                    })
+                );
+                let dependency = match lazy {
+                    true => DependencySpec::new(name, DependencyKind::Optional).group(Some("lazy")),
+                    false => DependencySpec::new(name, DependencyKind::Runtime),
+                };
+                dependencies.push(dependency.source(source));
+            }
+        }
+        Some((_, Value::Tuple(items))) if items.is_empty() => {}
+        Some((node, _)) => {
+            let message = "expected a struct for `.dependencies`".to_string();
+            return Err(error(node, message));
+        }
+        None => {}
+    }
+    DependencySpec::sort(&mut dependencies);
+
+    let include = match zon.get("paths") {
+        Some(Node {
+            value: Value::Tuple(paths),
+            ..
+        }) => Some(
+            paths
+                .iter()
+                .map(|path| match path.as_str() {
+                    Some(path) => Ok(path.to_string()),
+                    None => Err(error(path, "expected a string in `.paths`".to_string())),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Some(node) => return Err(error(node, "expected a tuple for `.paths`".to_string())),
+        None => None,
+    };
+    let engines =
+        string("minimum_zig_version")?.map(|version| HashMap::from([("zig".to_string(), version)]));
+
+    Ok(ProjectMetadata {
+        name,
+        version: string("version")?.unwrap_or_default(),
+        include,
+        engines,
+        dependencies: (!dependencies.is_empty()).then_some(dependencies),
+        properties: (!properties.is_empty()).then_some(properties),
+        ..Default::default()
+    })
+}
+
+/// `git+https://host/repo#commit` fetches a commit of a repository, other urls an archive
+fn url_source(url: &str) -> DependencySource {
+    match url.strip_prefix("git+") {
+        Some(repository) => {
+            let (url, rev) = match repository.split_once('#') {
+                Some((url, rev)) => (url, Some(rev.to_string())),
+                None => (repository, None),
+            };
+            DependencySource::Git {
+                url: url.to_string(),
+                branch: None,
+                tag: None,
+                rev,
+            }
+        }
+        None => DependencySource::Url {
+            url: url.to_string(),
+        },
+    }
+}
@@ -0,0 +1,313 @@
+//! Zig Object Notation, the anonymous struct and tuple literals `build.zig.zon` is written in:
+//! `.{ .name = .foo, .paths = .{ "src" } }`.
+use crate::syntax::SyntaxError;
+
+/// A value, with the line it starts on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Node {
+    pub(crate) value: Value,
+    pub(crate) line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    /// `.{ .field = value }`, the empty `.{}` included
+    Struct(Vec<(String, Node)>),
+    /// `.{ value, value }`
+    Tuple(Vec<Node>),
+    Str(String),
+    /// Number as written, `0x` prefix and `_` separators included
+    Number(String),
+    /// Enum literal `.name`
+    Enum(String),
+    Bool(bool),
+    Null,
+}
+
+impl Node {
+    pub(crate) fn get(&self, field: &str) -> Option<&Node> {
+        match &self.value {
+            Value::Struct(fields) => fields
+                .iter()
+                .find(|(name, _)| name == field)
+                .map(|(_, node)| node),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn parse(contents: &str) -> Result<Node, SyntaxError> {
+    let mut parser = Parser {
+        chars: contents.chars().collect(),
+        index: 0,
+        line: 1,
+    };
+    let node = parser.value()?;
+    parser.skip_trivia();
+    if parser.index < parser.chars.len() {
+        return Err(parser.error("expected the end of the file"));
+    }
+    Ok(node)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> SyntaxError {
+        SyntaxError::new(self.line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    /// Skip whitespace and `//` comments
+    fn skip_trivia(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.bump();
+            } else if c == '/' && self.chars.get(self.index + 1) == Some(&'/') {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.bump();
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_trivia();
+        if self.peek() == Some(expected) {
+            self.bump();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SyntaxError> {
+        match self.eat(expected) {
+            true => Ok(()),
+            false => Err(self.error(&format!("expected `{expected}`"))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, SyntaxError> {
+        self.skip_trivia();
+        let line = self.line;
+        let value = match self.peek() {
+            Some('.') => {
+                self.bump();
+                match self.peek() {
+                    Some('{') => {
+                        self.bump();
+                        self.literal(line)?
+                    }
+                    _ => Value::Enum(self.identifier()?),
+                }
+            }
+            Some('"') => Value::Str(self.string()?),
+            Some('\\') => Value::Str(self.multiline_string()?),
+            Some(c) if c.is_ascii_digit() || c == '-' => {
+                let start = self.index;
+                self.bump();
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || "._+-".contains(c))
+                {
+                    self.bump();
+                }
+                Value::Number(self.chars[start..self.index].iter().collect())
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => match self.identifier()?.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                "null" => Value::Null,
+                identifier => {
+                    self.line = line;
+                    return Err(self.error(&format!("unexpected identifier `{identifier}`")));
+                }
+            },
+            Some(c) => return Err(self.error(&format!("unexpected `{c}`"))),
+            None => return Err(self.error("unexpected end of file")),
+        };
+        Ok(Node { value, line })
+    }
+
+    /// Fields or items of a literal after its `.{`, `line` being the line it starts on
+    fn literal(&mut self, line: usize) -> Result<Value, SyntaxError> {
+        self.skip_trivia();
+        // `.name = value` starts a struct, anything else a tuple
+        let is_struct = self.peek() == Some('.') && {
+            let next = self.chars.get(self.index + 1).copied();
+            next.is_some_and(|c| c.is_alphabetic() || c == '_' || c == '@') && !self.is_enum_item()
+        };
+        let mut fields = Vec::new();
+        let mut items = Vec::new();
+        loop {
+            if self.eat('}') {
+                break;
+            }
+            if self.peek().is_none() {
+                self.line = line;
+                return Err(self.error("unclosed `.{`"));
+            }
+            if is_struct {
+                self.expect('.')?;
+                let name = self.identifier()?;
+                if fields.iter().any(|(field, _)| *field == name) {
+                    return Err(self.error(&format!("duplicate field `.{name}`")));
+                }
+                self.expect('=')?;
+                fields.push((name, self.value()?));
+            } else {
+                items.push(self.value()?);
+            }
+            if !self.eat(',') {
+                self.skip_trivia();
+                if self.peek().is_none() {
+                    self.line = line;
+                    return Err(self.error("unclosed `.{`"));
+                }
+                if !self.eat('}') {
+                    return Err(self.error("expected `,` or `}`"));
+                }
+                break;
+            }
+        }
+        Ok(match is_struct {
+            true => Value::Struct(fields),
+            false => Value::Tuple(items),
+        })
+    }
+
+    /// Whether the `.name` ahead is an enum literal item rather than a field, i.e. is not
+    /// followed by `=`
+    fn is_enum_item(&self) -> bool {
+        let mut index = self.index + 1;
+        if self.chars.get(index) == Some(&'@') {
+            index += 2;
+            while self.chars.get(index).is_some_and(|c| *c != '"') {
+                index += 1;
+            }
+            index += 1;
+        } else {
+            while self
+                .chars
+                .get(index)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+            {
+                index += 1;
+            }
+        }
+        while self.chars.get(index).is_some_and(|c| c.is_whitespace()) {
+            index += 1;
+        }
+        self.chars.get(index) != Some(&'=')
+    }
+
+    /// `name`, or `@"name"` for names that are not identifiers
+    fn identifier(&mut self) -> Result<String, SyntaxError> {
+        if self.peek() == Some('@') {
+            self.bump();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected `\"` after `@`"));
+            }
+            return self.string();
+        }
+        let start = self.index;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.bump();
+        }
+        if start == self.index || self.chars[start].is_ascii_digit() {
+            return Err(self.error("expected an identifier"));
+        }
+        Ok(self.chars[start..self.index].iter().collect())
+    }
+
+    fn string(&mut self) -> Result<String, SyntaxError> {
+        let line = self.line;
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some(c @ ('\\' | '"' | '\'')) => value.push(c),
+                    Some('x') => {
+                        let hex: String = [self.bump(), self.bump()].iter().flatten().collect();
+                        let c = u8::from_str_radix(&hex, 16)
+                            .map_err(|_| self.error("invalid `\\x` escape"))?;
+                        value.push(char::from(c));
+                    }
+                    Some('u') => {
+                        let mut hex = String::new();
+                        if self.bump() != Some('{') {
+                            return Err(self.error("expected `{` after `\\u`"));
+                        }
+                        while let Some(c) = self.bump().filter(|c| *c != '}') {
+                            hex.push(c);
+                        }
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("invalid `\\u` escape"))?;
+                        value.push(c);
+                    }
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                Some('\n') | None => {
+                    self.line = line;
+                    return Err(self.error("unterminated string"));
+                }
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    /// Consecutive `\\` lines, joined by newlines
+    fn multiline_string(&mut self) -> Result<String, SyntaxError> {
+        let mut lines = Vec::new();
+        loop {
+            if self.peek() != Some('\\') || self.chars.get(self.index + 1) != Some(&'\\') {
+                return Err(self.error("expected `\\\\`"));
+            }
+            self.index += 2;
+            let start = self.index;
+            while self.peek().is_some_and(|c| c != '\n') {
+                self.bump();
+            }
+            lines.push(self.chars[start..self.index].iter().collect::<String>());
+            // The string continues on the next line if it starts with `\\`
+            let checkpoint = (self.index, self.line);
+            self.skip_trivia();
+            if self.peek() != Some('\\') {
+                (self.index, self.line) = checkpoint;
+                return Ok(lines.join("\n"));
+            }
+        }
+    }
+}
@@ -0,0 +1,4 @@
+"""Check Python ASTs against templates"""
+import ast
+
+__version__ = '0.4.0'
@@ -0,0 +1,8 @@
+module example.com/invalid
+
+go 1.22
+
+require (
+	golang.org/x/mod v0.22.0
+	github.com/spf13/cobra 1.8.1
+)
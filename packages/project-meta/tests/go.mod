@@ -0,0 +1,31 @@
+// Deprecated: use example.com/confy/v2 instead.
+module example.com/confy
+
+go 1.22.0
+
+toolchain go1.23.4
+
+require github.com/spf13/cobra v1.8.1
+
+require (
+	golang.org/x/mod v0.22.0
+	"gopkg.in/yaml.v3" v3.0.1
+	github.com/inconshreveable/mousetrap v1.1.0 // indirect
+	github.com/spf13/pflag v1.0.5 // indirect; used by cobra
+)
+
+replace github.com/spf13/pflag => ../pflag
+
+replace (
+	golang.org/x/mod v0.22.0 => golang.org/x/mod v0.21.0
+	example.com/legacy => example.com/modern v1.4.0
+)
+
+exclude golang.org/x/mod v0.20.0
+
+retract (
+	v1.0.0 // published with a broken go.sum
+	[v1.1.0, v1.1.3]
+)
+
+godebug default=go1.21
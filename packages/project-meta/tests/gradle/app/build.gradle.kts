@@ -0,0 +1,39 @@
+import org.jetbrains.kotlin.gradle.dsl.JvmTarget
+
+plugins {
+    application
+    alias(libs.plugins.kotlin.jvm)
+    alias(libs.plugins.ktor)
+    id("com.github.ben-manes.versions") version "0.51.0"
+    id("org.jlleitschuh.gradle.ktlint") version "12.1.+"
+}
+
+description = "HTTP front end of the inventory"
+
+val jacksonVersion: String by project
+val exposedVersion = "0.50.+"
+
+dependencies {
+    implementation(platform(libs.ktor.bom))
+    implementation(libs.bundles.ktor.server)
+    implementation(libs.kotlinx.coroutines)
+    implementation(project(":core"))
+    implementation("com.fasterxml.jackson.module:jackson-module-kotlin:$jacksonVersion")
+    implementation("org.jetbrains.exposed:exposed-core:${exposedVersion}")
+    implementation(kotlin("reflect"))
+    runtimeOnly(libs.logback)
+    compileOnly("org.projectlombok:lombok:1.18.32")
+    implementation(files("libs/legacy.jar"))
+    implementation(System.getenv("EXTRA_DEPENDENCY"))
+
+    testImplementation(kotlin("test"))
+    testImplementation(libs.junit.jupiter)
+}
+
+kotlin {
+    jvmToolchain(17)
+}
+
+application {
+    mainClass.set("com.example.inventory.ApplicationKt")
+}
@@ -0,0 +1,5 @@
+# frozen_string_literal: true
+
+module Confy
+  VERSION = "2.4.1"
+end
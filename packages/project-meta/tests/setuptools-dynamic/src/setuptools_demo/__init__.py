@@ -0,0 +1,3 @@
+"""Demo package using setuptools dynamic metadata."""
+
+__version__: str = "3.1.4"
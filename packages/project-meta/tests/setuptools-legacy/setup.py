@@ -0,0 +1,43 @@
+#!/usr/bin/env python
+import os
+
+from setuptools import find_packages, setup
+
+here = os.path.abspath(os.path.dirname(__file__))
+
+with open(os.path.join(here, "README.rst")) as f:
+    long_description = f.read()
+
+setup(
+    name="legacy-pkg",
+    version=os.environ.get("LEGACY_VERSION", "0.0.0"),
+    description="A package still configured with "
+    'a setup.py',
+    long_description=long_description,
+    author="Ada Lovelace",
+    author_email="ada@example.com",
+    url="https://example.com/legacy-pkg",
+    license="BSD-3-Clause",
+    keywords="config metadata legacy",
+    classifiers=[
+        "Programming Language :: Python :: 3",
+        "License :: OSI Approved :: BSD License",  # trailing comma
+    ],
+    packages=find_packages("src"),
+    package_dir={"": "src"},
+    python_requires=">=3.8",
+    install_requires=[
+        "requests>=2.28,<3",
+        'importlib-metadata; python_version < "3.10"',
+    ],
+    extras_require={
+        "socks": ["PySocks>=1.5.6"],
+        "yaml": ["PyYAML"],
+    },
+    tests_require=["pytest>=7"] + (["pytest-xdist"] if os.name != "nt" else []),
+    entry_points={
+        "console_scripts": [
+            "legacy = legacy_pkg.cli:main",
+        ],
+    },
+)
@@ -0,0 +1,3 @@
+"""Legacy package."""
+
+__version__ = "0.9.3"